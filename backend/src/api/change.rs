@@ -0,0 +1,97 @@
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_graphql::{Enum, Object};
+
+use crate::api::device::Device;
+use crate::error::BackendError;
+use crate::topology::cache::{get_topology, topology_changes};
+use crate::topology::model::diff;
+use crate::topology::model::Topology;
+
+/// difference between two consecutive topologies
+pub struct TopologyChange {
+    detected_at: SystemTime,
+    change: diff::TopologyChange,
+    topology: Arc<Topology>,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum ChangedObject {
+    Device,
+    Port,
+    Link,
+    Address,
+}
+
+impl From<diff::ChangeKind> for ChangeKind {
+    fn from(value: diff::ChangeKind) -> Self {
+        match value {
+            diff::ChangeKind::Added => ChangeKind::Added,
+            diff::ChangeKind::Removed => ChangeKind::Removed,
+            diff::ChangeKind::Modified => ChangeKind::Modified,
+        }
+    }
+}
+
+impl From<diff::ChangedObject> for ChangedObject {
+    fn from(value: diff::ChangedObject) -> Self {
+        match value {
+            diff::ChangedObject::Device => ChangedObject::Device,
+            diff::ChangedObject::Port => ChangedObject::Port,
+            diff::ChangedObject::Link => ChangedObject::Link,
+            diff::ChangedObject::Address => ChangedObject::Address,
+        }
+    }
+}
+
+#[Object]
+impl TopologyChange {
+    /// unix timestamp of the refresh the change was detected at
+    async fn timestamp(&self) -> u64 {
+        self.detected_at
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default()
+    }
+    async fn kind(&self) -> ChangeKind {
+        self.change.kind().into()
+    }
+    async fn object_type(&self) -> ChangedObject {
+        self.change.object().into()
+    }
+    /// netbox id of the affected device, empty for links
+    async fn device_id(&self) -> Option<u32> {
+        self.change.device_id()
+    }
+    /// affected device, empty if it was removed
+    async fn device(&self) -> Option<Device> {
+        let device = self.topology.get_device_by_id(self.change.device_id()?)?;
+        Some(Device::new(device, self.topology.clone()))
+    }
+    async fn description(&self) -> &str {
+        self.change.description()
+    }
+}
+
+pub async fn list_topology_changes(
+    since: Option<u64>,
+) -> Result<Vec<TopologyChange>, BackendError> {
+    let topology = get_topology().await?;
+    let since = since.map(|s| UNIX_EPOCH + Duration::from_secs(s));
+    Ok(topology_changes(since)
+        .into_iter()
+        .map(|(detected_at, change)| TopologyChange {
+            detected_at,
+            change,
+            topology: topology.clone(),
+        })
+        .collect())
+}
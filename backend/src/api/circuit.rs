@@ -0,0 +1,174 @@
+use std::sync::Arc;
+
+use async_graphql::{Enum, Object};
+
+use crate::api::device::DevicePort;
+use crate::api::site::Site;
+use crate::error::BackendError;
+use crate::topology::cache::get_topology;
+use crate::topology::model;
+use crate::topology::model::circuit::TerminationSide as ModelTerminationSide;
+use crate::topology::model::Topology;
+
+#[derive(Debug)]
+pub struct Circuit {
+    circuit: Arc<model::circuit::Circuit>,
+    topology: Arc<Topology>,
+}
+
+impl Circuit {
+    pub fn new(circuit: Arc<model::circuit::Circuit>, topology: Arc<Topology>) -> Self {
+        Self { circuit, topology }
+    }
+    pub fn from_idx(idx: usize, topology: Arc<Topology>) -> Option<Self> {
+        topology
+            .get_circuit(idx)
+            .map(|c| Circuit::new(c, topology.clone()))
+    }
+}
+
+#[derive(Debug)]
+pub struct CircuitTermination {
+    termination: model::circuit::CircuitTermination,
+    topology: Arc<Topology>,
+}
+
+#[derive(Debug)]
+pub struct Provider(model::circuit::Provider);
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum CircuitStatus {
+    Active,
+    Decommissioned,
+    Deprovisioning,
+    Offline,
+    Planned,
+    Provisioning,
+    Unknown,
+}
+
+impl From<model::circuit::CircuitStatus> for CircuitStatus {
+    fn from(value: model::circuit::CircuitStatus) -> Self {
+        match value {
+            model::circuit::CircuitStatus::Active => CircuitStatus::Active,
+            model::circuit::CircuitStatus::Decommissioned => CircuitStatus::Decommissioned,
+            model::circuit::CircuitStatus::Deprovisioning => CircuitStatus::Deprovisioning,
+            model::circuit::CircuitStatus::Offline => CircuitStatus::Offline,
+            model::circuit::CircuitStatus::Planned => CircuitStatus::Planned,
+            model::circuit::CircuitStatus::Provisioning => CircuitStatus::Provisioning,
+            model::circuit::CircuitStatus::Unknown => CircuitStatus::Unknown,
+        }
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum TerminationSide {
+    A,
+    Z,
+}
+
+impl From<ModelTerminationSide> for TerminationSide {
+    fn from(value: ModelTerminationSide) -> Self {
+        match value {
+            ModelTerminationSide::A => TerminationSide::A,
+            ModelTerminationSide::Z => TerminationSide::Z,
+        }
+    }
+}
+
+pub async fn list_circuits() -> Result<Vec<Circuit>, BackendError> {
+    let topology = get_topology().await?;
+    Ok(topology.list_circuits_map(|c| Some(Circuit::new(c.clone(), topology.clone()))))
+}
+
+#[Object]
+impl Circuit {
+    /// netbox id of circuit
+    async fn id(&self) -> u32 {
+        self.circuit.id()
+    }
+    /// circuit id assigned by the provider
+    async fn cid(&self) -> &str {
+        self.circuit.cid()
+    }
+    async fn provider(&self) -> Provider {
+        Provider(self.circuit.provider().clone())
+    }
+    async fn circuit_type(&self) -> &str {
+        self.circuit.circuit_type()
+    }
+    async fn status(&self) -> CircuitStatus {
+        self.circuit.status().into()
+    }
+    /// committed rate in kbit/s
+    async fn commit_rate(&self) -> Option<u32> {
+        self.circuit.commit_rate()
+    }
+    async fn description(&self) -> &str {
+        self.circuit.description()
+    }
+    async fn terminations(&self) -> Vec<CircuitTermination> {
+        self.circuit
+            .terminations()
+            .iter()
+            .map(|t| CircuitTermination {
+                termination: t.clone(),
+                topology: self.topology.clone(),
+            })
+            .collect()
+    }
+}
+
+#[Object]
+impl CircuitTermination {
+    /// netbox id of circuit termination
+    async fn id(&self) -> u32 {
+        self.termination.id()
+    }
+    async fn side(&self) -> TerminationSide {
+        self.termination.side().into()
+    }
+    async fn site(&self) -> Option<Site> {
+        self.termination
+            .site()
+            .and_then(|sid| self.topology.get_site(sid))
+            .map(|s| Site::new(s.clone(), self.topology.clone()))
+    }
+    /// device port the termination is cabled to, may be a patch panel
+    async fn port(&self) -> Option<DevicePort> {
+        DevicePort::from_idx(self.termination.port()?, self.topology.clone())
+    }
+    /// router interface the circuit ends on
+    async fn interface(&self) -> Option<DevicePort> {
+        DevicePort::from_idx(
+            self.topology.termination_interface(&self.termination)?,
+            self.topology.clone(),
+        )
+    }
+    /// port speed in kbit/s
+    async fn port_speed(&self) -> Option<u32> {
+        self.termination.port_speed()
+    }
+    /// upstream speed in kbit/s, if different from the port speed
+    async fn upstream_speed(&self) -> Option<u32> {
+        self.termination.upstream_speed()
+    }
+    async fn xconnect_id(&self) -> &str {
+        self.termination.xconnect_id()
+    }
+}
+
+#[Object]
+impl Provider {
+    /// netbox id of provider
+    async fn id(&self) -> u32 {
+        self.0.id()
+    }
+    async fn name(&self) -> &str {
+        self.0.name()
+    }
+    /// customer account number at the provider
+    async fn account(&self) -> &str {
+        self.0.account()
+    }
+}
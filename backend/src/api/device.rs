@@ -6,11 +6,29 @@ use async_graphql::{Enum, Object};
 use ipnet::IpNet;
 use log::warn;
 
+use crate::api::circuit::Circuit;
 use crate::api::device_type::DeviceType;
+use crate::api::health::DeviceHealth;
+use crate::api::interface::{InterfaceStatus, InterfaceStatusReport, InterfaceTraffic};
+use crate::api::link::Link;
 use crate::api::location::Location;
+use crate::api::module::Module;
+use crate::api::monitoring::MonitoringSettings;
+use crate::api::path::CablePath;
+use crate::api::power::PowerSource;
+use crate::api::tenant::Tenant;
+use crate::api::virtual_chassis::VirtualChassis;
+use crate::api::vlan::{InterfaceMode, Vlan};
+use crate::api::wireless::WirelessClient;
+use crate::routeros::health::device_health;
+use crate::routeros::interface::interface_status;
+use crate::routeros::traffic::interface_traffic;
+use crate::routeros::wireless::{
+    clients_of_access_point, is_access_point, is_wireless_controller, wireless_clients,
+};
 use crate::topology::model;
-use crate::topology::model::Topology;
-use crate::{error::BackendError, topology::query::get_topology};
+use crate::topology::model::{PortIdx, Topology};
+use crate::{error::BackendError, topology::cache::get_topology};
 
 #[derive(Debug)]
 pub struct Device {
@@ -19,13 +37,17 @@ pub struct Device {
 }
 
 #[derive(Debug)]
-pub struct DevicePort(Arc<model::DevicePort>);
+pub struct DevicePort {
+    port: Arc<model::DevicePort>,
+    port_idx: PortIdx,
+    topology: Arc<Topology>,
+}
 
 #[derive(Debug)]
-pub struct IpNetApi(IpNet);
+pub struct InterfaceAddressApi(model::device::InterfaceAddress);
 
-impl IpNetApi {
-    fn new(value: IpNet) -> Self {
+impl InterfaceAddressApi {
+    fn new(value: model::device::InterfaceAddress) -> Self {
         Self(value)
     }
 }
@@ -36,9 +58,18 @@ impl Device {
     }
 }
 
-impl From<Arc<model::DevicePort>> for DevicePort {
-    fn from(value: Arc<model::DevicePort>) -> Self {
-        DevicePort(value)
+impl DevicePort {
+    pub fn new(port: Arc<model::DevicePort>, port_idx: PortIdx, topology: Arc<Topology>) -> Self {
+        DevicePort {
+            port,
+            port_idx,
+            topology,
+        }
+    }
+    pub fn from_idx(port_idx: PortIdx, topology: Arc<Topology>) -> Option<Self> {
+        topology
+            .get_port(&port_idx)
+            .map(|port| DevicePort::new(port, port_idx, topology))
     }
 }
 
@@ -58,10 +89,79 @@ impl Device {
     async fn name(&self) -> &str {
         self.device.name()
     }
+    /// category derived from the netbox device role
+    async fn category(&self) -> DeviceCategory {
+        self.device.category().into()
+    }
+    /// true if the device is expected to answer pings
+    async fn can_ping(&self) -> bool {
+        self.device.can_ping()
+    }
+    /// monitoring settings maintained in netbox
+    async fn monitoring(&self) -> MonitoringSettings {
+        MonitoringSettings::new(&self.device)
+    }
+    /// tenant the device is assigned to, inherited from its location or site if not set
+    async fn tenant(&self) -> Option<Tenant> {
+        self.topology
+            .get_tenant(self.topology.tenant_of_device(&self.device)?)
+            .map(|t| Tenant::from(t.as_ref()))
+    }
+    /// stack this device is a member of
+    async fn virtual_chassis(&self) -> Option<VirtualChassis> {
+        VirtualChassis::from_idx(
+            self.device.virtual_chassis()?.chassis(),
+            self.topology.clone(),
+        )
+    }
+    /// position within the virtual chassis
+    async fn vc_position(&self) -> Option<u32> {
+        self.device.virtual_chassis()?.position()
+    }
+    /// priority within the master election of the virtual chassis
+    async fn vc_priority(&self) -> Option<u32> {
+        self.device.virtual_chassis()?.priority()
+    }
+    /// modules installed in the module bays
+    async fn modules(&self) -> Vec<Module> {
+        self.device.modules().iter().map(Module::from).collect()
+    }
+    /// resources and sensors of RouterOS devices, refreshed after a few seconds
+    async fn health(&self) -> Result<Option<DeviceHealth>, BackendError> {
+        if !self.device.has_routeros() {
+            return Ok(None);
+        }
+        let report = device_health(&self.device).await?;
+        Ok(Some(DeviceHealth::from(report.as_ref())))
+    }
+    /// live state of all interfaces of RouterOS devices, fetched once for all ports
+    async fn interface_status(&self) -> Result<Option<InterfaceStatusReport>, BackendError> {
+        if !self.device.has_routeros() {
+            return Ok(None);
+        }
+        let report = interface_status(&self.device).await?;
+        Ok(Some(InterfaceStatusReport::from(report.as_ref())))
+    }
+    /// clients currently registered at the access point, including those its CAPsMAN controller
+    /// lists for it, all clients of the managed access points for controllers
+    async fn wireless_clients(&self) -> Result<Vec<WirelessClient>, BackendError> {
+        if is_access_point(&self.device) {
+            let clients = clients_of_access_point(&self.topology, &self.device).await?;
+            Ok(clients.iter().map(WirelessClient::from).collect())
+        } else if is_wireless_controller(&self.device) {
+            let clients = wireless_clients(&self.device).await?;
+            Ok(clients.iter().map(WirelessClient::from).collect())
+        } else {
+            Ok(vec![])
+        }
+    }
     async fn ping(&self) -> Result<PingResult, BackendError> {
+        if !self.device.can_ping() {
+            return Err(BackendError::NotPingable());
+        }
         let ip_addr: IpAddr = self
             .device
-            .get_loopback_address()
+            .ping_target()
             .ok_or(BackendError::MissingIpAddress())?;
 
         //info!("Send ping to {ip_addr}");
@@ -93,11 +193,43 @@ impl Device {
             .map(|tid| DeviceType::new(tid, self.topology.clone()))
     }
 
+    /// all power sources the device depends on, from the directly connected outlets up to the feeds
+    async fn powered_by(&self) -> Vec<PowerSource> {
+        self.topology
+            .get_device_idx(self.device.id())
+            .map(|idx| self.topology.powered_by(idx))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|source| PowerSource::new(source, self.topology.clone()))
+            .collect()
+    }
+    /// devices drawing power from this device, directly or through further pdus
+    async fn powers(&self) -> Vec<Device> {
+        self.topology
+            .get_device_idx(self.device.id())
+            .map(|idx| self.topology.powers(idx))
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|idx| self.topology.get_device(idx))
+            .map(|d| Device::new(d, self.topology.clone()))
+            .collect()
+    }
+
     async fn ports(&self) -> Vec<DevicePort> {
+        let device_idx = self.topology.get_device_idx(self.device.id());
         self.device
             .ports()
             .into_iter()
-            .map(DevicePort::from)
+            .enumerate()
+            .flat_map(|(port_idx, port)| {
+                device_idx.map(|device_idx| {
+                    DevicePort::new(
+                        port,
+                        PortIdx::new(device_idx, port_idx),
+                        self.topology.clone(),
+                    )
+                })
+            })
             .collect()
     }
 }
@@ -105,20 +237,106 @@ impl Device {
 #[Object]
 impl DevicePort {
     async fn name(&self) -> &str {
-        self.0.get_name()
+        self.port.get_name()
     }
-    async fn address(&self, address_type: Option<IpFamily>) -> Vec<IpNetApi> {
-        self.0
-            .list_nets()
-            .into_iter()
-            .map(IpNetApi::new)
-            .filter(|net| {
+    /// device owning this port
+    async fn device(&self) -> Option<Device> {
+        self.topology
+            .get_device(self.port_idx.device_idx())
+            .map(|d| Device::new(d, self.topology.clone()))
+    }
+    /// all addresses of this port, optionally filtered by family and vrf
+    async fn address(
+        &self,
+        address_type: Option<IpFamily>,
+        vrf: Option<String>,
+    ) -> Vec<InterfaceAddressApi> {
+        self.port
+            .addresses()
+            .iter()
+            .cloned()
+            .map(InterfaceAddressApi::new)
+            .filter(|address| {
                 address_type
-                    .map(|t| net.inner_family() == t)
+                    .map(|t| address.inner_family() == t)
+                    .unwrap_or(true)
+            })
+            .filter(|address| {
+                vrf.as_ref()
+                    .map(|vrf| address.0.vrf() == Some(vrf.as_str()))
                     .unwrap_or(true)
             })
             .collect()
     }
+    /// 802.1q mode of the interface
+    async fn mode(&self) -> Option<InterfaceMode> {
+        self.port.vlans()?.mode().map(InterfaceMode::from)
+    }
+    /// vlan transported without tag
+    async fn untagged_vlan(&self) -> Option<Vlan> {
+        let id = self.port.vlans()?.untagged()?;
+        Vlan::from_id(id, self.topology.clone())
+    }
+    /// vlans transported tagged
+    async fn tagged_vlans(&self) -> Vec<Vlan> {
+        self.port
+            .vlans()
+            .map(|vlans| vlans.tagged().as_slice())
+            .unwrap_or_default()
+            .iter()
+            .flat_map(|id| Vlan::from_id(*id, self.topology.clone()))
+            .collect()
+    }
+    /// live state read from the device, only available for interfaces of RouterOS devices,
+    /// the error of a failed fetch is reported by `interfaceStatus` of the device
+    async fn status(&self) -> Result<Option<InterfaceStatus>, BackendError> {
+        let model::DevicePort::Interface { name, .. } = self.port.as_ref() else {
+            return Ok(None);
+        };
+        let Some(device) = self.topology.get_device(self.port_idx.device_idx()) else {
+            return Ok(None);
+        };
+        if !device.has_routeros() {
+            return Ok(None);
+        }
+        let report = interface_status(&device).await?;
+        Ok(report.get(name).map(InterfaceStatus::from))
+    }
+    /// traffic counters and throughput from the last polls of the device
+    async fn traffic(&self) -> Option<InterfaceTraffic> {
+        let model::DevicePort::Interface { name, .. } = self.port.as_ref() else {
+            return None;
+        };
+        let device = self.topology.get_device(self.port_idx.device_idx())?;
+        interface_traffic(device.id(), name)
+            .as_ref()
+            .map(InterfaceTraffic::from)
+    }
+    /// cable or radio links connected to this port, several for breakout cables
+    async fn links(&self) -> Vec<Link> {
+        self.topology
+            .get_links_of_port(&self.port_idx)
+            .into_iter()
+            .map(|l| Link::new(l, self.topology.clone()))
+            .collect()
+    }
+    /// provider circuits terminated on this interface
+    async fn circuits(&self) -> Vec<Circuit> {
+        self.topology
+            .circuits_of_port(self.port_idx)
+            .into_iter()
+            .flat_map(|idx| Circuit::from_idx(idx, self.topology.clone()))
+            .collect()
+    }
+    /// follow the cabling through patch panels to the interfaces on the far end,
+    /// one path per branch of breakout cables
+    async fn traces(&self) -> Vec<CablePath> {
+        self.topology
+            .trace_paths(self.port_idx)
+            .into_iter()
+            .map(|path| CablePath::new(path, self.topology.clone()))
+            .collect()
+    }
 }
 
 pub async fn get_device(id: u32) -> Result<Option<Device>, BackendError> {
@@ -128,10 +346,14 @@ pub async fn get_device(id: u32) -> Result<Option<Device>, BackendError> {
         .map(|d| Device::new(d, topology)))
 }
 
-pub async fn list_devices() -> Result<Vec<Device>, BackendError> {
+/// monitored devices, devices without an address to ping are left out as their ping would fail
+pub async fn list_devices(tenant: Option<String>) -> Result<Vec<Device>, BackendError> {
     let topology = get_topology().await?;
     let results = topology.list_devices_map(|d| {
-        if d.has_routeros() {
+        if d.can_ping()
+            && d.ping_target().is_some()
+            && topology.tenant_matches(topology.tenant_of_device(d), tenant.as_deref())
+        {
             Some(Device::new(d.clone(), topology.clone()))
         } else {
             None
@@ -140,9 +362,9 @@ pub async fn list_devices() -> Result<Vec<Device>, BackendError> {
     Ok(results)
 }
 
-impl IpNetApi {
+impl InterfaceAddressApi {
     fn inner_family(&self) -> IpFamily {
-        match self.0 {
+        match self.0.net() {
             IpNet::V4(_) => IpFamily::V4,
             IpNet::V6(_) => IpFamily::V6,
         }
@@ -150,15 +372,23 @@ impl IpNetApi {
 }
 
 #[Object]
-impl IpNetApi {
+impl InterfaceAddressApi {
     /// format the whole network as string
     async fn as_string(&self) -> String {
-        self.0.to_string()
+        self.0.net().to_string()
     }
     /// determine ip address family
     async fn family(&self) -> IpFamily {
         self.inner_family()
     }
+    /// functional role of the address
+    async fn role(&self) -> AddressRole {
+        self.0.role().into()
+    }
+    /// name of the vrf, empty for the global table
+    async fn vrf(&self) -> Option<&str> {
+        self.0.vrf()
+    }
 }
 #[derive(Enum, Copy, Clone, Eq, PartialEq)]
 enum IpFamily {
@@ -166,6 +396,33 @@ enum IpFamily {
     V6,
 }
 
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum DeviceCategory {
+    Switch,
+    Router,
+    UserDevice,
+    PatchPanel,
+    Server,
+    WallConnector,
+    AccessPoint,
+    Unknown,
+}
+
+impl From<model::device::DeviceCategory> for DeviceCategory {
+    fn from(value: model::device::DeviceCategory) -> Self {
+        match value {
+            model::device::DeviceCategory::Switch => DeviceCategory::Switch,
+            model::device::DeviceCategory::Router => DeviceCategory::Router,
+            model::device::DeviceCategory::UserDevice => DeviceCategory::UserDevice,
+            model::device::DeviceCategory::PatchPanel => DeviceCategory::PatchPanel,
+            model::device::DeviceCategory::Server => DeviceCategory::Server,
+            model::device::DeviceCategory::WallConnector => DeviceCategory::WallConnector,
+            model::device::DeviceCategory::AccessPoint => DeviceCategory::AccessPoint,
+            model::device::DeviceCategory::Unknown => DeviceCategory::Unknown,
+        }
+    }
+}
+
 #[Object]
 impl PingAnswer {
     async fn duration_in_ms(&self) -> Result<u64, TryFromIntError> {
@@ -178,3 +435,32 @@ impl PingResult {
         &self.answer
     }
 }
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+enum AddressRole {
+    Primary,
+    Secondary,
+    Loopback,
+    Anycast,
+    Vip,
+    Vrrp,
+    Hsrp,
+    Glbp,
+    Carp,
+}
+
+impl From<model::device::AddressRole> for AddressRole {
+    fn from(value: model::device::AddressRole) -> Self {
+        match value {
+            model::device::AddressRole::Primary => AddressRole::Primary,
+            model::device::AddressRole::Secondary => AddressRole::Secondary,
+            model::device::AddressRole::Loopback => AddressRole::Loopback,
+            model::device::AddressRole::Anycast => AddressRole::Anycast,
+            model::device::AddressRole::Vip => AddressRole::Vip,
+            model::device::AddressRole::Vrrp => AddressRole::Vrrp,
+            model::device::AddressRole::Hsrp => AddressRole::Hsrp,
+            model::device::AddressRole::Glbp => AddressRole::Glbp,
+            model::device::AddressRole::Carp => AddressRole::Carp,
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use std::time::UNIX_EPOCH;
+
+use async_graphql::SimpleObject;
+
+use crate::routeros::health::HealthReport;
+
+/// resources and sensors read from the RouterOS api
+#[derive(SimpleObject)]
+pub struct DeviceHealth {
+    /// unix timestamp of the last fetch
+    updated_at: u64,
+    /// error of the last fetch, all values are missing in this case
+    error: Option<String>,
+    /// cpu usage in percent
+    cpu_load: Option<u32>,
+    /// free memory in bytes
+    free_memory: Option<u64>,
+    total_memory: Option<u64>,
+    /// free storage in bytes
+    free_disk: Option<u64>,
+    total_disk: Option<u64>,
+    uptime_seconds: Option<u64>,
+    board_name: Option<String>,
+    /// RouterOS version including the release channel
+    version: Option<String>,
+    /// supply voltage in V
+    voltage: Option<f64>,
+    /// temperature in °C
+    temperature: Option<f64>,
+}
+
+impl From<&HealthReport> for DeviceHealth {
+    fn from(value: &HealthReport) -> Self {
+        let health = value.health();
+        Self {
+            updated_at: value
+                .fetched_at()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            error: value.error().map(|e| e.to_string()),
+            cpu_load: health.and_then(|h| h.cpu_load()),
+            free_memory: health.and_then(|h| h.free_memory()),
+            total_memory: health.and_then(|h| h.total_memory()),
+            free_disk: health.and_then(|h| h.free_disk()),
+            total_disk: health.and_then(|h| h.total_disk()),
+            uptime_seconds: health.and_then(|h| h.uptime()).map(|u| u.as_secs()),
+            board_name: health.and_then(|h| h.board_name()).map(str::to_string),
+            version: health.and_then(|h| h.version()).map(str::to_string),
+            voltage: health.and_then(|h| h.voltage()),
+            temperature: health.and_then(|h| h.temperature()),
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use std::time::UNIX_EPOCH;
+
+use async_graphql::{Enum, SimpleObject};
+
+use crate::routeros::{interface, traffic};
+
+/// live state of an interface read from the RouterOS api
+#[derive(SimpleObject)]
+pub struct InterfaceStatus {
+    name: String,
+    /// link is up
+    running: bool,
+    /// interface is administratively disabled
+    disabled: bool,
+    /// negotiated speed in Mbit/s, only known for running ethernet ports
+    speed_mbps: Option<u32>,
+    duplex: Option<Duplex>,
+    /// time of the last link up as shown by the device clock
+    last_link_up_time: Option<String>,
+    /// count of link downs since the last reboot
+    link_downs: u32,
+}
+
+/// status of all interfaces read at once from the RouterOS api
+#[derive(SimpleObject)]
+pub struct InterfaceStatusReport {
+    /// unix timestamp of the last fetch
+    updated_at: u64,
+    /// error of the last fetch, no interfaces are listed in this case
+    error: Option<String>,
+    interfaces: Vec<InterfaceStatus>,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum Duplex {
+    Full,
+    Half,
+}
+
+/// counters polled from the RouterOS api and the throughput between the last two polls
+#[derive(SimpleObject)]
+pub struct InterfaceTraffic {
+    /// unix timestamp of the last poll
+    polled_at: u64,
+    rx_bytes: u64,
+    tx_bytes: u64,
+    rx_packets: u64,
+    tx_packets: u64,
+    rx_errors: u64,
+    tx_errors: u64,
+    rx_drops: u64,
+    tx_drops: u64,
+    /// received bits per second, missing until the second poll and after counter resets
+    rx_bits_per_second: Option<f64>,
+    /// sent bits per second
+    tx_bits_per_second: Option<f64>,
+    rx_packets_per_second: Option<f64>,
+    tx_packets_per_second: Option<f64>,
+}
+
+impl From<&interface::InterfaceStatus> for InterfaceStatus {
+    fn from(value: &interface::InterfaceStatus) -> Self {
+        Self {
+            name: value.name().to_string(),
+            running: value.running(),
+            disabled: value.disabled(),
+            speed_mbps: value.speed_mbps(),
+            duplex: value
+                .full_duplex()
+                .map(|full| if full { Duplex::Full } else { Duplex::Half }),
+            last_link_up_time: value.last_link_up_time().map(str::to_string),
+            link_downs: value.link_downs(),
+        }
+    }
+}
+
+impl From<&interface::InterfaceStatusReport> for InterfaceStatusReport {
+    fn from(value: &interface::InterfaceStatusReport) -> Self {
+        let mut interfaces: Vec<InterfaceStatus> = value
+            .interfaces()
+            .into_iter()
+            .flat_map(|interfaces| interfaces.values())
+            .map(InterfaceStatus::from)
+            .collect();
+        interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+        Self {
+            updated_at: value
+                .fetched_at()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            error: value.error().map(|e| e.to_string()),
+            interfaces,
+        }
+    }
+}
+
+impl From<&traffic::InterfaceTraffic> for InterfaceTraffic {
+    fn from(value: &traffic::InterfaceTraffic) -> Self {
+        let counters = value.counters();
+        let rates = value.rates();
+        Self {
+            polled_at: value
+                .polled_at()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            rx_bytes: counters.rx_bytes,
+            tx_bytes: counters.tx_bytes,
+            rx_packets: counters.rx_packets,
+            tx_packets: counters.tx_packets,
+            rx_errors: counters.rx_errors,
+            tx_errors: counters.tx_errors,
+            rx_drops: counters.rx_drops,
+            tx_drops: counters.tx_drops,
+            rx_bits_per_second: rates.map(|r| r.rx_bits_per_second),
+            tx_bits_per_second: rates.map(|r| r.tx_bits_per_second),
+            rx_packets_per_second: rates.map(|r| r.rx_packets_per_second),
+            tx_packets_per_second: rates.map(|r| r.tx_packets_per_second),
+        }
+    }
+}
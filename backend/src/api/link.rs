@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use async_graphql::{Enum, Object};
+
+use crate::api::device::DevicePort;
+use crate::topology::model;
+use crate::topology::model::link::LinkMedium;
+use crate::topology::model::Topology;
+
+#[derive(Debug)]
+pub struct Link {
+    link: Arc<model::Link>,
+    topology: Arc<Topology>,
+}
+
+impl Link {
+    pub fn new(link: Arc<model::Link>, topology: Arc<Topology>) -> Self {
+        Self { link, topology }
+    }
+}
+
+#[derive(Debug)]
+pub struct WirelessLink(model::link::WirelessLink);
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum LinkMediumType {
+    Cable,
+    Wireless,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum WirelessLinkStatus {
+    Connected,
+    Planned,
+    Decommissioning,
+    Unknown,
+}
+
+impl From<model::link::WirelessLinkStatus> for WirelessLinkStatus {
+    fn from(value: model::link::WirelessLinkStatus) -> Self {
+        match value {
+            model::link::WirelessLinkStatus::Connected => WirelessLinkStatus::Connected,
+            model::link::WirelessLinkStatus::Planned => WirelessLinkStatus::Planned,
+            model::link::WirelessLinkStatus::Decommissioning => WirelessLinkStatus::Decommissioning,
+            model::link::WirelessLinkStatus::Unknown => WirelessLinkStatus::Unknown,
+        }
+    }
+}
+
+#[Object]
+impl Link {
+    /// cable or radio link
+    async fn medium(&self) -> LinkMediumType {
+        match self.link.medium() {
+            LinkMedium::Cable(_) => LinkMediumType::Cable,
+            LinkMedium::Wireless(_) => LinkMediumType::Wireless,
+        }
+    }
+    /// netbox id of the cable
+    async fn cable_id(&self) -> Option<u32> {
+        self.link.cable_id()
+    }
+    /// radio details, only set for wireless links
+    async fn wireless(&self) -> Option<WirelessLink> {
+        self.link.wireless().cloned().map(WirelessLink)
+    }
+    /// ports on both ends of each segment
+    async fn ports(&self) -> Vec<DevicePort> {
+        self.link
+            .path()
+            .iter()
+            .flat_map(|segment| segment.ports())
+            .flat_map(|port| DevicePort::from_idx(*port, self.topology.clone()))
+            .collect()
+    }
+}
+
+#[Object]
+impl WirelessLink {
+    /// netbox id of the wireless link
+    async fn id(&self) -> u32 {
+        self.0.id()
+    }
+    async fn ssid(&self) -> &str {
+        self.0.ssid()
+    }
+    async fn status(&self) -> WirelessLinkStatus {
+        self.0.status().into()
+    }
+    async fn description(&self) -> &str {
+        self.0.description()
+    }
+}
@@ -3,11 +3,15 @@ use std::sync::Arc;
 use async_graphql::Object;
 
 use crate::api::device::Device;
+use crate::api::rack::Rack;
 use crate::api::site::Site;
+use crate::api::tenant::Tenant;
+use crate::api::wireless::WirelessClientCount;
 use crate::error::BackendError;
+use crate::routeros::wireless::count_wireless_clients;
+use crate::topology::cache::get_topology;
 use crate::topology::model;
 use crate::topology::model::Topology;
-use crate::topology::query::get_topology;
 
 #[derive(Debug)]
 pub struct Location {
@@ -20,9 +24,15 @@ impl Location {
         Location { location, topology }
     }
 }
-pub async fn list_locations() -> Result<Vec<Location>, BackendError> {
+pub async fn list_locations(tenant: Option<String>) -> Result<Vec<Location>, BackendError> {
     let topology = get_topology().await?;
-    Ok(topology.list_locations_map(|s| Some(Location::new(s.clone(), topology.clone()))))
+    Ok(topology.list_locations_map(|l| {
+        if topology.tenant_matches(topology.tenant_of_location(l), tenant.as_deref()) {
+            Some(Location::new(l.clone(), topology.clone()))
+        } else {
+            None
+        }
+    }))
 }
 
 pub async fn get_location(id: u32) -> Result<Option<Location>, BackendError> {
@@ -49,6 +59,28 @@ impl Location {
             .and_then(|sid| self.topology.get_site(sid))
             .map(|s| Site::new(s.clone(), self.topology.clone()))
     }
+    /// enclosing location (if there is any)
+    async fn parent(&self) -> Option<Location> {
+        self.location
+            .parent()
+            .and_then(|lid| self.topology.get_location(lid))
+            .map(|l| Location::new(l, self.topology.clone()))
+    }
+    /// locations nested directly below this one
+    async fn children(&self) -> Vec<Location> {
+        self.location
+            .children()
+            .iter()
+            .flat_map(|lid| self.topology.get_location(*lid))
+            .map(|l| Location::new(l, self.topology.clone()))
+            .collect()
+    }
+    /// tenant the location is assigned to, inherited from the enclosing locations or the site if not set
+    async fn tenant(&self) -> Option<Tenant> {
+        self.topology
+            .get_tenant(self.topology.tenant_of_location(&self.location)?)
+            .map(|t| Tenant::from(t.as_ref()))
+    }
     /// devices on that location
     async fn devices(&self) -> Vec<Device> {
         let topology = &self.topology;
@@ -60,4 +92,25 @@ impl Location {
             .map(|d| Device::new(d, topology.clone()))
             .collect()
     }
+    /// clients registered at the access points on this and all nested locations
+    async fn wireless_client_count(&self) -> WirelessClientCount {
+        let devices = self
+            .topology
+            .get_location_idx(self.location.id())
+            .map(|idx| self.topology.devices_within_location(idx))
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|idx| self.topology.get_device(idx))
+            .collect();
+        count_wireless_clients(&self.topology, devices).await.into()
+    }
+    /// racks on that location
+    async fn racks(&self) -> Vec<Rack> {
+        self.location
+            .racks()
+            .iter()
+            .flat_map(|rid| self.topology.get_rack(*rid))
+            .map(|r| Rack::new(r, self.topology.clone()))
+            .collect()
+    }
 }
@@ -2,12 +2,31 @@ use async_graphql::{EmptyMutation, EmptySubscription, Schema};
 
 use crate::api::query::Query;
 
+pub mod change;
+pub mod circuit;
 pub mod device;
 pub mod device_type;
+pub mod health;
+pub mod interface;
+pub mod link;
 pub mod location;
+pub mod module;
+pub mod monitoring;
+pub mod path;
+pub mod power;
+pub mod prefix;
 pub mod query;
+pub mod rack;
+pub mod region;
+pub mod report;
 pub mod settings;
 pub mod site;
+pub mod status;
+pub mod tenant;
+pub mod tree;
+pub mod virtual_chassis;
+pub mod vlan;
+pub mod wireless;
 
 pub type GraphqlSchema = Schema<Query, EmptyMutation, EmptySubscription>;
 
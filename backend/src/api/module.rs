@@ -0,0 +1,62 @@
+use async_graphql::{Enum, SimpleObject};
+
+use crate::topology::model;
+
+/// hardware module installed in a module bay, e.g. a SFP transceiver
+#[derive(SimpleObject)]
+pub struct Module {
+    /// netbox id of module
+    id: u32,
+    /// name of the module bay
+    bay: String,
+    /// position of the module bay, used when naming the components of the module
+    bay_position: String,
+    /// model of the module type
+    model: String,
+    manufacturer: String,
+    part_number: String,
+    serial: String,
+    asset_tag: Option<String>,
+    status: ModuleStatus,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum ModuleStatus {
+    Active,
+    Decommissioning,
+    Failed,
+    Offline,
+    Planned,
+    Staged,
+    Unknown,
+}
+
+impl From<&model::module::Module> for Module {
+    fn from(value: &model::module::Module) -> Self {
+        Self {
+            id: value.id(),
+            bay: value.bay().name().to_string(),
+            bay_position: value.bay().position().to_string(),
+            model: value.module_type().model().to_string(),
+            manufacturer: value.module_type().manufacturer().to_string(),
+            part_number: value.module_type().part_number().to_string(),
+            serial: value.serial().to_string(),
+            asset_tag: value.asset_tag().map(str::to_string),
+            status: value.status().into(),
+        }
+    }
+}
+
+impl From<model::module::ModuleStatus> for ModuleStatus {
+    fn from(value: model::module::ModuleStatus) -> Self {
+        match value {
+            model::module::ModuleStatus::Active => ModuleStatus::Active,
+            model::module::ModuleStatus::Decommissioning => ModuleStatus::Decommissioning,
+            model::module::ModuleStatus::Failed => ModuleStatus::Failed,
+            model::module::ModuleStatus::Offline => ModuleStatus::Offline,
+            model::module::ModuleStatus::Planned => ModuleStatus::Planned,
+            model::module::ModuleStatus::Staged => ModuleStatus::Staged,
+            model::module::ModuleStatus::Unknown => ModuleStatus::Unknown,
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use async_graphql::{Enum, SimpleObject};
+
+use crate::topology::model;
+
+/// monitoring settings of a device maintained in netbox
+#[derive(SimpleObject)]
+pub struct MonitoringSettings {
+    /// true if the device is monitored
+    enabled: bool,
+    /// address used for pinging, the loopback address if not overridden
+    ping_target: Option<String>,
+    criticality: Criticality,
+    /// interval between two checks in seconds, empty for the default
+    check_interval: Option<u64>,
+}
+
+impl MonitoringSettings {
+    pub fn new(device: &model::Device) -> Self {
+        let settings = device.monitoring();
+        Self {
+            enabled: device.can_ping(),
+            ping_target: device.ping_target().map(|a| a.to_string()),
+            criticality: settings.criticality().into(),
+            check_interval: settings.check_interval().map(|i| i.as_secs()),
+        }
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum Criticality {
+    Low,
+    Normal,
+    High,
+    Critical,
+}
+
+impl From<model::monitoring::Criticality> for Criticality {
+    fn from(value: model::monitoring::Criticality) -> Self {
+        match value {
+            model::monitoring::Criticality::Low => Criticality::Low,
+            model::monitoring::Criticality::Normal => Criticality::Normal,
+            model::monitoring::Criticality::High => Criticality::High,
+            model::monitoring::Criticality::Critical => Criticality::Critical,
+        }
+    }
+}
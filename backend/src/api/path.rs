@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use async_graphql::Object;
+
+use crate::api::device::DevicePort;
+use crate::api::link::Link;
+use crate::topology::model;
+use crate::topology::model::Topology;
+
+#[derive(Debug)]
+pub struct CablePath {
+    path: model::path::CablePath,
+    topology: Arc<Topology>,
+}
+
+impl CablePath {
+    pub fn new(path: model::path::CablePath, topology: Arc<Topology>) -> Self {
+        Self { path, topology }
+    }
+}
+
+#[Object]
+impl CablePath {
+    /// all passed ports, starting with the traced port
+    async fn hops(&self) -> Vec<DevicePort> {
+        self.path
+            .hops()
+            .iter()
+            .flat_map(|port| DevicePort::from_idx(*port, self.topology.clone()))
+            .collect()
+    }
+    /// crossed links, cables as well as radio links
+    async fn links(&self) -> Vec<Link> {
+        self.path
+            .links()
+            .iter()
+            .flat_map(|idx| self.topology.get_link(*idx))
+            .map(|l| Link::new(l, self.topology.clone()))
+            .collect()
+    }
+    /// true if the path crosses at least one radio link
+    async fn wireless(&self) -> bool {
+        self.path
+            .links()
+            .iter()
+            .flat_map(|idx| self.topology.get_link(*idx))
+            .any(|l| l.wireless().is_some())
+    }
+    /// interface on the other end of the cabling (if there is any)
+    async fn far_end(&self) -> Option<DevicePort> {
+        self.path
+            .far_end()
+            .and_then(|port| DevicePort::from_idx(port, self.topology.clone()))
+    }
+}
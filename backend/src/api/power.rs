@@ -0,0 +1,165 @@
+use std::sync::Arc;
+
+use async_graphql::{Enum, Object};
+
+use crate::api::device::Device;
+use crate::api::rack::Rack;
+use crate::api::site::Site;
+use crate::error::BackendError;
+use crate::topology::cache::get_topology;
+use crate::topology::model;
+use crate::topology::model::power::PowerSource as ModelPowerSource;
+use crate::topology::model::Topology;
+
+#[derive(Debug)]
+pub struct PowerFeed {
+    feed_idx: usize,
+    feed: Arc<model::power::PowerFeed>,
+    topology: Arc<Topology>,
+}
+
+impl PowerFeed {
+    pub fn from_idx(feed_idx: usize, topology: Arc<Topology>) -> Option<Self> {
+        let feed = topology.get_power_feed(feed_idx)?;
+        Some(Self {
+            feed_idx,
+            feed,
+            topology,
+        })
+    }
+}
+
+/// outlet or feed a device draws power from
+#[derive(Debug)]
+pub struct PowerSource {
+    source: ModelPowerSource,
+    topology: Arc<Topology>,
+}
+
+impl PowerSource {
+    pub fn new(source: ModelPowerSource, topology: Arc<Topology>) -> Self {
+        Self { source, topology }
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum PowerSourceType {
+    Outlet,
+    Feed,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum PowerFeedStatus {
+    Active,
+    Failed,
+    Offline,
+    Planned,
+    Unknown,
+}
+
+impl From<model::power::PowerFeedStatus> for PowerFeedStatus {
+    fn from(value: model::power::PowerFeedStatus) -> Self {
+        match value {
+            model::power::PowerFeedStatus::Active => PowerFeedStatus::Active,
+            model::power::PowerFeedStatus::Failed => PowerFeedStatus::Failed,
+            model::power::PowerFeedStatus::Offline => PowerFeedStatus::Offline,
+            model::power::PowerFeedStatus::Planned => PowerFeedStatus::Planned,
+            model::power::PowerFeedStatus::Unknown => PowerFeedStatus::Unknown,
+        }
+    }
+}
+
+pub async fn list_power_feeds() -> Result<Vec<PowerFeed>, BackendError> {
+    let topology = get_topology().await?;
+    Ok((0..)
+        .map_while(|idx| PowerFeed::from_idx(idx, topology.clone()))
+        .collect())
+}
+
+fn devices_of_source(source: ModelPowerSource, topology: &Arc<Topology>) -> Vec<Device> {
+    topology
+        .powered_devices(source)
+        .into_iter()
+        .flat_map(|idx| topology.get_device(idx))
+        .map(|d| Device::new(d, topology.clone()))
+        .collect()
+}
+
+#[Object]
+impl PowerFeed {
+    /// netbox id of power feed
+    async fn id(&self) -> u32 {
+        self.feed.id()
+    }
+    async fn name(&self) -> &str {
+        self.feed.name()
+    }
+    /// name of the power panel
+    async fn panel(&self) -> &str {
+        self.feed.panel()
+    }
+    async fn status(&self) -> PowerFeedStatus {
+        self.feed.status().into()
+    }
+    async fn site(&self) -> Option<Site> {
+        self.feed
+            .site()
+            .and_then(|sid| self.topology.get_site(sid))
+            .map(|s| Site::new(s.clone(), self.topology.clone()))
+    }
+    /// rack the feed ends in (if there is any)
+    async fn rack(&self) -> Option<Rack> {
+        self.feed
+            .rack()
+            .and_then(|rid| self.topology.get_rack(rid))
+            .map(|r| Rack::new(r, self.topology.clone()))
+    }
+    /// devices connected directly to this feed
+    async fn devices(&self) -> Vec<Device> {
+        devices_of_source(ModelPowerSource::Feed(self.feed_idx), &self.topology)
+    }
+}
+
+#[Object]
+impl PowerSource {
+    async fn source_type(&self) -> PowerSourceType {
+        match self.source {
+            ModelPowerSource::Outlet { .. } => PowerSourceType::Outlet,
+            ModelPowerSource::Feed(_) => PowerSourceType::Feed,
+        }
+    }
+    /// device providing the outlet, e.g. a pdu or ups
+    async fn device(&self) -> Option<Device> {
+        match self.source {
+            ModelPowerSource::Outlet { device, .. } => self
+                .topology
+                .get_device(device)
+                .map(|d| Device::new(d, self.topology.clone())),
+            ModelPowerSource::Feed(_) => None,
+        }
+    }
+    /// name of the outlet
+    async fn outlet(&self) -> Option<String> {
+        match self.source {
+            ModelPowerSource::Outlet { device, outlet } => self
+                .topology
+                .get_device(device)?
+                .power_outlets()
+                .get(outlet)
+                .map(|o| o.name().to_string()),
+            ModelPowerSource::Feed(_) => None,
+        }
+    }
+    async fn feed(&self) -> Option<PowerFeed> {
+        match self.source {
+            ModelPowerSource::Outlet { .. } => None,
+            ModelPowerSource::Feed(feed_idx) => {
+                PowerFeed::from_idx(feed_idx, self.topology.clone())
+            }
+        }
+    }
+    /// all devices connected directly to this source
+    async fn devices(&self) -> Vec<Device> {
+        devices_of_source(self.source, &self.topology)
+    }
+}
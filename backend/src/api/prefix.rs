@@ -0,0 +1,107 @@
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use async_graphql::Object;
+
+use crate::api::device::DevicePort;
+use crate::api::site::Site;
+use crate::api::tenant::Tenant;
+use crate::api::vlan::Vlan;
+use crate::error::BackendError;
+use crate::topology::cache::get_topology;
+use crate::topology::model;
+use crate::topology::model::prefix::AddressLookup;
+use crate::topology::model::Topology;
+
+#[derive(Debug)]
+pub struct Prefix {
+    prefix: Arc<model::Prefix>,
+    topology: Arc<Topology>,
+}
+
+impl Prefix {
+    pub fn new(prefix: Arc<model::Prefix>, topology: Arc<Topology>) -> Self {
+        Self { prefix, topology }
+    }
+}
+
+#[derive(Debug)]
+pub struct AddressLookupResult {
+    lookup: AddressLookup,
+    topology: Arc<Topology>,
+}
+
+pub async fn lookup_address(
+    ip: String,
+    vrf: Option<String>,
+) -> Result<AddressLookupResult, BackendError> {
+    let address: IpAddr = ip.parse()?;
+    let topology = get_topology().await?;
+    Ok(AddressLookupResult {
+        lookup: topology.lookup_address(address, vrf.as_deref()),
+        topology,
+    })
+}
+
+#[Object]
+impl Prefix {
+    /// netbox id of prefix
+    async fn id(&self) -> u32 {
+        self.prefix.id()
+    }
+    /// network with mask
+    async fn prefix(&self) -> String {
+        self.prefix.net().to_string()
+    }
+    /// name of the vrf, empty for the global table
+    async fn vrf(&self) -> Option<&str> {
+        self.prefix.vrf()
+    }
+    /// site of prefix (if there is any)
+    async fn site(&self) -> Option<Site> {
+        self.prefix
+            .site()
+            .and_then(|sid| self.topology.get_site(sid))
+            .map(|s| Site::new(s.clone(), self.topology.clone()))
+    }
+    /// vlan the prefix is used in
+    async fn vlan(&self) -> Option<Vlan> {
+        Vlan::from_id(self.prefix.vlan()?, self.topology.clone())
+    }
+    /// tenant the prefix is assigned to, inherited from its site if not set
+    async fn tenant(&self) -> Option<Tenant> {
+        self.topology
+            .get_tenant(self.topology.tenant_of_prefix(&self.prefix)?)
+            .map(|t| Tenant::from(t.as_ref()))
+    }
+}
+
+#[Object]
+impl AddressLookupResult {
+    /// the queried address
+    async fn address(&self) -> String {
+        self.lookup.address().to_string()
+    }
+    /// longest prefix containing the address
+    async fn prefix(&self) -> Option<Prefix> {
+        self.lookup
+            .prefix()
+            .and_then(|idx| self.topology.get_prefix(idx))
+            .map(|p| Prefix::new(p, self.topology.clone()))
+    }
+    /// interfaces holding the address
+    async fn ports(&self) -> Vec<DevicePort> {
+        self.lookup
+            .ports()
+            .iter()
+            .flat_map(|port| DevicePort::from_idx(*port, self.topology.clone()))
+            .collect()
+    }
+    /// site of the prefix or of the owning device
+    async fn site(&self) -> Option<Site> {
+        self.lookup
+            .site()
+            .and_then(|sid| self.topology.get_site(sid))
+            .map(|s| Site::new(s.clone(), self.topology.clone()))
+    }
+}
@@ -1,11 +1,23 @@
 use async_graphql::Object;
 
+use crate::api::change::{list_topology_changes, TopologyChange};
+use crate::api::circuit::{list_circuits, Circuit};
 use crate::api::device::{get_device, list_devices, Device};
 use crate::api::location::Location;
 use crate::api::location::{get_location, list_locations};
+use crate::api::power::{list_power_feeds, PowerFeed};
+use crate::api::prefix::{lookup_address, AddressLookupResult};
+use crate::api::rack::{get_rack, list_racks, Rack};
+use crate::api::region::{get_region, list_regions, Region};
+use crate::api::report::{get_topology_report, TopologyReport};
 use crate::api::settings::SettingsData;
 use crate::api::site::Site;
 use crate::api::site::{get_site, list_sites};
+use crate::api::status::TopologyStatus;
+use crate::api::tenant::{list_tenants, Tenant};
+use crate::api::tree::{navigation_tree, NavigationNode};
+use crate::api::virtual_chassis::{list_virtual_chassis, VirtualChassis};
+use crate::api::vlan::{get_vlan, list_vlans, Vlan};
 use crate::error;
 use crate::error::BackendError;
 
@@ -17,28 +29,97 @@ impl Query {
     async fn settings(&self) -> error::Result<SettingsData> {
         SettingsData::create_from_config()
     }
-    /// list all known devices available for query
-    async fn devices(&self) -> Result<Vec<Device>, BackendError> {
-        list_devices().await
+    /// list all monitored devices having an address to ping, optionally only those of a tenant
+    async fn devices(&self, tenant: Option<String>) -> Result<Vec<Device>, BackendError> {
+        list_devices(tenant).await
     }
     /// take single device by its id
     async fn device(&self, id: u32) -> Result<Option<Device>, BackendError> {
         get_device(id).await
     }
-    /// list all known sites available for query
-    async fn sites(&self) -> Result<Vec<Site>, BackendError> {
-        list_sites().await
+    /// list all known sites available for query, optionally only those of a tenant
+    async fn sites(&self, tenant: Option<String>) -> Result<Vec<Site>, BackendError> {
+        list_sites(tenant).await
     }
     /// get single site by id
     async fn site(&self, id: u32) -> Result<Option<Site>, BackendError> {
         get_site(id).await
     }
-    /// list all known locations
-    async fn locations(&self) -> Result<Vec<Location>, BackendError> {
-        list_locations().await
+    /// list all known locations, optionally only those of a tenant
+    async fn locations(&self, tenant: Option<String>) -> Result<Vec<Location>, BackendError> {
+        list_locations(tenant).await
+    }
+    /// tenants assigned to any site, location, device or prefix
+    async fn tenants(&self) -> Result<Vec<Tenant>, BackendError> {
+        list_tenants().await
     }
     /// get single location
     async fn location(&self, id: u32) -> Result<Option<Location>, BackendError> {
         get_location(id).await
     }
+    /// list all known regions
+    async fn regions(&self) -> Result<Vec<Region>, BackendError> {
+        list_regions().await
+    }
+    /// get single region
+    async fn region(&self, id: u32) -> Result<Option<Region>, BackendError> {
+        get_region(id).await
+    }
+    /// regions, sites and locations as nested tree
+    async fn navigation_tree(&self) -> Result<Vec<NavigationNode>, BackendError> {
+        navigation_tree().await
+    }
+    /// stacks of devices managed as one
+    async fn virtual_chassis(&self) -> Result<Vec<VirtualChassis>, BackendError> {
+        list_virtual_chassis().await
+    }
+    /// list all known racks
+    async fn racks(&self) -> Result<Vec<Rack>, BackendError> {
+        list_racks().await
+    }
+    /// get single rack
+    async fn rack(&self, id: u32) -> Result<Option<Rack>, BackendError> {
+        get_rack(id).await
+    }
+    /// list all known vlans, optionally only those with the given 802.1q id
+    async fn vlans(&self, vid: Option<u16>) -> Result<Vec<Vlan>, BackendError> {
+        list_vlans(vid).await
+    }
+    /// get single vlan by netbox id
+    async fn vlan(&self, id: u32) -> Result<Option<Vlan>, BackendError> {
+        get_vlan(id).await
+    }
+    /// list all provider circuits
+    async fn circuits(&self) -> Result<Vec<Circuit>, BackendError> {
+        list_circuits().await
+    }
+    /// list all power feeds of all power panels
+    async fn power_feeds(&self) -> Result<Vec<PowerFeed>, BackendError> {
+        list_power_feeds().await
+    }
+    /// find prefix, interfaces and site of an ip address
+    ///
+    /// without a vrf only the global table is searched, prefixes and addresses within vrfs are not considered
+    async fn lookup_address(
+        &self,
+        ip: String,
+        vrf: Option<String>,
+    ) -> Result<AddressLookupResult, BackendError> {
+        lookup_address(ip, vrf).await
+    }
+    /// age of the served topology and the last fetch error
+    async fn topology_status(&self) -> TopologyStatus {
+        TopologyStatus::create_from_cache()
+    }
+    /// devices, ports, links and addresses changed between refreshes, optionally since a unix timestamp
+    async fn topology_changes(
+        &self,
+        since: Option<u64>,
+    ) -> Result<Vec<TopologyChange>, BackendError> {
+        list_topology_changes(since).await
+    }
+    /// inconsistencies in the netbox inventory
+    async fn topology_report(&self) -> Result<TopologyReport, BackendError> {
+        get_topology_report().await
+    }
 }
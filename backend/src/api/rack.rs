@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use async_graphql::Object;
+
+use crate::api::device::Device;
+use crate::api::location::Location;
+use crate::api::site::Site;
+use crate::error::BackendError;
+use crate::topology::cache::get_topology;
+use crate::topology::model;
+use crate::topology::model::rack::RackMount;
+use crate::topology::model::Topology;
+
+#[derive(Debug)]
+pub struct Rack {
+    rack: Arc<model::Rack>,
+    topology: Arc<Topology>,
+}
+
+impl Rack {
+    pub fn new(rack: Arc<model::Rack>, topology: Arc<Topology>) -> Self {
+        Self { rack, topology }
+    }
+}
+
+#[derive(Debug)]
+pub struct MountedDevice {
+    mount: RackMount,
+    topology: Arc<Topology>,
+}
+
+pub async fn list_racks() -> Result<Vec<Rack>, BackendError> {
+    let topology = get_topology().await?;
+    Ok(topology.list_racks_map(|r| Some(Rack::new(r.clone(), topology.clone()))))
+}
+
+pub async fn get_rack(id: u32) -> Result<Option<Rack>, BackendError> {
+    let topology = get_topology().await?;
+    Ok(topology
+        .get_rack_by_id(id)
+        .map(|r| Rack::new(r, topology.clone())))
+}
+
+#[Object]
+impl Rack {
+    /// id of rack
+    async fn id(&self) -> u32 {
+        self.rack.id()
+    }
+    /// name of rack
+    async fn name(&self) -> &str {
+        self.rack.name()
+    }
+    /// height in rack units
+    async fn u_height(&self) -> u32 {
+        self.rack.u_height()
+    }
+    /// location of rack (if there is any)
+    async fn location(&self) -> Option<Location> {
+        self.rack
+            .location()
+            .and_then(|lid| self.topology.get_location(lid))
+            .map(|l| Location::new(l, self.topology.clone()))
+    }
+    /// site of rack
+    async fn site(&self) -> Option<Site> {
+        self.rack
+            .site()
+            .and_then(|sid| self.topology.get_site(sid))
+            .map(|s| Site::new(s.clone(), self.topology.clone()))
+    }
+    /// mounted devices ordered by position
+    async fn devices(&self) -> Vec<MountedDevice> {
+        self.rack
+            .devices()
+            .iter()
+            .map(|mount| MountedDevice {
+                mount: *mount,
+                topology: self.topology.clone(),
+            })
+            .collect()
+    }
+}
+
+#[Object]
+impl MountedDevice {
+    /// lowest unit occupied by the device, may be a half unit like 12.5
+    async fn position(&self) -> Option<f64> {
+        self.mount.position().map(|p| p.units())
+    }
+    async fn device(&self) -> Option<Device> {
+        self.topology
+            .get_device(self.mount.device())
+            .map(|d| Device::new(d, self.topology.clone()))
+    }
+}
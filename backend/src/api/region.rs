@@ -0,0 +1,123 @@
+use std::sync::Arc;
+
+use async_graphql::Object;
+
+use crate::api::site::Site;
+use crate::error::BackendError;
+use crate::topology::cache::get_topology;
+use crate::topology::model;
+use crate::topology::model::Topology;
+
+#[derive(Debug)]
+pub struct Region {
+    region: Arc<model::Region>,
+    topology: Arc<Topology>,
+}
+
+impl Region {
+    pub fn new(region: Arc<model::Region>, topology: Arc<Topology>) -> Self {
+        Self { region, topology }
+    }
+}
+
+#[derive(Debug)]
+pub struct SiteGroup {
+    group: Arc<model::Region>,
+    topology: Arc<Topology>,
+}
+
+impl SiteGroup {
+    pub fn new(group: Arc<model::Region>, topology: Arc<Topology>) -> Self {
+        Self { group, topology }
+    }
+}
+
+pub async fn list_regions() -> Result<Vec<Region>, BackendError> {
+    let topology = get_topology().await?;
+    Ok(topology.list_regions_map(|r| Some(Region::new(r.clone(), topology.clone()))))
+}
+
+pub async fn get_region(id: u32) -> Result<Option<Region>, BackendError> {
+    let topology = get_topology().await?;
+    Ok(topology
+        .get_region_by_id(id)
+        .map(|r| Region::new(r, topology.clone())))
+}
+
+fn list_sites(sites: &[usize], topology: &Arc<Topology>) -> Vec<Site> {
+    sites
+        .iter()
+        .flat_map(|sid| topology.get_site(*sid))
+        .map(|s| Site::new(s.clone(), topology.clone()))
+        .collect()
+}
+
+#[Object]
+impl Region {
+    /// id of region
+    async fn id(&self) -> u32 {
+        self.region.id()
+    }
+    /// name of region
+    async fn name(&self) -> &str {
+        self.region.name()
+    }
+    async fn slug(&self) -> &str {
+        self.region.slug()
+    }
+    /// enclosing region (if there is any)
+    async fn parent(&self) -> Option<Region> {
+        self.region
+            .parent()
+            .and_then(|rid| self.topology.get_region(rid))
+            .map(|r| Region::new(r, self.topology.clone()))
+    }
+    /// regions nested directly below this one
+    async fn children(&self) -> Vec<Region> {
+        self.region
+            .children()
+            .iter()
+            .flat_map(|rid| self.topology.get_region(*rid))
+            .map(|r| Region::new(r, self.topology.clone()))
+            .collect()
+    }
+    /// sites directly assigned to this region
+    async fn sites(&self) -> Vec<Site> {
+        list_sites(self.region.sites(), &self.topology)
+    }
+}
+
+#[Object]
+impl SiteGroup {
+    /// id of site group
+    async fn id(&self) -> u32 {
+        self.group.id()
+    }
+    /// name of site group
+    async fn name(&self) -> &str {
+        self.group.name()
+    }
+    async fn slug(&self) -> &str {
+        self.group.slug()
+    }
+    /// enclosing site group (if there is any)
+    async fn parent(&self) -> Option<SiteGroup> {
+        self.group
+            .parent()
+            .and_then(|gid| self.topology.get_site_group(gid))
+            .map(|g| SiteGroup::new(g, self.topology.clone()))
+    }
+    /// site groups nested directly below this one
+    async fn children(&self) -> Vec<SiteGroup> {
+        self.group
+            .children()
+            .iter()
+            .flat_map(|gid| self.topology.get_site_group(*gid))
+            .map(|g| SiteGroup::new(g, self.topology.clone()))
+            .collect()
+    }
+    /// sites directly assigned to this group
+    async fn sites(&self) -> Vec<Site> {
+        list_sites(self.group.sites(), &self.topology)
+    }
+}
@@ -0,0 +1,191 @@
+use std::sync::Arc;
+
+use async_graphql::{Enum, Object};
+
+use crate::api::device::{Device, DevicePort};
+use crate::error::BackendError;
+use crate::topology::cache::get_topology;
+use crate::topology::model::validation;
+use crate::topology::model::Topology;
+
+/// Inconsistencies found in the netbox inventory
+#[derive(Debug)]
+pub struct TopologyReport {
+    topology: Arc<Topology>,
+}
+
+#[derive(Debug)]
+pub struct TopologyIssue {
+    issue: validation::TopologyIssue,
+    topology: Arc<Topology>,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum IssueKind {
+    MissingLoopback,
+    DuplicateAddress,
+    DuplicatePrefix,
+    MissingLocation,
+    InvalidMonitoringSetting,
+    InvalidModule,
+    UnknownCableTermination,
+    InvalidCable,
+    InvalidWirelessLink,
+    InvalidPowerFeed,
+    InvalidCircuit,
+    UncabledRearPort,
+}
+
+pub async fn get_topology_report() -> Result<TopologyReport, BackendError> {
+    Ok(TopologyReport {
+        topology: get_topology().await?,
+    })
+}
+
+impl TopologyIssue {
+    fn device_name(&self, device_idx: usize) -> String {
+        self.topology
+            .get_device(device_idx)
+            .map(|d| d.name().to_string())
+            .unwrap_or_default()
+    }
+}
+
+#[Object]
+impl TopologyReport {
+    /// count of found issues
+    async fn count(&self) -> usize {
+        self.topology.issues().len()
+    }
+    /// all found issues, optionally filtered by kind
+    async fn issues(&self, kind: Option<IssueKind>) -> Vec<TopologyIssue> {
+        self.topology
+            .issues()
+            .iter()
+            .map(|issue| TopologyIssue {
+                issue: issue.clone(),
+                topology: self.topology.clone(),
+            })
+            .filter(|issue| kind.map(|k| issue.issue_kind() == k).unwrap_or(true))
+            .collect()
+    }
+}
+
+impl TopologyIssue {
+    fn issue_kind(&self) -> IssueKind {
+        match &self.issue {
+            validation::TopologyIssue::MissingLoopback { .. } => IssueKind::MissingLoopback,
+            validation::TopologyIssue::DuplicateAddress { .. } => IssueKind::DuplicateAddress,
+            validation::TopologyIssue::DuplicatePrefix { .. } => IssueKind::DuplicatePrefix,
+            validation::TopologyIssue::MissingLocation { .. } => IssueKind::MissingLocation,
+            validation::TopologyIssue::InvalidMonitoringSetting { .. } => {
+                IssueKind::InvalidMonitoringSetting
+            }
+            validation::TopologyIssue::InvalidModule { .. } => IssueKind::InvalidModule,
+            validation::TopologyIssue::UnknownCableTermination { .. } => {
+                IssueKind::UnknownCableTermination
+            }
+            validation::TopologyIssue::InvalidCable { .. } => IssueKind::InvalidCable,
+            validation::TopologyIssue::InvalidWirelessLink { .. } => IssueKind::InvalidWirelessLink,
+            validation::TopologyIssue::InvalidPowerFeed { .. } => IssueKind::InvalidPowerFeed,
+            validation::TopologyIssue::InvalidCircuit { .. } => IssueKind::InvalidCircuit,
+            validation::TopologyIssue::UncabledRearPort { .. } => IssueKind::UncabledRearPort,
+        }
+    }
+}
+
+#[Object]
+impl TopologyIssue {
+    /// kind of issue
+    async fn kind(&self) -> IssueKind {
+        self.issue_kind()
+    }
+    /// human readable description of the issue
+    async fn message(&self) -> String {
+        match &self.issue {
+            validation::TopologyIssue::MissingLoopback { device } => format!(
+                "RouterOS device {} has no loopback address",
+                self.device_name(*device)
+            ),
+            validation::TopologyIssue::DuplicateAddress {
+                address,
+                vrf,
+                ports,
+            } => format!(
+                "Address {address} is assigned to {} interfaces in vrf {}",
+                ports.len(),
+                vrf.as_deref().unwrap_or("global")
+            ),
+            validation::TopologyIssue::DuplicatePrefix {
+                net,
+                vrf,
+                prefix_ids,
+            } => format!(
+                "Prefix {net} is maintained {} times in vrf {}",
+                prefix_ids.len(),
+                vrf.as_deref().unwrap_or("global")
+            ),
+            validation::TopologyIssue::MissingLocation { device } => {
+                format!("Device {} has no location", self.device_name(*device))
+            }
+            validation::TopologyIssue::InvalidMonitoringSetting { device, reason } => format!(
+                "Monitoring settings of device {} are invalid: {reason}",
+                self.device_name(*device)
+            ),
+            validation::TopologyIssue::InvalidModule {
+                device,
+                module_id,
+                reason,
+            } => format!(
+                "Module {module_id} of device {} is invalid: {reason}",
+                self.device_name(*device)
+            ),
+            validation::TopologyIssue::UnknownCableTermination { cable_id, port_id } => {
+                format!("Cable {cable_id} ends on unknown port {port_id}")
+            }
+            validation::TopologyIssue::InvalidCable { cable_id, reason } => {
+                format!("Cable {cable_id} is invalid: {reason}")
+            }
+            validation::TopologyIssue::InvalidWirelessLink {
+                wireless_link_id,
+                reason,
+            } => format!("Wireless link {wireless_link_id} is invalid: {reason}"),
+            validation::TopologyIssue::InvalidPowerFeed {
+                power_feed_id,
+                reason,
+            } => format!("Power feed {power_feed_id} is invalid: {reason}"),
+            validation::TopologyIssue::InvalidCircuit { circuit_id, reason } => {
+                format!("Circuit {circuit_id} is invalid: {reason}")
+            }
+            validation::TopologyIssue::UncabledRearPort {
+                rear_port,
+                front_ports,
+            } => format!(
+                "Rear port of {} front ports on device {} has no cable",
+                front_ports.len(),
+                self.device_name(rear_port.device_idx())
+            ),
+        }
+    }
+    /// affected devices
+    async fn devices(&self) -> Vec<Device> {
+        self.issue
+            .devices()
+            .into_iter()
+            .flat_map(|idx| self.topology.get_device(idx))
+            .map(|d| Device::new(d, self.topology.clone()))
+            .collect()
+    }
+    /// affected ports
+    async fn ports(&self) -> Vec<DevicePort> {
+        self.issue
+            .ports()
+            .into_iter()
+            .flat_map(|port| DevicePort::from_idx(port, self.topology.clone()))
+            .collect()
+    }
+    /// netbox id of the affected cable
+    async fn cable_id(&self) -> Option<u32> {
+        self.issue.cable_id()
+    }
+}
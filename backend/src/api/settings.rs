@@ -14,9 +14,9 @@ impl SettingsData {
     pub fn create_from_config() -> error::Result<Self> {
         let config = config();
         Ok(SettingsData {
-            client_id: config.auth_client_id(),
-            auth_url: config.auth_url(),
-            token_url: config.auth_token_url(),
+            client_id: config.auth_client_id()?,
+            auth_url: config.auth_url()?,
+            token_url: config.auth_token_url()?,
         })
     }
 }
@@ -2,11 +2,17 @@ use std::sync::Arc;
 
 use async_graphql::Object;
 
+use crate::api::circuit::Circuit;
 use crate::api::location::Location;
+use crate::api::region::{Region, SiteGroup};
+use crate::api::tenant::Tenant;
+use crate::api::vlan::VlanGroup;
+use crate::api::wireless::WirelessClientCount;
 use crate::error::BackendError;
+use crate::routeros::wireless::count_wireless_clients;
+use crate::topology::cache::get_topology;
 use crate::topology::model;
 use crate::topology::model::Topology;
-use crate::topology::query::get_topology;
 
 #[derive(Debug)]
 pub struct Site {
@@ -26,9 +32,15 @@ pub async fn get_site(id: u32) -> Result<Option<Site>, BackendError> {
         .get_site_by_id(id)
         .map(|s| Site::new(s.clone(), topology.clone())))
 }
-pub async fn list_sites() -> Result<Vec<Site>, BackendError> {
+pub async fn list_sites(tenant: Option<String>) -> Result<Vec<Site>, BackendError> {
     let topology = get_topology().await?;
-    Ok(topology.list_sites_map(|s| Some(Site::new(s.clone(), topology.clone()))))
+    Ok(topology.list_sites_map(|s| {
+        if topology.tenant_matches(s.tenant(), tenant.as_deref()) {
+            Some(Site::new(s.clone(), topology.clone()))
+        } else {
+            None
+        }
+    }))
 }
 
 #[Object]
@@ -58,4 +70,51 @@ impl Site {
     async fn count_locations(&self) -> usize {
         self.site.locations().len()
     }
+    /// region of site (if there is any)
+    async fn region(&self) -> Option<Region> {
+        self.site
+            .region()
+            .and_then(|rid| self.topology.get_region(rid))
+            .map(|r| Region::new(r, self.topology.clone()))
+    }
+    /// site group of site (if there is any)
+    async fn group(&self) -> Option<SiteGroup> {
+        self.site
+            .group()
+            .and_then(|gid| self.topology.get_site_group(gid))
+            .map(|g| SiteGroup::new(g, self.topology.clone()))
+    }
+    /// vlan groups assigned to this site
+    async fn vlan_groups(&self) -> Vec<VlanGroup> {
+        self.site
+            .vlan_groups()
+            .iter()
+            .flat_map(|idx| self.topology.get_vlan_group(*idx))
+            .map(|g| VlanGroup::new(g, self.topology.clone()))
+            .collect()
+    }
+    /// tenant the site is assigned to
+    async fn tenant(&self) -> Option<Tenant> {
+        self.topology
+            .get_tenant(self.site.tenant()?)
+            .map(|t| Tenant::from(t.as_ref()))
+    }
+    /// provider circuits terminating at this site
+    async fn circuits(&self) -> Vec<Circuit> {
+        self.topology
+            .get_site_idx(self.site.id())
+            .map(|idx| self.topology.circuits_of_site(idx))
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|idx| Circuit::from_idx(idx, self.topology.clone()))
+            .collect()
+    }
+    /// clients registered at all access points of this site
+    async fn wireless_client_count(&self) -> WirelessClientCount {
+        let site_idx = self.topology.get_site_idx(self.site.id());
+        let devices = self
+            .topology
+            .list_devices_map(|d| (site_idx.is_some() && d.site() == site_idx).then(|| d.clone()));
+        count_wireless_clients(&self.topology, devices).await.into()
+    }
 }
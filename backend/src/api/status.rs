@@ -0,0 +1,28 @@
+use async_graphql::SimpleObject;
+
+use crate::topology::cache::topology_cache_status;
+
+/// freshness of the served topology
+#[derive(SimpleObject)]
+pub struct TopologyStatus {
+    /// seconds since the served topology was fetched, empty if there is none yet
+    age: Option<u64>,
+    /// error of the last fetch, empty if it was successful
+    last_error: Option<String>,
+    /// seconds since the last failed fetch
+    last_error_age: Option<u64>,
+    /// count of failed fetches since startup
+    failed_fetches: u64,
+}
+
+impl TopologyStatus {
+    pub fn create_from_cache() -> Self {
+        let status = topology_cache_status();
+        TopologyStatus {
+            age: status.age().map(|a| a.as_secs()),
+            last_error: status.last_error().map(|e| e.to_string()),
+            last_error_age: status.last_error_age().map(|a| a.as_secs()),
+            failed_fetches: status.failed_fetches(),
+        }
+    }
+}
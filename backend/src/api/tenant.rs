@@ -0,0 +1,37 @@
+use async_graphql::SimpleObject;
+
+use crate::error::BackendError;
+use crate::topology::cache::get_topology;
+use crate::topology::model;
+
+/// owner of sites, locations, devices and prefixes
+#[derive(SimpleObject)]
+pub struct Tenant {
+    /// netbox id of tenant
+    id: u32,
+    name: String,
+    /// slug used to filter the lists by tenant
+    slug: String,
+    /// name of the tenant group
+    group: Option<String>,
+}
+
+impl From<&model::Tenant> for Tenant {
+    fn from(value: &model::Tenant) -> Self {
+        Self {
+            id: value.id(),
+            name: value.name().to_string(),
+            slug: value.slug().to_string(),
+            group: value.group().map(str::to_string),
+        }
+    }
+}
+
+pub async fn list_tenants() -> Result<Vec<Tenant>, BackendError> {
+    let topology = get_topology().await?;
+    Ok(topology
+        .list_tenants()
+        .iter()
+        .map(|t| Tenant::from(t.as_ref()))
+        .collect())
+}
@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use async_graphql::{Enum, SimpleObject};
+
+use crate::error::BackendError;
+use crate::topology::cache::get_topology;
+use crate::topology::model::{Location, Region, Site, Topology};
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum NavigationNodeKind {
+    Region,
+    Site,
+    Location,
+}
+
+/// entry of the navigation tree: regions contain sites, sites contain locations
+#[derive(SimpleObject)]
+pub struct NavigationNode {
+    kind: NavigationNodeKind,
+    id: u32,
+    name: String,
+    children: Vec<NavigationNode>,
+}
+
+pub async fn navigation_tree() -> Result<Vec<NavigationNode>, BackendError> {
+    let topology = get_topology().await?;
+    let mut roots = topology.list_regions_map(|r| {
+        if r.parent().is_none() {
+            Some(region_node(r, &topology))
+        } else {
+            None
+        }
+    });
+    roots.append(&mut topology.list_sites_map(|s| {
+        if s.region().is_none() {
+            Some(site_node(s, &topology))
+        } else {
+            None
+        }
+    }));
+    Ok(roots)
+}
+
+fn region_node(region: &Arc<Region>, topology: &Arc<Topology>) -> NavigationNode {
+    let mut children: Vec<_> = region
+        .children()
+        .iter()
+        .flat_map(|rid| topology.get_region(*rid))
+        .map(|r| region_node(&r, topology))
+        .collect();
+    children.extend(
+        region
+            .sites()
+            .iter()
+            .flat_map(|sid| topology.get_site(*sid))
+            .map(|s| site_node(s, topology)),
+    );
+    NavigationNode {
+        kind: NavigationNodeKind::Region,
+        id: region.id(),
+        name: region.name().to_string(),
+        children,
+    }
+}
+
+fn site_node(site: &Arc<Site>, topology: &Arc<Topology>) -> NavigationNode {
+    let mut locations: Vec<_> = site
+        .locations()
+        .iter()
+        .flat_map(|lid| topology.get_location(*lid))
+        .filter(|l| l.parent().is_none())
+        .collect();
+    locations.sort_by_key(|l| l.id());
+    NavigationNode {
+        kind: NavigationNodeKind::Site,
+        id: site.id(),
+        name: site.name().to_string(),
+        children: locations
+            .iter()
+            .map(|l| location_node(l, topology))
+            .collect(),
+    }
+}
+
+fn location_node(location: &Arc<Location>, topology: &Arc<Topology>) -> NavigationNode {
+    NavigationNode {
+        kind: NavigationNodeKind::Location,
+        id: location.id(),
+        name: location.name().to_string(),
+        children: location
+            .children()
+            .iter()
+            .flat_map(|lid| topology.get_location(*lid))
+            .map(|l| location_node(&l, topology))
+            .collect(),
+    }
+}
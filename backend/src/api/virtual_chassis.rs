@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use async_graphql::Object;
+
+use crate::api::device::Device;
+use crate::error::BackendError;
+use crate::topology::cache::get_topology;
+use crate::topology::model;
+use crate::topology::model::Topology;
+
+/// stack of devices managed as one
+#[derive(Debug)]
+pub struct VirtualChassis {
+    chassis: Arc<model::virtual_chassis::VirtualChassis>,
+    topology: Arc<Topology>,
+}
+
+impl VirtualChassis {
+    pub fn new(
+        chassis: Arc<model::virtual_chassis::VirtualChassis>,
+        topology: Arc<Topology>,
+    ) -> Self {
+        Self { chassis, topology }
+    }
+    pub fn from_idx(chassis_idx: usize, topology: Arc<Topology>) -> Option<Self> {
+        let chassis = topology.get_virtual_chassis(chassis_idx)?;
+        Some(Self::new(chassis, topology))
+    }
+}
+
+#[Object]
+impl VirtualChassis {
+    /// netbox id of virtual chassis
+    async fn id(&self) -> u32 {
+        self.chassis.id()
+    }
+    async fn name(&self) -> &str {
+        self.chassis.name()
+    }
+    async fn domain(&self) -> &str {
+        self.chassis.domain()
+    }
+    /// device controlling the chassis
+    async fn master(&self) -> Option<Device> {
+        let device = self.topology.get_device(self.chassis.master()?)?;
+        Some(Device::new(device, self.topology.clone()))
+    }
+    /// member devices ordered by their position
+    async fn members(&self) -> Vec<Device> {
+        self.chassis
+            .members()
+            .iter()
+            .flat_map(|idx| self.topology.get_device(*idx))
+            .map(|d| Device::new(d, self.topology.clone()))
+            .collect()
+    }
+}
+
+pub async fn list_virtual_chassis() -> Result<Vec<VirtualChassis>, BackendError> {
+    let topology = get_topology().await?;
+    Ok(topology
+        .list_virtual_chassis_map(|c| Some(VirtualChassis::new(c.clone(), topology.clone()))))
+}
@@ -0,0 +1,160 @@
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use async_graphql::{Enum, Object};
+
+use crate::api::device::{Device, DevicePort};
+use crate::api::site::Site;
+use crate::error::BackendError;
+use crate::topology::cache::get_topology;
+use crate::topology::model;
+use crate::topology::model::Topology;
+
+#[derive(Debug)]
+pub struct Vlan {
+    vlan: Arc<model::Vlan>,
+    vlan_idx: usize,
+    topology: Arc<Topology>,
+}
+
+impl Vlan {
+    pub fn new(vlan: Arc<model::Vlan>, vlan_idx: usize, topology: Arc<Topology>) -> Self {
+        Self {
+            vlan,
+            vlan_idx,
+            topology,
+        }
+    }
+    pub fn from_id(id: u32, topology: Arc<Topology>) -> Option<Self> {
+        let vlan_idx = topology.get_vlan_idx(id)?;
+        topology
+            .get_vlan(vlan_idx)
+            .map(|vlan| Vlan::new(vlan, vlan_idx, topology))
+    }
+}
+
+#[derive(Debug)]
+pub struct VlanGroup {
+    group: Arc<model::VlanGroup>,
+    topology: Arc<Topology>,
+}
+
+impl VlanGroup {
+    pub fn new(group: Arc<model::VlanGroup>, topology: Arc<Topology>) -> Self {
+        Self { group, topology }
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum InterfaceMode {
+    Access,
+    Tagged,
+    TaggedAll,
+}
+
+impl From<model::vlan::InterfaceMode> for InterfaceMode {
+    fn from(value: model::vlan::InterfaceMode) -> Self {
+        match value {
+            model::vlan::InterfaceMode::Access => InterfaceMode::Access,
+            model::vlan::InterfaceMode::Tagged => InterfaceMode::Tagged,
+            model::vlan::InterfaceMode::TaggedAll => InterfaceMode::TaggedAll,
+        }
+    }
+}
+
+pub async fn list_vlans(vid: Option<u16>) -> Result<Vec<Vlan>, BackendError> {
+    let topology = get_topology().await?;
+    Ok(topology.list_vlans_map(|v| {
+        if vid.map(|vid| v.vid() == vid).unwrap_or(true) {
+            Vlan::from_id(v.id(), topology.clone())
+        } else {
+            None
+        }
+    }))
+}
+
+pub async fn get_vlan(id: u32) -> Result<Option<Vlan>, BackendError> {
+    let topology = get_topology().await?;
+    Ok(Vlan::from_id(id, topology))
+}
+
+#[Object]
+impl Vlan {
+    /// netbox id of vlan
+    async fn id(&self) -> u32 {
+        self.vlan.id()
+    }
+    /// 802.1q vlan id
+    async fn vid(&self) -> u16 {
+        self.vlan.vid()
+    }
+    async fn name(&self) -> &str {
+        self.vlan.name()
+    }
+    /// vlan group (if there is any)
+    async fn group(&self) -> Option<VlanGroup> {
+        self.vlan
+            .group()
+            .and_then(|gid| self.topology.get_vlan_group(gid))
+            .map(|g| VlanGroup::new(g, self.topology.clone()))
+    }
+    /// site of vlan (if there is any)
+    async fn site(&self) -> Option<Site> {
+        self.vlan
+            .site()
+            .and_then(|sid| self.topology.get_site(sid))
+            .map(|s| Site::new(s.clone(), self.topology.clone()))
+    }
+    /// interfaces carrying this vlan tagged or untagged
+    async fn ports(&self) -> Vec<DevicePort> {
+        self.topology
+            .ports_of_vlan(self.vlan_idx)
+            .iter()
+            .flat_map(|port| DevicePort::from_idx(*port, self.topology.clone()))
+            .collect()
+    }
+    /// devices with at least one interface in this vlan
+    async fn devices(&self) -> Vec<Device> {
+        self.topology
+            .ports_of_vlan(self.vlan_idx)
+            .iter()
+            .map(|port| port.device_idx())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .flat_map(|idx| self.topology.get_device(idx))
+            .map(|d| Device::new(d, self.topology.clone()))
+            .collect()
+    }
+}
+
+#[Object]
+impl VlanGroup {
+    /// netbox id of vlan group
+    async fn id(&self) -> u32 {
+        self.group.id()
+    }
+    async fn name(&self) -> &str {
+        self.group.name()
+    }
+    async fn slug(&self) -> &str {
+        self.group.slug()
+    }
+    /// site owning this group
+    async fn site(&self) -> Option<Site> {
+        self.topology
+            .get_site(self.group.site())
+            .map(|s| Site::new(s.clone(), self.topology.clone()))
+    }
+    /// vlans of this group
+    async fn vlans(&self) -> Vec<Vlan> {
+        self.group
+            .vlans()
+            .iter()
+            .flat_map(|idx| {
+                self.topology
+                    .get_vlan(*idx)
+                    .map(|v| Vlan::new(v, *idx, self.topology.clone()))
+            })
+            .collect()
+    }
+}
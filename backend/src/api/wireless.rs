@@ -0,0 +1,76 @@
+use async_graphql::{Enum, SimpleObject};
+
+use crate::routeros::wireless;
+
+/// client registered at an access point
+#[derive(SimpleObject)]
+pub struct WirelessClient {
+    mac_address: String,
+    /// wireless interface the client is registered at
+    interface: String,
+    ssid: Option<String>,
+    /// signal strength received from the client in dBm
+    signal_strength: Option<i32>,
+    /// rate towards the client as shown by RouterOS
+    tx_rate: Option<String>,
+    /// rate from the client as shown by RouterOS
+    rx_rate: Option<String>,
+    /// seconds since the client registered
+    uptime_seconds: Option<u64>,
+    /// registration table the client was found in
+    table: RegistrationTable,
+    /// identity of the access point, only known for clients listed by a CAPsMAN controller
+    cap: Option<String>,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum RegistrationTable {
+    Wireless,
+    Wifi,
+    Capsman,
+}
+
+/// clients registered at all access points of a location or site
+#[derive(SimpleObject)]
+pub struct WirelessClientCount {
+    clients: usize,
+    access_points: usize,
+    /// access points whose clients could not be read and are missing in the count
+    unreachable_access_points: usize,
+}
+
+impl From<&wireless::WirelessClient> for WirelessClient {
+    fn from(value: &wireless::WirelessClient) -> Self {
+        Self {
+            mac_address: value.mac_address().to_string(),
+            interface: value.interface().to_string(),
+            ssid: value.ssid().map(str::to_string),
+            signal_strength: value.signal_strength(),
+            tx_rate: value.tx_rate().map(str::to_string),
+            rx_rate: value.rx_rate().map(str::to_string),
+            uptime_seconds: value.uptime().map(|u| u.as_secs()),
+            table: value.table().into(),
+            cap: value.cap().map(str::to_string),
+        }
+    }
+}
+
+impl From<wireless::RegistrationTable> for RegistrationTable {
+    fn from(value: wireless::RegistrationTable) -> Self {
+        match value {
+            wireless::RegistrationTable::Wireless => RegistrationTable::Wireless,
+            wireless::RegistrationTable::Wifi => RegistrationTable::Wifi,
+            wireless::RegistrationTable::Capsman => RegistrationTable::Capsman,
+        }
+    }
+}
+
+impl From<wireless::WirelessClientCount> for WirelessClientCount {
+    fn from(value: wireless::WirelessClientCount) -> Self {
+        Self {
+            clients: value.clients,
+            access_points: value.access_points,
+            unreachable_access_points: value.unreachable_access_points,
+        }
+    }
+}
@@ -1,16 +1,30 @@
 use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use lazy_static::lazy_static;
 
+use crate::error::BackendError;
+use crate::routeros::pool::RouterOsSettings;
+use crate::topology::model::device::DeviceCategory;
+use crate::topology::model::TopologyError;
+use crate::topology::source::TopologySourceType;
+
 #[derive(Debug, Parser)]
+#[command(subcommand_negates_reqs = true)]
 pub struct Settings {
+    /// the server arguments are only required without a command
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// client-id for oauth2
-    #[arg(long, env = "AUTH_CLIENT_ID")]
-    auth_client_id: String,
+    #[arg(long, env = "AUTH_CLIENT_ID", required = true)]
+    auth_client_id: Option<String>,
     /// issuer-url for oauth2
-    #[arg(long, env = "AUTH_ISSUER")]
-    auth_issuer: String,
+    #[arg(long, env = "AUTH_ISSUER", required = true)]
+    auth_issuer: Option<String>,
     /// auth token url for oauth2 (default to "AUTH_ISSUER/protocol/openid-connect/token")
     #[arg(long, env = "AUTH_TOKEN_URL")]
     auth_token_url: Option<String>,
@@ -28,30 +42,135 @@ pub struct Settings {
     #[arg(long, default_value = "::1", env = "SERVER_BIND_ADDR")]
     server_bind_address: IpAddr,
 
+    /// Source of the topology
+    #[arg(long, value_enum, default_value = "netbox", env = "TOPOLOGY_SOURCE")]
+    topology_source: TopologySourceType,
+    /// Snapshot file (.json, .yaml or .yml) to read the topology from
+    #[arg(long, env = "TOPOLOGY_FILE", required_if_eq("topology_source", "file"))]
+    topology_file: Option<PathBuf>,
+
     /// URL of netbox server
-    #[arg(long, env = "NETBOX_ENDPOINT")]
-    netbox_endpoint: String,
+    #[arg(
+        long,
+        env = "NETBOX_ENDPOINT",
+        required_if_eq("topology_source", "netbox")
+    )]
+    netbox_endpoint: Option<String>,
     /// Authentication token of netbox server
-    #[arg(long, env = "NETBOX_TOKEN")]
-    netbox_token: String,
+    #[arg(
+        long,
+        env = "NETBOX_TOKEN",
+        required_if_eq("topology_source", "netbox")
+    )]
+    netbox_token: Option<String>,
+    /// Count of objects fetched from netbox within one request
+    #[arg(long, default_value = "500", env = "NETBOX_PAGE_SIZE")]
+    netbox_page_size: usize,
+    /// Count of requests sent to netbox in parallel
+    #[arg(long, default_value = "4", env = "NETBOX_CONCURRENCY")]
+    netbox_concurrency: usize,
+    /// Secret to check the signature of netbox webhooks, the webhook endpoint is disabled if unset
+    #[arg(long, env = "NETBOX_WEBHOOK_SECRET")]
+    netbox_webhook_secret: Option<String>,
+    /// Seconds between two topology refreshes, the last good topology is kept on errors
+    #[arg(long, default_value = "30", env = "TOPOLOGY_REFRESH_INTERVAL")]
+    topology_refresh_interval: u64,
+    /// Count of detected topology changes kept in the history
+    #[arg(long, default_value = "1000", env = "TOPOLOGY_CHANGE_HISTORY")]
+    topology_change_history: usize,
+    /// User for the RouterOS api
+    #[arg(long, env = "ROUTEROS_USER")]
+    routeros_user: Option<String>,
+    /// Password for the RouterOS api
+    #[arg(long, default_value = "", env = "ROUTEROS_PASSWORD")]
+    routeros_password: String,
+    /// Use the TLS api (api-ssl), the device certificates are not verified
+    #[arg(long, env = "ROUTEROS_TLS")]
+    routeros_tls: bool,
+    /// Port of the RouterOS api (default: 8728, 8729 with TLS)
+    #[arg(long, env = "ROUTEROS_PORT")]
+    routeros_port: Option<u16>,
+    /// Seconds to wait for connecting to a device and for each api command
+    #[arg(long, default_value = "10", env = "ROUTEROS_TIMEOUT")]
+    routeros_timeout: u64,
+    /// Seconds the status fetched from a device is reused for further requests
+    #[arg(long, default_value = "5", env = "ROUTEROS_STATUS_MAX_AGE")]
+    routeros_status_max_age: u64,
+    /// Count of RouterOS devices polled in parallel
+    #[arg(long, default_value = "16", env = "ROUTEROS_CONCURRENCY")]
+    routeros_concurrency: usize,
+    /// Seconds between two polls of the interface counters of all RouterOS devices
+    #[arg(long, default_value = "30", env = "ROUTEROS_TRAFFIC_POLL_INTERVAL")]
+    routeros_traffic_poll_interval: u64,
+    /// Mapping of netbox device role slugs to device categories (role=category,...)
+    #[arg(
+        long,
+        env = "DEVICE_ROLE_CATEGORIES",
+        value_delimiter = ',',
+        default_value = "switch=switch,router=router,patch-panel=patch-panel,server=server,wall-connector=wall-connector,access-point=access-point"
+    )]
+    device_role_categories: Vec<RoleCategory>,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum Command {
+    /// Fetch the current topology from netbox and write it into a snapshot file
+    DumpTopology {
+        /// target file (.json, .yaml or .yml)
+        file: PathBuf,
+    },
+}
+
+/// Category assigned to all devices of a netbox device role
+#[derive(Debug, Clone)]
+pub struct RoleCategory {
+    role: String,
+    category: DeviceCategory,
+}
+
+impl FromStr for RoleCategory {
+    type Err = TopologyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (role, category) = s.split_once('=').unwrap_or((s, s));
+        if role.trim().is_empty() || category.contains('=') {
+            return Err(TopologyError::InvalidRoleMapping(s.to_string()));
+        }
+        Ok(RoleCategory {
+            role: role.trim().to_string(),
+            category: category.trim().parse()?,
+        })
+    }
 }
 
 impl Settings {
-    pub fn auth_client_id(&self) -> &str {
-        &self.auth_client_id
+    pub fn auth_client_id(&self) -> Result<&str, BackendError> {
+        self.auth_client_id
+            .as_deref()
+            .ok_or(BackendError::MissingConfig("AUTH_CLIENT_ID"))
     }
-    pub fn auth_issuer(&self) -> &str {
-        &self.auth_issuer
+    pub fn auth_issuer(&self) -> Result<&str, BackendError> {
+        self.auth_issuer
+            .as_deref()
+            .ok_or(BackendError::MissingConfig("AUTH_ISSUER"))
     }
-    pub fn auth_token_url(&self) -> String {
-        self.auth_token_url
-            .clone()
-            .unwrap_or_else(|| format!("{}/protocol/openid-connect/token", self.auth_issuer))
+    pub fn auth_token_url(&self) -> Result<String, BackendError> {
+        match &self.auth_token_url {
+            Some(url) => Ok(url.clone()),
+            None => Ok(format!(
+                "{}/protocol/openid-connect/token",
+                self.auth_issuer()?
+            )),
+        }
     }
-    pub fn auth_url(&self) -> String {
-        self.auth_url
-            .clone()
-            .unwrap_or_else(|| format!("{}/protocol/openid-connect/auth", self.auth_issuer))
+    pub fn auth_url(&self) -> Result<String, BackendError> {
+        match &self.auth_url {
+            Some(url) => Ok(url.clone()),
+            None => Ok(format!(
+                "{}/protocol/openid-connect/auth",
+                self.auth_issuer()?
+            )),
+        }
     }
     pub fn server_port(&self) -> u16 {
         self.server_port
@@ -63,11 +182,59 @@ impl Settings {
     pub fn server_bind_address(&self) -> &IpAddr {
         &self.server_bind_address
     }
-    pub fn netbox_endpoint(&self) -> &str {
-        &self.netbox_endpoint
+    pub fn command(&self) -> Option<&Command> {
+        self.command.as_ref()
+    }
+    pub fn topology_source(&self) -> TopologySourceType {
+        self.topology_source
+    }
+    pub fn topology_file(&self) -> Option<&Path> {
+        self.topology_file.as_deref()
+    }
+    pub fn netbox_endpoint(&self) -> Option<&str> {
+        self.netbox_endpoint.as_deref()
+    }
+    pub fn netbox_token(&self) -> Option<&str> {
+        self.netbox_token.as_deref()
+    }
+    pub fn netbox_page_size(&self) -> usize {
+        self.netbox_page_size.max(1)
     }
-    pub fn netbox_token(&self) -> &str {
-        &self.netbox_token
+    pub fn netbox_concurrency(&self) -> usize {
+        self.netbox_concurrency.max(1)
+    }
+    pub fn netbox_webhook_secret(&self) -> Option<&str> {
+        self.netbox_webhook_secret.as_deref()
+    }
+    pub fn topology_refresh_interval(&self) -> Duration {
+        Duration::from_secs(self.topology_refresh_interval.max(1))
+    }
+    pub fn topology_change_history(&self) -> usize {
+        self.topology_change_history
+    }
+    pub fn routeros_settings(&self) -> RouterOsSettings {
+        RouterOsSettings::new(
+            self.routeros_user.clone(),
+            self.routeros_password.clone(),
+            self.routeros_tls,
+            self.routeros_port,
+            Duration::from_secs(self.routeros_timeout),
+        )
+    }
+    pub fn routeros_status_max_age(&self) -> Duration {
+        Duration::from_secs(self.routeros_status_max_age)
+    }
+    pub fn routeros_concurrency(&self) -> usize {
+        self.routeros_concurrency.max(1)
+    }
+    pub fn routeros_traffic_poll_interval(&self) -> Duration {
+        Duration::from_secs(self.routeros_traffic_poll_interval.max(1))
+    }
+    pub fn device_category_of_role(&self, role_slug: &str) -> Option<DeviceCategory> {
+        self.device_role_categories
+            .iter()
+            .find(|mapping| mapping.role == role_slug)
+            .map(|mapping| mapping.category)
     }
 }
 
@@ -77,3 +244,68 @@ lazy_static! {
 pub fn config() -> &'static Settings {
     &CONFIG
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use crate::config::{Command, RoleCategory, Settings};
+    use crate::topology::model::device::DeviceCategory;
+    use crate::topology::model::TopologyError;
+
+    #[test]
+    fn test_dump_topology_without_server_arguments() {
+        let settings = Settings::try_parse_from([
+            "mikrotik-status",
+            "--netbox-endpoint",
+            "https://netbox.example.com/graphql/",
+            "--netbox-token",
+            "secret",
+            "dump-topology",
+            "topology.json",
+        ])
+        .unwrap();
+        assert!(matches!(
+            settings.command(),
+            Some(Command::DumpTopology { .. })
+        ));
+        assert!(settings.auth_issuer().is_err());
+        assert!(Settings::try_parse_from([
+            "mikrotik-status",
+            "--netbox-endpoint",
+            "https://netbox.example.com/graphql/",
+            "--netbox-token",
+            "secret",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_role_categories() {
+        let mapping: RoleCategory = "core-switch = switch".parse().unwrap();
+        assert_eq!("core-switch", mapping.role);
+        assert_eq!(DeviceCategory::Switch, mapping.category);
+        // a role without mapping is its own category
+        let mapping: RoleCategory = "wall-connector".parse().unwrap();
+        assert_eq!(DeviceCategory::WallConnector, mapping.category);
+        assert_eq!(
+            DeviceCategory::AccessPoint,
+            "access-point".parse::<DeviceCategory>().unwrap()
+        );
+
+        assert!(matches!(
+            "ap=wifi".parse::<RoleCategory>(),
+            Err(TopologyError::UnknownDeviceCategory(category)) if category == "wifi"
+        ));
+        assert!(matches!(
+            "core-switch".parse::<RoleCategory>(),
+            Err(TopologyError::UnknownDeviceCategory(_))
+        ));
+        for malformed in ["=switch", "ap=", "a=b=switch", ""] {
+            assert!(
+                malformed.parse::<RoleCategory>().is_err(),
+                "{malformed} was accepted"
+            );
+        }
+    }
+}
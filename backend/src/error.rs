@@ -1,11 +1,13 @@
 use std::backtrace::Backtrace;
 use std::error::Error;
 use std::fmt::{Debug, Display, Formatter};
+use std::net::AddrParseError;
 use std::num::ParseIntError;
 use std::sync::Arc;
 
 use thiserror::Error;
 
+use crate::routeros::RouterOsError;
 use crate::topology::query::NetboxError;
 
 pub type Result<T> = std::result::Result<T, BackendError>;
@@ -44,10 +46,17 @@ pub enum BackendError {
         error: ParseIntError,
         backtrace: Arc<Backtrace>,
     },
+    #[error("Error parsing ip address: {error}")]
+    ParseAddress {
+        error: AddrParseError,
+        backtrace: Arc<Backtrace>,
+    },
     #[error("Multiple Errors")]
     Umbrella(Vec<BackendError>),
     #[error("No ip address found")]
     MissingIpAddress(),
+    #[error("Device cannot be pinged")]
+    NotPingable(),
     #[error("No valid device type found")]
     MissingDeviceType(),
     #[error("Error from Netbox: {error}")]
@@ -60,6 +69,30 @@ pub enum BackendError {
         error: Arc<clap::Error>,
         backtrace: Arc<Backtrace>,
     },
+    #[error("Missing config value {0}")]
+    MissingConfig(&'static str),
+    #[error("IO Error: {error}")]
+    Io {
+        error: Arc<std::io::Error>,
+        backtrace: Arc<Backtrace>,
+    },
+    #[error("Error processing json: {error}")]
+    Json {
+        error: Arc<serde_json::Error>,
+        backtrace: Arc<Backtrace>,
+    },
+    #[error("Error processing yaml: {error}")]
+    Yaml {
+        error: Arc<serde_yaml::Error>,
+        backtrace: Arc<Backtrace>,
+    },
+    #[error("Unsupported snapshot file {0}, expected .json, .yaml or .yml")]
+    UnsupportedSnapshotFormat(String),
+    #[error("Error from RouterOS api: {error}")]
+    RouterOs {
+        error: RouterOsError,
+        backtrace: Arc<Backtrace>,
+    },
 }
 
 impl From<&BackendError> for BackendError {
@@ -86,6 +119,15 @@ impl From<NetboxError> for BackendError {
     }
 }
 
+impl From<RouterOsError> for BackendError {
+    fn from(error: RouterOsError) -> Self {
+        BackendError::RouterOs {
+            error,
+            backtrace: Arc::new(Backtrace::force_capture()),
+        }
+    }
+}
+
 impl From<ParseIntError> for BackendError {
     fn from(error: ParseIntError) -> Self {
         BackendError::ParseInt {
@@ -95,6 +137,15 @@ impl From<ParseIntError> for BackendError {
     }
 }
 
+impl From<AddrParseError> for BackendError {
+    fn from(error: AddrParseError) -> Self {
+        BackendError::ParseAddress {
+            error,
+            backtrace: Arc::new(Backtrace::force_capture()),
+        }
+    }
+}
+
 impl From<reqwest::Error> for BackendError {
     fn from(error: reqwest::Error) -> Self {
         BackendError::Reqwest {
@@ -103,3 +154,30 @@ impl From<reqwest::Error> for BackendError {
         }
     }
 }
+
+impl From<std::io::Error> for BackendError {
+    fn from(error: std::io::Error) -> Self {
+        BackendError::Io {
+            error: Arc::new(error),
+            backtrace: Arc::new(Backtrace::force_capture()),
+        }
+    }
+}
+
+impl From<serde_json::Error> for BackendError {
+    fn from(error: serde_json::Error) -> Self {
+        BackendError::Json {
+            error: Arc::new(error),
+            backtrace: Arc::new(Backtrace::force_capture()),
+        }
+    }
+}
+
+impl From<serde_yaml::Error> for BackendError {
+    fn from(error: serde_yaml::Error) -> Self {
+        BackendError::Yaml {
+            error: Arc::new(error),
+            backtrace: Arc::new(Backtrace::force_capture()),
+        }
+    }
+}
@@ -3,4 +3,5 @@ pub mod config;
 
 pub mod context;
 pub mod error;
+pub mod routeros;
 pub mod topology;
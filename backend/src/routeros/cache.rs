@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::error::BackendError;
+
+type CacheSlot<T> = Arc<tokio::sync::Mutex<Option<(Instant, Arc<T>)>>>;
+
+/// values fetched from devices, reused by all requests within the maximum age
+pub struct DeviceStatusCache<T> {
+    max_age: Duration,
+    entries: Mutex<HashMap<u32, CacheSlot<T>>>,
+}
+
+impl<T> DeviceStatusCache<T> {
+    pub fn new(max_age: Duration) -> Self {
+        Self {
+            max_age,
+            entries: Default::default(),
+        }
+    }
+
+    /// cached value of the device with the netbox id, fetched first if it is missing or too old
+    pub async fn get_or_fetch<F, Fut>(
+        &self,
+        device_id: u32,
+        fetch: F,
+    ) -> Result<Arc<T>, BackendError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, BackendError>>,
+    {
+        let slot = self
+            .entries
+            .lock()
+            .unwrap()
+            .entry(device_id)
+            .or_default()
+            .clone();
+        // concurrent requests for the same device wait for a single fetch
+        let mut slot = slot.lock().await;
+        if let Some((fetched_at, value)) = slot.as_ref() {
+            if fetched_at.elapsed() < self.max_age {
+                return Ok(value.clone());
+            }
+        }
+        let value = Arc::new(fetch().await?);
+        *slot = Some((Instant::now(), value.clone()));
+        Ok(value)
+    }
+}
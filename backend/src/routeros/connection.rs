@@ -0,0 +1,384 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use log::{debug, warn};
+use tokio::io::{split, AsyncRead, AsyncWrite, AsyncWriteExt, ReadHalf};
+use tokio::net::TcpStream;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio_rustls::rustls::client::{ServerCertVerified, ServerCertVerifier};
+use tokio_rustls::rustls::{Certificate, ClientConfig, ServerName};
+use tokio_rustls::TlsConnector;
+
+use crate::error::BackendError;
+use crate::routeros::pool::RouterOsSettings;
+use crate::routeros::protocol::{encode_sentence, read_sentence, Command, Reply, ReplyType, Row};
+use crate::routeros::RouterOsError;
+
+type CommandResult = Result<Vec<Row>, RouterOsError>;
+
+/// command waiting for its `!done`
+struct PendingCommand {
+    path: String,
+    rows: Vec<Row>,
+    trap: Option<RouterOsError>,
+    reply: oneshot::Sender<CommandResult>,
+}
+
+/// logged in api session, commands are tagged and may run concurrently
+pub struct Connection {
+    writer: tokio::sync::Mutex<Box<dyn AsyncWrite + Send + Unpin>>,
+    pending: Arc<Mutex<HashMap<u32, PendingCommand>>>,
+    closed: Arc<AtomicBool>,
+    next_tag: AtomicU32,
+    timeout: Duration,
+    /// task reading the replies, owns the read half of the stream
+    reader: JoinHandle<()>,
+}
+
+impl Connection {
+    /// connect and login, using TLS if configured
+    pub async fn connect(
+        address: SocketAddr,
+        settings: &RouterOsSettings,
+    ) -> Result<Arc<Connection>, BackendError> {
+        let timeout = settings.timeout();
+        let stream = tokio::time::timeout(timeout, TcpStream::connect(address))
+            .await
+            .map_err(|_| RouterOsError::Timeout(timeout))??;
+        stream.set_nodelay(true)?;
+        let connection = if settings.tls() {
+            let connector = TlsConnector::from(Arc::new(tls_config()));
+            let stream = tokio::time::timeout(
+                timeout,
+                connector.connect(ServerName::IpAddress(address.ip()), stream),
+            )
+            .await
+            .map_err(|_| RouterOsError::Timeout(timeout))?
+            .map_err(|e| RouterOsError::Tls(e.to_string()))?;
+            Connection::start(stream, timeout)
+        } else {
+            Connection::start(stream, timeout)
+        };
+        if let Err(error) = connection
+            .login(settings.user()?, settings.password())
+            .await
+        {
+            connection.close();
+            return Err(error.into());
+        }
+        debug!("Logged in to RouterOS api on {address}");
+        Ok(connection)
+    }
+
+    /// handle the api protocol on an established stream
+    pub fn start<S>(stream: S, timeout: Duration) -> Arc<Connection>
+    where
+        S: AsyncRead + AsyncWrite + Send + 'static,
+    {
+        let (reader, writer) = split(stream);
+        let pending: Arc<Mutex<HashMap<u32, PendingCommand>>> = Default::default();
+        let closed: Arc<AtomicBool> = Default::default();
+        let reader = tokio::spawn(read_replies(reader, pending.clone(), closed.clone()));
+        Arc::new(Connection {
+            writer: tokio::sync::Mutex::new(Box::new(writer)),
+            pending,
+            closed,
+            next_tag: AtomicU32::new(1),
+            timeout,
+            reader,
+        })
+    }
+
+    async fn login(&self, user: &str, password: &str) -> Result<(), RouterOsError> {
+        let result = self
+            .execute(
+                &Command::new("/login")
+                    .attribute("name", user)
+                    .attribute("password", password),
+            )
+            .await;
+        match result {
+            Ok(rows) if rows.iter().any(|row| row.contains_key("ret")) => Err(
+                RouterOsError::Login("challenge login of RouterOS before 6.43".to_string()),
+            ),
+            Ok(_) => Ok(()),
+            Err(RouterOsError::Trap { message, .. }) => Err(RouterOsError::Login(message)),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// send a command and collect its `!re` replies
+    pub async fn execute(&self, command: &Command) -> CommandResult {
+        let tag = self.next_tag.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = oneshot::channel();
+        {
+            let mut pending = self.pending.lock().unwrap();
+            // checked under the lock, the reader fails all pending commands when closing
+            if self.is_closed() {
+                return Err(RouterOsError::ConnectionClosed);
+            }
+            pending.insert(
+                tag,
+                PendingCommand {
+                    path: command.path().to_string(),
+                    rows: vec![],
+                    trap: None,
+                    reply: sender,
+                },
+            );
+        }
+        if let Err(error) = self.send(&command.words_with_tag(tag)).await {
+            self.pending.lock().unwrap().remove(&tag);
+            warn!("Cannot send RouterOS command: {error}");
+            self.closed.store(true, Ordering::Relaxed);
+            return Err(RouterOsError::ConnectionClosed);
+        }
+        match tokio::time::timeout(self.timeout, receiver).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(RouterOsError::ConnectionClosed),
+            Err(_) => {
+                self.pending.lock().unwrap().remove(&tag);
+                // best effort, the interrupted command answers with a trap nobody waits for
+                let cancel = Command::new("/cancel").attribute("tag", &tag.to_string());
+                let cancel_tag = self.next_tag.fetch_add(1, Ordering::Relaxed);
+                let _ = self.send(&cancel.words_with_tag(cancel_tag)).await;
+                Err(RouterOsError::Timeout(self.timeout))
+            }
+        }
+    }
+
+    /// true if the device or the network closed the connection
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Relaxed)
+    }
+
+    /// stop reading replies, the stream is closed with the last half dropped
+    fn close(&self) {
+        self.reader.abort();
+        let mut pending = self.pending.lock().unwrap();
+        self.closed.store(true, Ordering::Relaxed);
+        for (_, command) in pending.drain() {
+            let _ = command.reply.send(Err(RouterOsError::ConnectionClosed));
+        }
+    }
+
+    async fn send(&self, words: &[String]) -> std::io::Result<()> {
+        let mut writer = self.writer.lock().await;
+        writer.write_all(&encode_sentence(words)).await?;
+        writer.flush().await
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+async fn read_replies<S: AsyncRead>(
+    mut reader: ReadHalf<S>,
+    pending: Arc<Mutex<HashMap<u32, PendingCommand>>>,
+    closed: Arc<AtomicBool>,
+) {
+    let error = loop {
+        let reply = match read_sentence(&mut reader).await {
+            Ok(words) => Reply::parse(words),
+            Err(error) => {
+                debug!("RouterOS connection closed: {error}");
+                break RouterOsError::ConnectionClosed;
+            }
+        };
+        let reply = match reply {
+            Ok(reply) => reply,
+            Err(error) => break error,
+        };
+        if reply.reply_type() == ReplyType::Fatal {
+            break RouterOsError::Fatal(reply.message());
+        }
+        let Some(tag) = reply.tag() else {
+            warn!("Ignoring untagged RouterOS reply {reply:?}");
+            continue;
+        };
+        let mut pending = pending.lock().unwrap();
+        match reply.reply_type() {
+            ReplyType::Re => {
+                if let Some(command) = pending.get_mut(&tag) {
+                    command.rows.push(reply.into_attributes());
+                }
+            }
+            ReplyType::Trap => {
+                if let Some(command) = pending.get_mut(&tag) {
+                    command.trap = Some(RouterOsError::Trap {
+                        command: command.path.clone(),
+                        category: reply.category(),
+                        message: reply.message(),
+                    });
+                }
+            }
+            ReplyType::Done => {
+                if let Some(command) = pending.remove(&tag) {
+                    let result = match command.trap {
+                        Some(trap) => Err(trap),
+                        None => Ok(command.rows),
+                    };
+                    let _ = command.reply.send(result);
+                }
+            }
+            ReplyType::Empty | ReplyType::Fatal => {}
+        }
+    };
+    let mut pending = pending.lock().unwrap();
+    closed.store(true, Ordering::Relaxed);
+    for (_, command) in pending.drain() {
+        let _ = command.reply.send(Err(error.clone()));
+    }
+}
+
+/// RouterOS creates self signed certificates for addresses, so the certificate is not verified
+struct AcceptDeviceCertificate;
+
+impl ServerCertVerifier for AcceptDeviceCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, tokio_rustls::rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+fn tls_config() -> ClientConfig {
+    ClientConfig::builder()
+        .with_safe_defaults()
+        .with_custom_certificate_verifier(Arc::new(AcceptDeviceCertificate))
+        .with_no_client_auth()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::error::BackendError;
+    use crate::routeros::connection::Connection;
+    use crate::routeros::mock::{MockRouter, RunningMock};
+    use crate::routeros::pool::RouterOsSettings;
+    use crate::routeros::protocol::Command;
+    use crate::routeros::RouterOsError;
+
+    #[tokio::test]
+    async fn test_concurrent_tagged_commands() {
+        let mock = MockRouter::new("admin", "secret")
+            .delayed_reply(
+                "/system/resource/print",
+                vec![vec![("uptime", "1d2h"), ("cpu-load", "3")]],
+                Duration::from_millis(200),
+            )
+            .reply(
+                "/interface/print",
+                vec![vec![("name", "ether1")], vec![("name", "ether2")]],
+            )
+            .trap("/tool/fetch", 1, "invalid url")
+            .start()
+            .await;
+        let settings = RouterOsSettings::new(
+            Some("admin".to_string()),
+            "secret".to_string(),
+            false,
+            Some(mock.address().port()),
+            Duration::from_secs(2),
+        );
+        let connection = Connection::connect(mock.address(), &settings)
+            .await
+            .unwrap();
+
+        let resource = Command::new("/system/resource/print");
+        let interfaces = Command::new("/interface/print").proplist(&["name"]);
+        let fetch = Command::new("/tool/fetch").attribute("url", "nowhere");
+        let (resource, interfaces, fetch) = tokio::join!(
+            connection.execute(&resource),
+            connection.execute(&interfaces),
+            connection.execute(&fetch)
+        );
+        assert_eq!("1d2h", resource.unwrap()[0]["uptime"]);
+        assert_eq!(2, interfaces.unwrap().len());
+        match fetch {
+            Err(RouterOsError::Trap {
+                command,
+                category,
+                message,
+            }) => {
+                assert_eq!("/tool/fetch", command);
+                assert_eq!(Some(1), category);
+                assert_eq!("invalid url", message);
+            }
+            other => panic!("expected trap, got {other:?}"),
+        }
+
+        assert!(connection.execute(&Command::new("/quit")).await.is_err());
+        assert!(connection.is_closed());
+    }
+
+    /// wait for the mock to see the client closing its connections
+    async fn wait_for_closed_connections(mock: &RunningMock) {
+        for _ in 0..100 {
+            if mock.open_connections() == 0 {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("{} connections left open", mock.open_connections());
+    }
+
+    #[tokio::test]
+    async fn test_close_connection_on_drop_and_failed_login() {
+        let mock = MockRouter::new("admin", "secret")
+            .reply("/system/identity/print", vec![vec![("name", "rt01")]])
+            .start()
+            .await;
+
+        let result = Connection::connect(mock.address(), &mock.settings("wrong")).await;
+        assert!(matches!(
+            result,
+            Err(BackendError::RouterOs {
+                error: RouterOsError::Login(_),
+                ..
+            })
+        ));
+        wait_for_closed_connections(&mock).await;
+
+        let connection = Connection::connect(mock.address(), &mock.settings("secret"))
+            .await
+            .unwrap();
+        assert_eq!(1, mock.open_connections());
+        drop(connection);
+        wait_for_closed_connections(&mock).await;
+    }
+
+    #[tokio::test]
+    async fn test_connect_with_tls() {
+        let mock = MockRouter::new("admin", "secret")
+            .tls()
+            .reply("/system/identity/print", vec![vec![("name", "rt01")]])
+            .start()
+            .await;
+        let settings = mock.settings("secret");
+        assert!(settings.tls());
+        let connection = Connection::connect(mock.address(), &settings)
+            .await
+            .unwrap();
+        let rows = connection
+            .execute(&Command::new("/system/identity/print"))
+            .await
+            .unwrap();
+        assert_eq!("rt01", rows[0]["name"]);
+        assert_eq!(1, mock.logins());
+    }
+}
@@ -0,0 +1,219 @@
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use lazy_static::lazy_static;
+
+use crate::config::config;
+use crate::error::BackendError;
+use crate::routeros::cache::DeviceStatusCache;
+use crate::routeros::pool::{routeros_pool, ConnectionPool};
+use crate::routeros::protocol::{parse_duration, Command, Row};
+use crate::routeros::RouterOsError;
+use crate::topology::model::Device;
+
+lazy_static! {
+    static ref HEALTH_CACHE: DeviceStatusCache<HealthReport> =
+        DeviceStatusCache::new(config().routeros_status_max_age());
+}
+
+/// resources and sensor values of a RouterOS device
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DeviceHealth {
+    cpu_load: Option<u32>,
+    free_memory: Option<u64>,
+    total_memory: Option<u64>,
+    free_disk: Option<u64>,
+    total_disk: Option<u64>,
+    uptime: Option<Duration>,
+    board_name: Option<String>,
+    version: Option<String>,
+    voltage: Option<f64>,
+    temperature: Option<f64>,
+}
+
+/// outcome of a single health fetch, errors are reported instead of failing the request
+#[derive(Clone, Debug)]
+pub struct HealthReport {
+    fetched_at: SystemTime,
+    result: Result<DeviceHealth, BackendError>,
+}
+
+impl DeviceHealth {
+    fn from_rows(resource: &Row, health: &[Row]) -> Self {
+        let mut device_health = Self {
+            cpu_load: parse(resource, "cpu-load"),
+            free_memory: parse(resource, "free-memory"),
+            total_memory: parse(resource, "total-memory"),
+            free_disk: parse(resource, "free-hdd-space"),
+            total_disk: parse(resource, "total-hdd-space"),
+            uptime: resource.get("uptime").and_then(|u| parse_duration(u)),
+            board_name: resource.get("board-name").cloned(),
+            version: resource.get("version").cloned(),
+            voltage: None,
+            temperature: None,
+        };
+        for row in health {
+            match row.get("name") {
+                // RouterOS 7 returns one row per sensor
+                Some(name) => {
+                    let value = parse(row, "value");
+                    match name.as_str() {
+                        "voltage" => device_health.voltage = value,
+                        "temperature" => device_health.temperature = value,
+                        "cpu-temperature" => {
+                            device_health.temperature = device_health.temperature.or(value)
+                        }
+                        _ => {}
+                    }
+                }
+                // RouterOS 6 returns all sensors as attributes of a single row
+                None => {
+                    device_health.voltage = parse(row, "voltage");
+                    device_health.temperature =
+                        parse(row, "temperature").or_else(|| parse(row, "cpu-temperature"));
+                }
+            }
+        }
+        device_health
+    }
+    /// cpu usage in percent
+    pub fn cpu_load(&self) -> Option<u32> {
+        self.cpu_load
+    }
+    /// free memory in bytes
+    pub fn free_memory(&self) -> Option<u64> {
+        self.free_memory
+    }
+    pub fn total_memory(&self) -> Option<u64> {
+        self.total_memory
+    }
+    /// free storage in bytes
+    pub fn free_disk(&self) -> Option<u64> {
+        self.free_disk
+    }
+    pub fn total_disk(&self) -> Option<u64> {
+        self.total_disk
+    }
+    pub fn uptime(&self) -> Option<Duration> {
+        self.uptime
+    }
+    pub fn board_name(&self) -> Option<&str> {
+        self.board_name.as_deref()
+    }
+    /// RouterOS version including the channel, e.g. `7.7 (stable)`
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+    /// supply voltage in V, only reported by devices with sensors
+    pub fn voltage(&self) -> Option<f64> {
+        self.voltage
+    }
+    /// board or cpu temperature in °C
+    pub fn temperature(&self) -> Option<f64> {
+        self.temperature
+    }
+}
+
+impl HealthReport {
+    pub fn fetched_at(&self) -> SystemTime {
+        self.fetched_at
+    }
+    pub fn health(&self) -> Option<&DeviceHealth> {
+        self.result.as_ref().ok()
+    }
+    pub fn error(&self) -> Option<&BackendError> {
+        self.result.as_ref().err()
+    }
+}
+
+fn parse<T: std::str::FromStr>(row: &Row, name: &str) -> Option<T> {
+    row.get(name)?.parse().ok()
+}
+
+/// read `/system/resource` and `/system/health` of the device
+pub async fn fetch_health(
+    pool: &ConnectionPool,
+    device: &Device,
+) -> Result<DeviceHealth, BackendError> {
+    let connection = pool.connection(device).await?;
+    let resource = Command::new("/system/resource/print");
+    let health = Command::new("/system/health/print");
+    let (resource, health) =
+        tokio::join!(connection.execute(&resource), connection.execute(&health));
+    let health = match health {
+        Ok(rows) => rows,
+        // devices without sensors, e.g. virtual routers, have no health menu
+        Err(RouterOsError::Trap { .. }) => vec![],
+        Err(error) => return Err(error.into()),
+    };
+    let resource = resource?.into_iter().next().unwrap_or_default();
+    Ok(DeviceHealth::from_rows(&resource, &health))
+}
+
+/// recently fetched health of the device, including the error of the last fetch
+pub async fn device_health(device: &Device) -> Result<Arc<HealthReport>, BackendError> {
+    HEALTH_CACHE
+        .get_or_fetch(device.id(), || async {
+            Ok(HealthReport {
+                result: fetch_health(routeros_pool(), device).await,
+                fetched_at: SystemTime::now(),
+            })
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::routeros::health::fetch_health;
+    use crate::routeros::mock::{mock_device, MockRouter};
+
+    #[tokio::test]
+    async fn test_fetch_health() {
+        let mock = MockRouter::new("admin", "secret")
+            .reply(
+                "/system/resource/print",
+                vec![vec![
+                    ("uptime", "1w2d3h4m5s"),
+                    ("version", "7.7 (stable)"),
+                    ("cpu-load", "12"),
+                    ("free-memory", "872349696"),
+                    ("total-memory", "1073741824"),
+                    ("free-hdd-space", "970735616"),
+                    ("total-hdd-space", "1073741824"),
+                    ("board-name", "RB5009UG+S+"),
+                ]],
+            )
+            .reply(
+                "/system/health/print",
+                vec![
+                    vec![("name", "voltage"), ("value", "24.1"), ("type", "V")],
+                    vec![("name", "cpu-temperature"), ("value", "47"), ("type", "C")],
+                ],
+            )
+            .start()
+            .await;
+        let health = fetch_health(&mock.pool(), &mock_device()).await.unwrap();
+        assert_eq!(Some(12), health.cpu_load());
+        assert_eq!(Some(872349696), health.free_memory());
+        assert_eq!(Some(970735616), health.free_disk());
+        assert_eq!(
+            Some(Duration::from_secs(9 * 24 * 3600 + 3 * 3600 + 4 * 60 + 5)),
+            health.uptime()
+        );
+        assert_eq!(Some("RB5009UG+S+"), health.board_name());
+        assert_eq!(Some("7.7 (stable)"), health.version());
+        assert_eq!(Some(24.1), health.voltage());
+        assert_eq!(Some(47.0), health.temperature());
+
+        // virtual routers have no sensors
+        let mock = MockRouter::new("admin", "secret")
+            .reply("/system/resource/print", vec![vec![("cpu-load", "1")]])
+            .start()
+            .await;
+        let health = fetch_health(&mock.pool(), &mock_device()).await.unwrap();
+        assert_eq!(Some(1), health.cpu_load());
+        assert_eq!(None, health.temperature());
+    }
+}
@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use lazy_static::lazy_static;
+
+use crate::config::config;
+use crate::error::BackendError;
+use crate::routeros::cache::DeviceStatusCache;
+use crate::routeros::pool::{routeros_pool, ConnectionPool};
+use crate::routeros::protocol::{Command, Row};
+use crate::routeros::RouterOsError;
+use crate::topology::model::Device;
+
+lazy_static! {
+    static ref INTERFACE_STATUS_CACHE: DeviceStatusCache<InterfaceStatusReport> =
+        DeviceStatusCache::new(config().routeros_status_max_age());
+}
+
+/// operational state of an interface as reported by RouterOS
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InterfaceStatus {
+    name: String,
+    running: bool,
+    disabled: bool,
+    rate: Option<String>,
+    full_duplex: Option<bool>,
+    last_link_up_time: Option<String>,
+    link_downs: u32,
+}
+
+/// outcome of a single status fetch of all interfaces, errors are cached like values
+#[derive(Clone, Debug)]
+pub struct InterfaceStatusReport {
+    fetched_at: SystemTime,
+    result: Result<HashMap<String, InterfaceStatus>, BackendError>,
+}
+
+impl InterfaceStatus {
+    fn from_row(row: &Row) -> Option<Self> {
+        Some(Self {
+            name: row.get("name")?.clone(),
+            running: flag(row, "running"),
+            disabled: flag(row, "disabled"),
+            rate: None,
+            full_duplex: None,
+            last_link_up_time: row
+                .get("last-link-up-time")
+                .filter(|t| !t.is_empty())
+                .cloned(),
+            link_downs: row
+                .get("link-downs")
+                .and_then(|count| count.parse().ok())
+                .unwrap_or_default(),
+        })
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn running(&self) -> bool {
+        self.running
+    }
+    pub fn disabled(&self) -> bool {
+        self.disabled
+    }
+    /// negotiated rate as shown by RouterOS, e.g. `1Gbps`, only known for running ethernet ports
+    pub fn rate(&self) -> Option<&str> {
+        self.rate.as_deref()
+    }
+    /// negotiated rate in Mbit/s
+    pub fn speed_mbps(&self) -> Option<u32> {
+        let rate = self.rate.as_deref()?;
+        let (value, factor) = if let Some(value) = rate.strip_suffix("Gbps") {
+            (value, 1000.0)
+        } else {
+            (rate.strip_suffix("Mbps")?, 1.0)
+        };
+        value.parse::<f64>().ok().map(|v| (v * factor) as u32)
+    }
+    pub fn full_duplex(&self) -> Option<bool> {
+        self.full_duplex
+    }
+    /// time of the last link up in the format of the device clock
+    pub fn last_link_up_time(&self) -> Option<&str> {
+        self.last_link_up_time.as_deref()
+    }
+    /// count of link downs since the last reboot
+    pub fn link_downs(&self) -> u32 {
+        self.link_downs
+    }
+}
+
+impl InterfaceStatusReport {
+    pub fn fetched_at(&self) -> SystemTime {
+        self.fetched_at
+    }
+    /// status of all interfaces by name, missing if the fetch failed
+    pub fn interfaces(&self) -> Option<&HashMap<String, InterfaceStatus>> {
+        self.result.as_ref().ok()
+    }
+    pub fn get(&self, name: &str) -> Option<&InterfaceStatus> {
+        self.interfaces()?.get(name)
+    }
+    pub fn error(&self) -> Option<&BackendError> {
+        self.result.as_ref().err()
+    }
+}
+
+fn flag(row: &Row, name: &str) -> bool {
+    row.get(name).map(|value| value == "true").unwrap_or(false)
+}
+
+/// status of all interfaces of the device by interface name
+pub async fn fetch_interface_status(
+    pool: &ConnectionPool,
+    device: &Device,
+) -> Result<HashMap<String, InterfaceStatus>, BackendError> {
+    let connection = pool.connection(device).await?;
+    let rows = connection
+        .execute(&Command::new("/interface/print").proplist(&[
+            "name",
+            "type",
+            "running",
+            "disabled",
+            "last-link-up-time",
+            "link-downs",
+        ]))
+        .await?;
+    let running_ethernet: Vec<&str> = rows
+        .iter()
+        .filter(|row| {
+            row.get("type").map(|t| t == "ether").unwrap_or(false) && flag(row, "running")
+        })
+        .flat_map(|row| row.get("name"))
+        .map(|name| name.as_str())
+        .collect();
+    let mut interfaces: HashMap<String, InterfaceStatus> = rows
+        .iter()
+        .flat_map(InterfaceStatus::from_row)
+        .map(|status| (status.name.clone(), status))
+        .collect();
+    if running_ethernet.is_empty() {
+        return Ok(interfaces);
+    }
+    let monitor = match connection
+        .execute(
+            &Command::new("/interface/ethernet/monitor")
+                .attribute("numbers", &running_ethernet.join(","))
+                .attribute("once", "")
+                .proplist(&["name", "rate", "full-duplex"]),
+        )
+        .await
+    {
+        Ok(rows) => rows,
+        // the rate is optional, busy switches may not answer the monitor in time
+        Err(RouterOsError::Trap { .. } | RouterOsError::Timeout(_)) => vec![],
+        Err(error) => return Err(error.into()),
+    };
+    for row in monitor {
+        let Some(status) = row.get("name").and_then(|name| interfaces.get_mut(name)) else {
+            continue;
+        };
+        status.rate = row.get("rate").cloned();
+        status.full_duplex = row.get("full-duplex").map(|value| value == "true");
+    }
+    Ok(interfaces)
+}
+
+async fn fetch_report(
+    pool: &ConnectionPool,
+    device: &Device,
+) -> Result<InterfaceStatusReport, BackendError> {
+    Ok(InterfaceStatusReport {
+        result: fetch_interface_status(pool, device).await,
+        fetched_at: SystemTime::now(),
+    })
+}
+
+/// recently fetched status of all interfaces of the device, including the error of the last fetch
+pub async fn interface_status(device: &Device) -> Result<Arc<InterfaceStatusReport>, BackendError> {
+    INTERFACE_STATUS_CACHE
+        .get_or_fetch(device.id(), || fetch_report(routeros_pool(), device))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use crate::routeros::cache::DeviceStatusCache;
+    use crate::routeros::interface::{fetch_interface_status, fetch_report};
+    use crate::routeros::mock::{mock_device, MockRouter};
+
+    #[tokio::test]
+    async fn test_fetch_interface_status() {
+        let mock = MockRouter::new("admin", "secret")
+            .reply(
+                "/interface/print",
+                vec![
+                    vec![
+                        ("name", "ether1"),
+                        ("type", "ether"),
+                        ("running", "true"),
+                        ("disabled", "false"),
+                        ("last-link-up-time", "2023-01-12 09:31:07"),
+                        ("link-downs", "3"),
+                    ],
+                    vec![
+                        ("name", "ether2"),
+                        ("type", "ether"),
+                        ("running", "false"),
+                        ("disabled", "true"),
+                        ("link-downs", "0"),
+                    ],
+                    vec![
+                        ("name", "loopback"),
+                        ("type", "bridge"),
+                        ("running", "true"),
+                        ("disabled", "false"),
+                    ],
+                ],
+            )
+            .reply(
+                "/interface/ethernet/monitor",
+                vec![vec![
+                    ("name", "ether1"),
+                    ("rate", "2.5Gbps"),
+                    ("full-duplex", "true"),
+                ]],
+            )
+            .start()
+            .await;
+        let status = fetch_interface_status(&mock.pool(), &mock_device())
+            .await
+            .unwrap();
+        assert_eq!(3, status.len());
+        let ether1 = &status["ether1"];
+        assert!(ether1.running());
+        assert_eq!(Some(2500), ether1.speed_mbps());
+        assert_eq!(Some(true), ether1.full_duplex());
+        assert_eq!(Some("2023-01-12 09:31:07"), ether1.last_link_up_time());
+        assert_eq!(3, ether1.link_downs());
+        let ether2 = &status["ether2"];
+        assert!(ether2.disabled());
+        assert_eq!(None, ether2.speed_mbps());
+        assert_eq!(None, ether2.last_link_up_time());
+    }
+
+    #[tokio::test]
+    async fn test_cache_failed_fetch() {
+        let mock = MockRouter::new("admin", "other").start().await;
+        let pool = mock.pool();
+        let device = mock_device();
+        let cache = DeviceStatusCache::new(Duration::from_secs(60));
+        let fetches = AtomicUsize::new(0);
+        // every port of the device asks for the status of its interface
+        for _ in 0..3 {
+            let report = cache
+                .get_or_fetch(device.id(), || {
+                    fetches.fetch_add(1, Ordering::SeqCst);
+                    fetch_report(&pool, &device)
+                })
+                .await
+                .unwrap();
+            assert!(report.error().is_some());
+            assert_eq!(None, report.get("ether1"));
+        }
+        assert_eq!(1, fetches.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_keep_status_without_monitor() {
+        // the mock traps commands it has no reply for, like the monitor here
+        let mock = MockRouter::new("admin", "secret")
+            .reply(
+                "/interface/print",
+                vec![vec![
+                    ("name", "ether1"),
+                    ("type", "ether"),
+                    ("running", "true"),
+                    ("disabled", "false"),
+                    ("link-downs", "1"),
+                ]],
+            )
+            .start()
+            .await;
+        let status = fetch_interface_status(&mock.pool(), &mock_device())
+            .await
+            .unwrap();
+        let ether1 = &status["ether1"];
+        assert!(ether1.running());
+        assert_eq!(1, ether1.link_downs());
+        assert_eq!(None, ether1.rate());
+        assert_eq!(None, ether1.full_duplex());
+    }
+}
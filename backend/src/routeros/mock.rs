@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::io::{split, AsyncRead, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::Mutex;
+use tokio_rustls::rustls::{Certificate, PrivateKey, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+
+use crate::routeros::pool::{ConnectionPool, RouterOsSettings};
+use crate::routeros::protocol::{encode_sentence, read_sentence, Row};
+use crate::topology::model::device::{AddressRole, DeviceBuilder, InterfaceAddress};
+use crate::topology::model::{Device, DeviceType, Topology};
+
+/// answer of the mock to a command path
+#[derive(Clone, Debug)]
+enum MockReply {
+    Rows(Vec<Row>, Duration),
+    Trap(u32, String),
+}
+
+/// RouterOS api server on localhost answering with prepared replies
+pub(crate) struct MockRouter {
+    user: String,
+    password: String,
+    replies: HashMap<String, MockReply>,
+    tls: bool,
+}
+
+/// running mock, stops with the test runtime
+pub(crate) struct RunningMock {
+    address: SocketAddr,
+    logins: Arc<AtomicUsize>,
+    open_connections: Arc<AtomicUsize>,
+    tls: bool,
+}
+
+impl MockRouter {
+    pub fn new(user: &str, password: &str) -> Self {
+        Self {
+            user: user.to_string(),
+            password: password.to_string(),
+            replies: HashMap::new(),
+            tls: false,
+        }
+    }
+    /// accept api-ssl connections with a self signed certificate
+    pub fn tls(mut self) -> Self {
+        self.tls = true;
+        self
+    }
+    /// answer `path` with one `!re` per row
+    pub fn reply(self, path: &str, rows: Vec<Vec<(&str, &str)>>) -> Self {
+        self.delayed_reply(path, rows, Duration::ZERO)
+    }
+    /// answer `path` after the delay, other commands are answered in the meantime
+    pub fn delayed_reply(
+        mut self,
+        path: &str,
+        rows: Vec<Vec<(&str, &str)>>,
+        delay: Duration,
+    ) -> Self {
+        let rows = rows
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect()
+            })
+            .collect();
+        self.replies
+            .insert(path.to_string(), MockReply::Rows(rows, delay));
+        self
+    }
+    pub fn trap(mut self, path: &str, category: u32, message: &str) -> Self {
+        self.replies.insert(
+            path.to_string(),
+            MockReply::Trap(category, message.to_string()),
+        );
+        self
+    }
+
+    pub async fn start(self) -> RunningMock {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let logins = Arc::new(AtomicUsize::new(0));
+        let open_connections = Arc::new(AtomicUsize::new(0));
+        let acceptor = self.tls.then(|| TlsAcceptor::from(Arc::new(tls_config())));
+        let mock = Arc::new(self);
+        let running = RunningMock {
+            address,
+            logins: logins.clone(),
+            open_connections: open_connections.clone(),
+            tls: mock.tls,
+        };
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let mock = mock.clone();
+                let logins = logins.clone();
+                let open_connections = open_connections.clone();
+                let acceptor = acceptor.clone();
+                tokio::spawn(async move {
+                    open_connections.fetch_add(1, Ordering::SeqCst);
+                    match acceptor {
+                        Some(acceptor) => {
+                            if let Ok(stream) = acceptor.accept(stream).await {
+                                mock.serve(stream, logins).await;
+                            }
+                        }
+                        None => mock.serve(stream, logins).await,
+                    }
+                    open_connections.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+        running
+    }
+
+    async fn serve<S>(self: Arc<Self>, stream: S, logins: Arc<AtomicUsize>)
+    where
+        S: AsyncRead + AsyncWrite + Send + 'static,
+    {
+        let (mut reader, writer) = split(stream);
+        let writer = Arc::new(Mutex::new(writer));
+        let mut logged_in = false;
+        while let Ok(words) = read_sentence(&mut reader).await {
+            let path = words.first().cloned().unwrap_or_default();
+            let mut attributes = HashMap::new();
+            let mut tag = None;
+            for word in &words[1..] {
+                if let Some(value) = word.strip_prefix(".tag=") {
+                    tag = Some(value.to_string());
+                } else if let Some((name, value)) =
+                    word.strip_prefix('=').and_then(|w| w.split_once('='))
+                {
+                    attributes.insert(name.to_string(), value.to_string());
+                }
+            }
+            let reply = match path.as_str() {
+                "/login" => {
+                    if attributes.get("name") == Some(&self.user)
+                        && attributes.get("password") == Some(&self.password)
+                    {
+                        logged_in = true;
+                        logins.fetch_add(1, Ordering::SeqCst);
+                        MockReply::Rows(vec![], Duration::ZERO)
+                    } else {
+                        MockReply::Trap(5, "invalid user name or password (6)".to_string())
+                    }
+                }
+                "/quit" => {
+                    let mut writer = writer.lock().await;
+                    let _ = write(&mut *writer, &["!fatal", "session terminated on request"]).await;
+                    return;
+                }
+                _ if !logged_in => MockReply::Trap(5, "not logged in".to_string()),
+                "/cancel" => MockReply::Rows(vec![], Duration::ZERO),
+                path => self
+                    .replies
+                    .get(path)
+                    .cloned()
+                    .unwrap_or_else(|| MockReply::Trap(0, "no such command".to_string())),
+            };
+            tokio::spawn(answer(writer.clone(), tag, reply));
+        }
+    }
+}
+
+impl RunningMock {
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+    /// count of successful logins
+    pub fn logins(&self) -> usize {
+        self.logins.load(Ordering::SeqCst)
+    }
+    /// count of connections not yet closed by the client
+    pub fn open_connections(&self) -> usize {
+        self.open_connections.load(Ordering::SeqCst)
+    }
+    /// settings logging in as `admin` with `password`
+    pub fn settings(&self, password: &str) -> RouterOsSettings {
+        RouterOsSettings::new(
+            Some("admin".to_string()),
+            password.to_string(),
+            self.tls,
+            Some(self.address.port()),
+            Duration::from_secs(2),
+        )
+    }
+    /// pool logging in as `admin` with password `secret`
+    pub fn pool(&self) -> ConnectionPool {
+        ConnectionPool::new(self.settings("secret"))
+    }
+}
+
+/// RouterOS device with the loopback address 127.0.0.1 the mock listens on
+pub(crate) fn mock_device() -> Arc<Device> {
+    let mut topology_builder = Topology::builder();
+    topology_builder.append_device_type(DeviceType::new("rb5009".to_string(), 1, true));
+    let mut device_builder = DeviceBuilder::new(1, "rt01".to_string(), true);
+    device_builder.set_device_type(1);
+    device_builder.append_interface(
+        1,
+        "loopback".to_string(),
+        vec![InterfaceAddress::new(
+            "127.0.0.1/32".parse().unwrap(),
+            AddressRole::Loopback,
+            None,
+        )],
+    );
+    let device_idx = topology_builder.append_device(device_builder);
+    let topology = topology_builder.build().unwrap();
+    topology.get_device(device_idx).unwrap()
+}
+
+/// self signed certificate for 127.0.0.1, like the one RouterOS creates for api-ssl
+fn tls_config() -> ServerConfig {
+    let certificates = rustls_pemfile::certs(&mut &include_bytes!("mock_cert.pem")[..])
+        .unwrap()
+        .into_iter()
+        .map(Certificate)
+        .collect();
+    let key = rustls_pemfile::pkcs8_private_keys(&mut &include_bytes!("mock_key.pem")[..])
+        .unwrap()
+        .remove(0);
+    ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certificates, PrivateKey(key))
+        .unwrap()
+}
+
+async fn answer<W: AsyncWrite + Unpin>(
+    writer: Arc<Mutex<W>>,
+    tag: Option<String>,
+    reply: MockReply,
+) {
+    let tag_word = tag.map(|t| format!(".tag={t}"));
+    let mut sentences = Vec::new();
+    match reply {
+        MockReply::Rows(rows, delay) => {
+            tokio::time::sleep(delay).await;
+            for row in rows {
+                let mut words = vec!["!re".to_string()];
+                words.extend(row.into_iter().map(|(k, v)| format!("={k}={v}")));
+                sentences.push(words);
+            }
+        }
+        MockReply::Trap(category, message) => sentences.push(vec![
+            "!trap".to_string(),
+            format!("=category={category}"),
+            format!("=message={message}"),
+        ]),
+    }
+    sentences.push(vec!["!done".to_string()]);
+    let mut writer = writer.lock().await;
+    for mut words in sentences {
+        words.extend(tag_word.clone());
+        if write(&mut *writer, &words).await.is_err() {
+            return;
+        }
+    }
+}
+
+async fn write<W: AsyncWrite + Unpin, S: AsRef<str>>(
+    writer: &mut W,
+    words: &[S],
+) -> std::io::Result<()> {
+    writer.write_all(&encode_sentence(words)).await?;
+    writer.flush().await
+}
@@ -0,0 +1,38 @@
+use std::time::Duration;
+
+use thiserror::Error;
+
+pub mod cache;
+pub mod connection;
+pub mod health;
+pub mod interface;
+#[cfg(test)]
+pub(crate) mod mock;
+pub mod pool;
+pub mod protocol;
+pub mod traffic;
+pub mod wireless;
+
+#[derive(Debug, Error, Clone)]
+pub enum RouterOsError {
+    /// category 0 means missing item or command, 1 argument failure, 2 interrupted command,
+    /// 3 scripting failure, 4 general failure, 5 api failure
+    #[error("Command {command} failed: {message}")]
+    Trap {
+        command: String,
+        category: Option<u32>,
+        message: String,
+    },
+    #[error("Device closed the connection: {0}")]
+    Fatal(String),
+    #[error("Login failed: {0}")]
+    Login(String),
+    #[error("Invalid reply: {0}")]
+    Protocol(String),
+    #[error("Connection closed")]
+    ConnectionClosed,
+    #[error("No answer within {0:?}")]
+    Timeout(Duration),
+    #[error("TLS handshake failed: {0}")]
+    Tls(String),
+}
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+
+use crate::config::config;
+use crate::error::BackendError;
+use crate::routeros::connection::Connection;
+use crate::routeros::protocol::{Command, Row};
+use crate::topology::model::Device;
+
+lazy_static! {
+    static ref ROUTEROS_POOL: ConnectionPool = ConnectionPool::new(config().routeros_settings());
+}
+
+/// credentials and transport used for all devices
+#[derive(Clone, Debug)]
+pub struct RouterOsSettings {
+    user: Option<String>,
+    password: String,
+    tls: bool,
+    port: Option<u16>,
+    timeout: Duration,
+}
+
+impl RouterOsSettings {
+    pub fn new(
+        user: Option<String>,
+        password: String,
+        tls: bool,
+        port: Option<u16>,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            user,
+            password,
+            tls,
+            port,
+            timeout,
+        }
+    }
+    pub fn user(&self) -> Result<&str, BackendError> {
+        self.user
+            .as_deref()
+            .ok_or(BackendError::MissingConfig("ROUTEROS_USER"))
+    }
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+    pub fn tls(&self) -> bool {
+        self.tls
+    }
+    /// configured port, 8729 with TLS and 8728 otherwise
+    pub fn port(&self) -> u16 {
+        self.port.unwrap_or(if self.tls { 8729 } else { 8728 })
+    }
+    /// limit for connecting and for each command
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+}
+
+type ConnectionSlot = Arc<tokio::sync::Mutex<Option<Arc<Connection>>>>;
+
+/// one api connection per device, reconnected after it was closed
+pub struct ConnectionPool {
+    settings: RouterOsSettings,
+    connections: Mutex<HashMap<IpAddr, ConnectionSlot>>,
+}
+
+impl ConnectionPool {
+    pub fn new(settings: RouterOsSettings) -> Self {
+        Self {
+            settings,
+            connections: Default::default(),
+        }
+    }
+
+    /// connection to the loopback address of the device
+    pub async fn connection(&self, device: &Device) -> Result<Arc<Connection>, BackendError> {
+        let address = device
+            .get_loopback_address()
+            .ok_or(BackendError::MissingIpAddress())?;
+        self.connection_to(address).await
+    }
+
+    pub async fn connection_to(&self, address: IpAddr) -> Result<Arc<Connection>, BackendError> {
+        let slot = self
+            .connections
+            .lock()
+            .unwrap()
+            .entry(address)
+            .or_default()
+            .clone();
+        // only one login per device at a time, other devices are not blocked
+        let mut slot = slot.lock().await;
+        if let Some(connection) = slot.as_ref().filter(|c| !c.is_closed()) {
+            return Ok(connection.clone());
+        }
+        let connection = Connection::connect(
+            SocketAddr::new(address, self.settings.port()),
+            &self.settings,
+        )
+        .await?;
+        *slot = Some(connection.clone());
+        Ok(connection)
+    }
+
+    /// run a command on the device, reconnecting if the last connection was closed
+    pub async fn execute(
+        &self,
+        device: &Device,
+        command: &Command,
+    ) -> Result<Vec<Row>, BackendError> {
+        let connection = self.connection(device).await?;
+        Ok(connection.execute(command).await?)
+    }
+}
+
+pub fn routeros_pool() -> &'static ConnectionPool {
+    &ROUTEROS_POOL
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::BackendError;
+    use crate::routeros::mock::{mock_device, MockRouter};
+    use crate::routeros::pool::ConnectionPool;
+    use crate::routeros::protocol::Command;
+    use crate::routeros::RouterOsError;
+
+    #[tokio::test]
+    async fn test_pool_connections_per_device() {
+        let mock = MockRouter::new("admin", "secret")
+            .reply("/system/identity/print", vec![vec![("name", "rt01")]])
+            .start()
+            .await;
+        let device = mock_device();
+
+        let pool = mock.pool();
+        let identity = Command::new("/system/identity/print");
+        for _ in 0..2 {
+            let rows = pool.execute(&device, &identity).await.unwrap();
+            assert_eq!("rt01", rows[0]["name"]);
+        }
+        assert_eq!(1, mock.logins());
+
+        let result = pool.execute(&device, &Command::new("/nothing")).await;
+        assert!(matches!(
+            result,
+            Err(BackendError::RouterOs {
+                error: RouterOsError::Trap {
+                    category: Some(0),
+                    ..
+                },
+                ..
+            })
+        ));
+
+        // a closed connection is replaced on the next command
+        assert!(pool.execute(&device, &Command::new("/quit")).await.is_err());
+        pool.execute(&device, &identity).await.unwrap();
+        assert_eq!(2, mock.logins());
+
+        let pool = ConnectionPool::new(mock.settings("wrong"));
+        let result = pool.execute(&device, &identity).await;
+        assert!(matches!(
+            result,
+            Err(BackendError::RouterOs {
+                error: RouterOsError::Login(_),
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,303 @@
+use std::collections::HashMap;
+use std::io;
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::routeros::RouterOsError;
+
+/// longest word accepted from a device, longer length prefixes are treated as garbage
+const MAX_WORD_LENGTH: usize = 64 * 1024;
+
+/// attributes of a single `!re` reply
+pub type Row = HashMap<String, String>;
+
+/// command sentence sent to the device
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Command {
+    words: Vec<String>,
+}
+
+impl Command {
+    /// command path, e.g. `/interface/print`
+    pub fn new(path: &str) -> Self {
+        Self {
+            words: vec![path.to_string()],
+        }
+    }
+    pub fn attribute(mut self, name: &str, value: &str) -> Self {
+        self.words.push(format!("={name}={value}"));
+        self
+    }
+    /// limit the returned attributes
+    pub fn proplist(self, properties: &[&str]) -> Self {
+        self.attribute(".proplist", &properties.join(","))
+    }
+    /// query word, e.g. `?type=ether`
+    pub fn query(mut self, query: &str) -> Self {
+        self.words.push(format!("?{query}"));
+        self
+    }
+    pub fn path(&self) -> &str {
+        &self.words[0]
+    }
+    pub(crate) fn words_with_tag(&self, tag: u32) -> Vec<String> {
+        let mut words = self.words.clone();
+        words.push(format!(".tag={tag}"));
+        words
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplyType {
+    Re,
+    Done,
+    Trap,
+    Fatal,
+    Empty,
+}
+
+/// sentence received from the device
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reply {
+    reply_type: ReplyType,
+    tag: Option<u32>,
+    attributes: Row,
+    message: Option<String>,
+}
+
+impl Reply {
+    pub fn parse(words: Vec<String>) -> Result<Reply, RouterOsError> {
+        let mut words = words.into_iter();
+        let reply_type = match words.next().as_deref() {
+            Some("!re") => ReplyType::Re,
+            Some("!done") => ReplyType::Done,
+            Some("!trap") => ReplyType::Trap,
+            Some("!fatal") => ReplyType::Fatal,
+            Some("!empty") => ReplyType::Empty,
+            other => {
+                return Err(RouterOsError::Protocol(format!(
+                    "unknown reply {}",
+                    other.unwrap_or_default()
+                )))
+            }
+        };
+        let mut tag = None;
+        let mut attributes = HashMap::new();
+        let mut message = None;
+        for word in words {
+            if let Some(value) = word.strip_prefix(".tag=") {
+                tag = value.parse().ok();
+            } else if let Some((name, value)) =
+                word.strip_prefix('=').and_then(|w| w.split_once('='))
+            {
+                attributes.insert(name.to_string(), value.to_string());
+            } else if reply_type == ReplyType::Fatal {
+                // the reason of !fatal is sent as plain word
+                message = Some(word);
+            }
+        }
+        Ok(Reply {
+            reply_type,
+            tag,
+            attributes,
+            message,
+        })
+    }
+    pub fn reply_type(&self) -> ReplyType {
+        self.reply_type
+    }
+    pub fn tag(&self) -> Option<u32> {
+        self.tag
+    }
+    pub fn attributes(&self) -> &Row {
+        &self.attributes
+    }
+    pub fn into_attributes(self) -> Row {
+        self.attributes
+    }
+    /// error message of `!trap` and `!fatal`
+    pub fn message(&self) -> String {
+        self.message
+            .clone()
+            .or_else(|| self.attributes.get("message").cloned())
+            .unwrap_or_default()
+    }
+    /// error category of `!trap`
+    pub fn category(&self) -> Option<u32> {
+        self.attributes.get("category")?.parse().ok()
+    }
+}
+
+/// parse the duration format of the api, e.g. `1w2d3h4m5s` or `5s250ms`
+pub fn parse_duration(value: &str) -> Option<Duration> {
+    let mut duration = Duration::ZERO;
+    let mut number = String::new();
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let count = number.parse::<u64>().ok()?;
+        number.clear();
+        let part = match c {
+            'w' => Duration::from_secs(count.checked_mul(7 * 24 * 3600)?),
+            'd' => Duration::from_secs(count.checked_mul(24 * 3600)?),
+            'h' => Duration::from_secs(count.checked_mul(3600)?),
+            'm' if chars.peek() == Some(&'s') => {
+                chars.next();
+                Duration::from_millis(count)
+            }
+            'm' => Duration::from_secs(count.checked_mul(60)?),
+            's' => Duration::from_secs(count),
+            _ => return None,
+        };
+        duration = duration.checked_add(part)?;
+    }
+    number.is_empty().then_some(duration)
+}
+
+/// length prefix of a word, 1 to 5 bytes depending on the length
+pub fn encode_length(length: usize, out: &mut Vec<u8>) {
+    let len = length as u32;
+    match length {
+        0..=0x7f => out.push(len as u8),
+        0x80..=0x3fff => out.extend_from_slice(&(len | 0x8000).to_be_bytes()[2..]),
+        0x4000..=0x1f_ffff => out.extend_from_slice(&(len | 0xc0_0000).to_be_bytes()[1..]),
+        0x20_0000..=0x0fff_ffff => out.extend_from_slice(&(len | 0xe000_0000).to_be_bytes()),
+        _ => {
+            out.push(0xf0);
+            out.extend_from_slice(&len.to_be_bytes());
+        }
+    }
+}
+
+/// words followed by the empty word terminating the sentence
+pub fn encode_sentence<S: AsRef<str>>(words: &[S]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for word in words {
+        let word = word.as_ref().as_bytes();
+        encode_length(word.len(), &mut out);
+        out.extend_from_slice(word);
+    }
+    out.push(0);
+    out
+}
+
+async fn read_length<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<usize> {
+    let first = reader.read_u8().await?;
+    let (mut length, additional) = match first {
+        0x00..=0x7f => (first as usize, 0),
+        0x80..=0xbf => ((first & 0x3f) as usize, 1),
+        0xc0..=0xdf => ((first & 0x1f) as usize, 2),
+        0xe0..=0xef => ((first & 0x0f) as usize, 3),
+        0xf0 => (0, 4),
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid length prefix {first:#x}"),
+            ))
+        }
+    };
+    for _ in 0..additional {
+        length = (length << 8) | reader.read_u8().await? as usize;
+    }
+    Ok(length)
+}
+
+/// read words until the empty word
+pub async fn read_sentence<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<Vec<String>> {
+    let mut words = Vec::new();
+    loop {
+        let length = read_length(reader).await?;
+        if length == 0 {
+            return Ok(words);
+        }
+        // the length is checked before allocating, the peer is not authenticated
+        if length > MAX_WORD_LENGTH {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("word of {length} bytes exceeds {MAX_WORD_LENGTH} bytes"),
+            ));
+        }
+        let mut word = vec![0; length];
+        reader.read_exact(&mut word).await?;
+        words.push(String::from_utf8_lossy(&word).into_owned());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use std::time::Duration;
+
+    use crate::routeros::protocol::{
+        encode_length, encode_sentence, parse_duration, read_sentence, Reply, ReplyType,
+    };
+
+    #[tokio::test]
+    async fn test_encode_and_read_words() {
+        for (length, encoded) in [
+            (0x7f, vec![0x7f]),
+            (0x80, vec![0x80, 0x80]),
+            (0x3fff, vec![0xbf, 0xff]),
+            (0x4000, vec![0xc0, 0x40, 0x00]),
+            (0x20_0000, vec![0xe0, 0x20, 0x00, 0x00]),
+            (0x1000_0000, vec![0xf0, 0x10, 0x00, 0x00, 0x00]),
+        ] {
+            let mut out = Vec::new();
+            encode_length(length, &mut out);
+            assert_eq!(encoded, out, "length {length:#x}");
+        }
+
+        let long_word = "x".repeat(200);
+        let data = encode_sentence(&["!re", "=name=ether1", &long_word, ".tag=3"]);
+        let mut reader = data.as_slice();
+        let words = read_sentence(&mut reader).await.unwrap();
+        assert_eq!(4, words.len());
+        assert_eq!(long_word, words[2]);
+
+        let reply = Reply::parse(words).unwrap();
+        assert_eq!(ReplyType::Re, reply.reply_type());
+        assert_eq!(Some(3), reply.tag());
+        assert_eq!(
+            Some("ether1"),
+            reply.attributes().get("name").map(String::as_str)
+        );
+
+        let trap = Reply::parse(vec![
+            "!trap".to_string(),
+            "=category=0".to_string(),
+            "=message=no such command".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(Some(0), trap.category());
+        assert_eq!("no such command", trap.message());
+    }
+
+    #[tokio::test]
+    async fn test_reject_oversized_word() {
+        let mut data = Vec::new();
+        encode_length(0x0fff_ffff, &mut data);
+        data.extend_from_slice(b"!re");
+        let error = read_sentence(&mut data.as_slice()).await.unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, error.kind());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(
+            Some(Duration::from_secs(702_245)),
+            parse_duration("1w1d3h4m5s")
+        );
+        assert_eq!(Some(Duration::from_millis(5250)), parse_duration("5s250ms"));
+        assert_eq!(None, parse_duration("5x"));
+        assert_eq!(None, parse_duration("12"));
+        assert_eq!(None, parse_duration("99999999999999999w"));
+        assert_eq!(
+            None,
+            parse_duration("18446744073709551615s18446744073709551615s")
+        );
+    }
+}
@@ -0,0 +1,366 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant, SystemTime};
+
+use async_graphql::futures_util::stream::{self, StreamExt};
+use lazy_static::lazy_static;
+use log::warn;
+use tokio::time::MissedTickBehavior;
+
+use crate::config::config;
+use crate::error::BackendError;
+use crate::routeros::pool::{routeros_pool, ConnectionPool};
+use crate::routeros::protocol::{Command, Row};
+use crate::topology::cache::get_topology;
+use crate::topology::model::Device;
+
+lazy_static! {
+    static ref TRAFFIC_STORE: TrafficStore = TrafficStore::default();
+}
+
+/// interface counters since the last reboot of the device
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InterfaceCounters {
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub rx_packets: u64,
+    pub tx_packets: u64,
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+    pub rx_drops: u64,
+    pub tx_drops: u64,
+}
+
+/// throughput between the last two polls
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrafficRates {
+    pub rx_bits_per_second: f64,
+    pub tx_bits_per_second: f64,
+    pub rx_packets_per_second: f64,
+    pub tx_packets_per_second: f64,
+}
+
+/// last polled counters of an interface
+#[derive(Clone, Debug)]
+pub struct InterfaceTraffic {
+    counters: InterfaceCounters,
+    rates: Option<TrafficRates>,
+    polled_at: SystemTime,
+}
+
+struct DeviceTraffic {
+    device_name: String,
+    polled_at: Instant,
+    interfaces: HashMap<String, InterfaceTraffic>,
+}
+
+/// counters of all polled devices by netbox device id
+#[derive(Default)]
+pub struct TrafficStore {
+    devices: RwLock<HashMap<u32, DeviceTraffic>>,
+}
+
+impl InterfaceCounters {
+    fn from_row(row: &Row) -> Self {
+        let counter = |name: &str| {
+            row.get(name)
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_default()
+        };
+        Self {
+            rx_bytes: counter("rx-byte"),
+            tx_bytes: counter("tx-byte"),
+            rx_packets: counter("rx-packet"),
+            tx_packets: counter("tx-packet"),
+            rx_errors: counter("rx-error"),
+            tx_errors: counter("tx-error"),
+            rx_drops: counter("rx-drop"),
+            tx_drops: counter("tx-drop"),
+        }
+    }
+
+    /// rates since the previous counters, `None` if the counters were reset in between
+    fn rates_since(&self, previous: &InterfaceCounters, elapsed: Duration) -> Option<TrafficRates> {
+        let seconds = elapsed.as_secs_f64();
+        if seconds <= 0.0 {
+            return None;
+        }
+        let rate = |current: u64, previous: u64| {
+            current
+                .checked_sub(previous)
+                .map(|delta| delta as f64 / seconds)
+        };
+        Some(TrafficRates {
+            rx_bits_per_second: rate(self.rx_bytes, previous.rx_bytes)? * 8.0,
+            tx_bits_per_second: rate(self.tx_bytes, previous.tx_bytes)? * 8.0,
+            rx_packets_per_second: rate(self.rx_packets, previous.rx_packets)?,
+            tx_packets_per_second: rate(self.tx_packets, previous.tx_packets)?,
+        })
+    }
+}
+
+impl InterfaceTraffic {
+    pub fn counters(&self) -> &InterfaceCounters {
+        &self.counters
+    }
+    /// `None` after the first poll and after counter resets
+    pub fn rates(&self) -> Option<&TrafficRates> {
+        self.rates.as_ref()
+    }
+    pub fn polled_at(&self) -> SystemTime {
+        self.polled_at
+    }
+}
+
+impl TrafficStore {
+    /// store the polled counters of a device and compute the rates since its previous poll
+    pub fn record(
+        &self,
+        device: &Device,
+        counters: HashMap<String, InterfaceCounters>,
+        polled_at: Instant,
+    ) {
+        let mut devices = self.devices.write().unwrap();
+        let previous = devices.get(&device.id());
+        let now = SystemTime::now();
+        let interfaces = counters
+            .into_iter()
+            .map(|(name, counters)| {
+                let rates = previous.and_then(|previous| {
+                    let last = previous.interfaces.get(&name)?;
+                    counters.rates_since(
+                        &last.counters,
+                        polled_at.saturating_duration_since(previous.polled_at),
+                    )
+                });
+                let traffic = InterfaceTraffic {
+                    counters,
+                    rates,
+                    polled_at: now,
+                };
+                (name, traffic)
+            })
+            .collect();
+        devices.insert(
+            device.id(),
+            DeviceTraffic {
+                device_name: device.name().to_string(),
+                polled_at,
+                interfaces,
+            },
+        );
+    }
+
+    pub fn interface(&self, device_id: u32, interface: &str) -> Option<InterfaceTraffic> {
+        self.devices
+            .read()
+            .unwrap()
+            .get(&device_id)?
+            .interfaces
+            .get(interface)
+            .cloned()
+    }
+
+    /// traffic of all interfaces as device id, device name, interface name and traffic
+    pub fn list(&self) -> Vec<(u32, String, String, InterfaceTraffic)> {
+        let devices = self.devices.read().unwrap();
+        devices
+            .iter()
+            .flat_map(|(id, device)| {
+                device.interfaces.iter().map(|(name, traffic)| {
+                    let device_name = device.device_name.clone();
+                    (*id, device_name, name.clone(), traffic.clone())
+                })
+            })
+            .collect()
+    }
+
+    /// remove devices no longer polled
+    fn retain(&self, device_ids: &[u32]) {
+        self.devices
+            .write()
+            .unwrap()
+            .retain(|id, _| device_ids.contains(id));
+    }
+}
+
+/// counters of all interfaces of the device by interface name
+pub async fn fetch_interface_counters(
+    pool: &ConnectionPool,
+    device: &Device,
+) -> Result<HashMap<String, InterfaceCounters>, BackendError> {
+    let rows = pool
+        .execute(
+            device,
+            &Command::new("/interface/print").proplist(&[
+                "name",
+                "rx-byte",
+                "tx-byte",
+                "rx-packet",
+                "tx-packet",
+                "rx-error",
+                "tx-error",
+                "rx-drop",
+                "tx-drop",
+            ]),
+        )
+        .await?;
+    Ok(rows
+        .iter()
+        .flat_map(|row| Some((row.get("name")?.clone(), InterfaceCounters::from_row(row))))
+        .collect())
+}
+
+async fn poll_device(device: Arc<Device>) {
+    let polled_at = Instant::now();
+    match fetch_interface_counters(routeros_pool(), &device).await {
+        Ok(counters) => TRAFFIC_STORE.record(&device, counters, polled_at),
+        Err(error) => warn!("Cannot poll traffic of {}: {error}", device.name()),
+    }
+}
+
+/// last polled traffic of the interface
+pub fn interface_traffic(device_id: u32, interface: &str) -> Option<InterfaceTraffic> {
+    TRAFFIC_STORE.interface(device_id, interface)
+}
+
+/// last polled traffic of all interfaces as device id, device name, interface name and traffic
+pub fn list_interface_traffic() -> Vec<(u32, String, String, InterfaceTraffic)> {
+    TRAFFIC_STORE.list()
+}
+
+/// RouterOS devices with a loopback address, unless their monitoring is switched off in netbox
+fn is_polled(device: &Device) -> bool {
+    device.has_routeros()
+        && device.monitoring().enabled() != Some(false)
+        && device.get_loopback_address().is_some()
+}
+
+/// poll the counters of all RouterOS devices within the configured interval
+pub fn spawn_traffic_poller() {
+    let interval = config().routeros_traffic_poll_interval();
+    let concurrency = config().routeros_concurrency();
+    tokio::spawn(async move {
+        let mut ticks = tokio::time::interval(interval);
+        // a slow round delays the next one instead of starting the missed rounds at once
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticks.tick().await;
+            let topology = match get_topology().await {
+                Ok(topology) => topology,
+                Err(error) => {
+                    warn!("Cannot poll traffic without topology: {error}");
+                    continue;
+                }
+            };
+            let devices = topology.list_devices_map(|d| is_polled(d).then(|| d.clone()));
+            TRAFFIC_STORE.retain(&devices.iter().map(|d| d.id()).collect::<Vec<_>>());
+            stream::iter(devices)
+                .for_each_concurrent(concurrency, poll_device)
+                .await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::{Duration, Instant};
+
+    use crate::routeros::mock::{mock_device, MockRouter};
+    use crate::routeros::traffic::{
+        fetch_interface_counters, is_polled, InterfaceCounters, TrafficStore,
+    };
+    use crate::topology::model::device::{AddressRole, DeviceBuilder, InterfaceAddress};
+    use crate::topology::model::monitoring::MonitoringSettings;
+    use crate::topology::model::{DeviceType, Topology};
+
+    #[tokio::test]
+    async fn test_rates_between_polls() {
+        let mock = MockRouter::new("admin", "secret")
+            .reply(
+                "/interface/print",
+                vec![vec![
+                    ("name", "ether1"),
+                    ("rx-byte", "1000000"),
+                    ("tx-byte", "2000000"),
+                    ("rx-packet", "1000"),
+                    ("tx-packet", "2000"),
+                    ("rx-error", "1"),
+                    ("rx-drop", "2"),
+                ]],
+            )
+            .start()
+            .await;
+        let device = mock_device();
+        let counters = fetch_interface_counters(&mock.pool(), &device)
+            .await
+            .unwrap();
+        let first = counters["ether1"];
+        assert_eq!(1000000, first.rx_bytes);
+        assert_eq!(1, first.rx_errors);
+        assert_eq!(0, first.tx_errors);
+
+        let store = TrafficStore::default();
+        let start = Instant::now();
+        store.record(&device, counters, start);
+        assert!(store.interface(1, "ether1").unwrap().rates().is_none());
+
+        let second = InterfaceCounters {
+            rx_bytes: first.rx_bytes + 1250000,
+            tx_bytes: first.tx_bytes + 125000,
+            rx_packets: first.rx_packets + 1000,
+            tx_packets: first.tx_packets + 100,
+            ..first
+        };
+        store.record(
+            &device,
+            HashMap::from([("ether1".to_string(), second)]),
+            start + Duration::from_secs(10),
+        );
+        let rates = *store.interface(1, "ether1").unwrap().rates().unwrap();
+        assert_eq!(1000000.0, rates.rx_bits_per_second);
+        assert_eq!(100000.0, rates.tx_bits_per_second);
+        assert_eq!(100.0, rates.rx_packets_per_second);
+        assert_eq!(10.0, rates.tx_packets_per_second);
+
+        // counters restart after a reboot
+        store.record(
+            &device,
+            HashMap::from([("ether1".to_string(), InterfaceCounters::default())]),
+            start + Duration::from_secs(20),
+        );
+        assert!(store.interface(1, "ether1").unwrap().rates().is_none());
+        let list = store.list();
+        assert_eq!(1, list.len());
+        assert_eq!((1, "ether1"), (list[0].0, list[0].2.as_str()));
+    }
+
+    #[test]
+    fn test_skip_unmonitored_devices() {
+        let mut topology_builder = Topology::builder();
+        topology_builder.append_device_type(DeviceType::new("rb5009".to_string(), 1, true));
+        for (id, enabled) in [(1, None), (2, Some(false)), (3, Some(true))] {
+            let mut device_builder = DeviceBuilder::new(id, format!("rt0{id}"), true);
+            device_builder.set_device_type(1);
+            device_builder.append_interface(
+                1,
+                "loopback".to_string(),
+                vec![InterfaceAddress::new(
+                    format!("172.16.0.{id}/32").parse().unwrap(),
+                    AddressRole::Loopback,
+                    None,
+                )],
+            );
+            if let Some(enabled) = enabled {
+                let mut monitoring = MonitoringSettings::default();
+                monitoring.set_enabled(enabled);
+                device_builder.set_monitoring(monitoring);
+            }
+            topology_builder.append_device(device_builder);
+        }
+        let topology = topology_builder.build().unwrap();
+        let polled = topology.list_devices_map(|d| is_polled(d).then(|| d.id()));
+        assert_eq!(vec![1, 3], polled);
+    }
+}
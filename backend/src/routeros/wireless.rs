@@ -0,0 +1,418 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_graphql::futures_util::stream::{self, StreamExt};
+use lazy_static::lazy_static;
+use log::warn;
+
+use crate::config::config;
+use crate::error::BackendError;
+use crate::routeros::cache::DeviceStatusCache;
+use crate::routeros::connection::Connection;
+use crate::routeros::pool::{routeros_pool, ConnectionPool};
+use crate::routeros::protocol::{parse_duration, Command, Row};
+use crate::routeros::RouterOsError;
+use crate::topology::model::device::DeviceCategory;
+use crate::topology::model::{Device, Topology};
+
+lazy_static! {
+    static ref WIRELESS_CLIENT_CACHE: DeviceStatusCache<Vec<WirelessClient>> =
+        DeviceStatusCache::new(config().routeros_status_max_age());
+}
+
+/// registration table a client was found in, depends on the installed wireless package
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegistrationTable {
+    /// legacy `wireless` package
+    Wireless,
+    /// `wifi` package of RouterOS 7
+    Wifi,
+    /// access points managed by the legacy CAPsMAN
+    Capsman,
+}
+
+/// client currently registered at an access point
+#[derive(Clone, Debug, PartialEq)]
+pub struct WirelessClient {
+    mac_address: String,
+    interface: String,
+    ssid: Option<String>,
+    signal_strength: Option<i32>,
+    tx_rate: Option<String>,
+    rx_rate: Option<String>,
+    uptime: Option<Duration>,
+    table: RegistrationTable,
+    cap: Option<String>,
+}
+
+impl RegistrationTable {
+    fn command(&self) -> Command {
+        match self {
+            RegistrationTable::Wireless => {
+                Command::new("/interface/wireless/registration-table/print")
+            }
+            RegistrationTable::Wifi => Command::new("/interface/wifi/registration-table/print"),
+            RegistrationTable::Capsman => Command::new("/caps-man/registration-table/print"),
+        }
+    }
+    /// attribute holding the signal strength in dBm
+    fn signal_attribute(&self) -> &'static str {
+        match self {
+            RegistrationTable::Wireless => "signal-strength",
+            RegistrationTable::Wifi => "signal",
+            RegistrationTable::Capsman => "rx-signal",
+        }
+    }
+}
+
+impl WirelessClient {
+    fn from_row(
+        row: &Row,
+        table: RegistrationTable,
+        ssid_of_interface: &HashMap<String, String>,
+        cap_of_interface: &HashMap<String, String>,
+    ) -> Option<Self> {
+        let interface = row.get("interface").cloned().unwrap_or_default();
+        let ssid = row
+            .get("ssid")
+            .or_else(|| ssid_of_interface.get(&interface))
+            .cloned();
+        let cap = cap_of_interface.get(&interface).cloned();
+        Some(Self {
+            mac_address: row.get("mac-address")?.clone(),
+            ssid,
+            signal_strength: row
+                .get(table.signal_attribute())
+                .and_then(|signal| parse_signal(signal)),
+            tx_rate: row.get("tx-rate").cloned(),
+            rx_rate: row.get("rx-rate").cloned(),
+            uptime: row.get("uptime").and_then(|u| parse_duration(u)),
+            interface,
+            table,
+            cap,
+        })
+    }
+    pub fn mac_address(&self) -> &str {
+        &self.mac_address
+    }
+    /// wireless interface of the access point the client is registered at
+    pub fn interface(&self) -> &str {
+        &self.interface
+    }
+    pub fn ssid(&self) -> Option<&str> {
+        self.ssid.as_deref()
+    }
+    /// signal strength received from the client in dBm
+    pub fn signal_strength(&self) -> Option<i32> {
+        self.signal_strength
+    }
+    /// rate towards the client as shown by RouterOS, e.g. `866.7Mbps-80MHz/2S/SGI`
+    pub fn tx_rate(&self) -> Option<&str> {
+        self.tx_rate.as_deref()
+    }
+    /// rate from the client
+    pub fn rx_rate(&self) -> Option<&str> {
+        self.rx_rate.as_deref()
+    }
+    /// time since the client registered
+    pub fn uptime(&self) -> Option<Duration> {
+        self.uptime
+    }
+    pub fn table(&self) -> RegistrationTable {
+        self.table
+    }
+    /// identity of the access point, only known for clients listed by a CAPsMAN controller
+    pub fn cap(&self) -> Option<&str> {
+        self.cap.as_deref()
+    }
+    /// listed by a controller for the access point, the CAP identity is expected to be its name
+    fn managed_by(&self, device: &Device) -> bool {
+        self.cap
+            .as_deref()
+            .map(|cap| cap.eq_ignore_ascii_case(device.name()))
+            .unwrap_or(false)
+    }
+}
+
+/// clients registered at a group of access points
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WirelessClientCount {
+    pub clients: usize,
+    pub access_points: usize,
+    /// access points whose registration tables could not be read
+    pub unreachable_access_points: usize,
+}
+
+/// signal strength in dBm, the legacy package appends the rate, e.g. `-58@HT20-7`
+fn parse_signal(signal: &str) -> Option<i32> {
+    signal.split('@').next()?.parse().ok()
+}
+
+/// identity of the CAP by the interface the controller created for its radio
+fn cap_of_interface(radios: Vec<Row>, cap_attribute: &str) -> HashMap<String, String> {
+    radios
+        .into_iter()
+        .flat_map(|mut row| {
+            let cap = row.remove(cap_attribute).filter(|cap| !cap.is_empty())?;
+            Some((row.remove("interface")?, cap))
+        })
+        .collect()
+}
+
+/// rows of the table, empty if the package providing it is not installed
+async fn read_table(connection: &Connection, command: &Command) -> Result<Vec<Row>, BackendError> {
+    match connection.execute(command).await {
+        Ok(rows) => Ok(rows),
+        Err(RouterOsError::Trap { .. }) => Ok(vec![]),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// clients of all registration tables of the device
+pub async fn fetch_wireless_clients(
+    pool: &ConnectionPool,
+    device: &Device,
+) -> Result<Vec<WirelessClient>, BackendError> {
+    let connection = pool.connection(device).await?;
+    let interfaces = Command::new("/interface/wireless/print").proplist(&["name", "ssid"]);
+    let wireless = RegistrationTable::Wireless.command();
+    let wifi = RegistrationTable::Wifi.command();
+    let capsman = RegistrationTable::Capsman.command();
+    let capsman_radios =
+        Command::new("/caps-man/radio/print").proplist(&["interface", "remote-cap-identity"]);
+    let wifi_radios = Command::new("/interface/wifi/radio/print").proplist(&["interface", "cap"]);
+    let (interfaces, wireless, wifi, capsman, capsman_radios, wifi_radios) = tokio::join!(
+        read_table(&connection, &interfaces),
+        read_table(&connection, &wireless),
+        read_table(&connection, &wifi),
+        read_table(&connection, &capsman),
+        read_table(&connection, &capsman_radios),
+        read_table(&connection, &wifi_radios)
+    );
+    // the legacy registration table has no ssid, it is configured on the interface
+    let ssid_of_interface: HashMap<String, String> = interfaces?
+        .into_iter()
+        .flat_map(|mut row| Some((row.remove("name")?, row.remove("ssid")?)))
+        .collect();
+    // controllers list the clients of their access points on the interfaces of the remote radios
+    let local = HashMap::new();
+    let capsman_caps = cap_of_interface(capsman_radios?, "remote-cap-identity");
+    let wifi_caps = cap_of_interface(wifi_radios?, "cap");
+    let mut clients = Vec::new();
+    for (table, rows, caps) in [
+        (RegistrationTable::Wireless, wireless?, &local),
+        (RegistrationTable::Wifi, wifi?, &wifi_caps),
+        (RegistrationTable::Capsman, capsman?, &capsman_caps),
+    ] {
+        clients.extend(
+            rows.iter()
+                .flat_map(|row| WirelessClient::from_row(row, table, &ssid_of_interface, caps)),
+        );
+    }
+    Ok(clients)
+}
+
+/// recently fetched clients registered at the access point
+pub async fn wireless_clients(device: &Device) -> Result<Arc<Vec<WirelessClient>>, BackendError> {
+    WIRELESS_CLIENT_CACHE
+        .get_or_fetch(device.id(), || {
+            fetch_wireless_clients(routeros_pool(), device)
+        })
+        .await
+}
+
+/// RouterOS devices in the access point role
+pub fn is_access_point(device: &Device) -> bool {
+    device.category() == DeviceCategory::AccessPoint && device.has_routeros()
+}
+
+/// RouterOS devices in the router role, CAPsMAN runs on them and lists the clients of its CAPs
+pub fn is_wireless_controller(device: &Device) -> bool {
+    device.category() == DeviceCategory::Router && device.has_routeros()
+}
+
+/// clients the controllers on the given sites list for their access points
+async fn managed_clients(topology: &Arc<Topology>, sites: HashSet<usize>) -> Vec<WirelessClient> {
+    let controllers = topology.list_devices_map(|d| {
+        (is_wireless_controller(d) && d.site().is_some_and(|site| sites.contains(&site)))
+            .then(|| d.clone())
+    });
+    stream::iter(controllers)
+        .map(|controller| async move {
+            match wireless_clients(&controller).await {
+                Ok(clients) => clients
+                    .iter()
+                    .filter(|c| c.cap.is_some())
+                    .cloned()
+                    .collect(),
+                Err(error) => {
+                    warn!(
+                        "Cannot read wireless clients of controller {}: {error}",
+                        controller.name()
+                    );
+                    vec![]
+                }
+            }
+        })
+        .buffer_unordered(config().routeros_concurrency())
+        .concat()
+        .await
+}
+
+/// own clients of the access point and the clients controllers list for it, each client once
+fn merge_clients(
+    device: &Device,
+    own: &[WirelessClient],
+    managed: &[WirelessClient],
+) -> Vec<WirelessClient> {
+    let mut seen = HashSet::new();
+    own.iter()
+        .chain(managed.iter().filter(|client| client.managed_by(device)))
+        .filter(|client| seen.insert(client.mac_address.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// clients registered at the access point, including those listed by its CAPsMAN controller
+pub async fn clients_of_access_point(
+    topology: &Arc<Topology>,
+    device: &Device,
+) -> Result<Vec<WirelessClient>, BackendError> {
+    let sites = device.site().into_iter().collect();
+    let (own, managed) = tokio::join!(wireless_clients(device), managed_clients(topology, sites));
+    Ok(merge_clients(device, &own?, &managed))
+}
+
+/// count the clients of all access points within the devices
+pub async fn count_wireless_clients(
+    topology: &Arc<Topology>,
+    devices: Vec<Arc<Device>>,
+) -> WirelessClientCount {
+    let access_points: Vec<_> = devices
+        .into_iter()
+        .filter(|device| is_access_point(device))
+        .collect();
+    let mut count = WirelessClientCount::default();
+    if access_points.is_empty() {
+        return count;
+    }
+    let sites = access_points.iter().flat_map(|d| d.site()).collect();
+    let managed = managed_clients(topology, sites).await;
+    let results: Vec<_> = stream::iter(access_points)
+        .map(|device| {
+            let managed = &managed;
+            async move {
+                let result = wireless_clients(&device).await;
+                if let Err(error) = &result {
+                    warn!("Cannot read wireless clients of {}: {error}", device.name());
+                }
+                result.map(|own| merge_clients(&device, &own, managed).len())
+            }
+        })
+        .buffer_unordered(config().routeros_concurrency())
+        .collect()
+        .await;
+    for result in results {
+        count.access_points += 1;
+        match result {
+            Ok(clients) => count.clients += clients,
+            Err(_) => count.unreachable_access_points += 1,
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::routeros::mock::{mock_device, MockRouter};
+    use crate::routeros::wireless::{
+        fetch_wireless_clients, merge_clients, RegistrationTable, WirelessClient,
+    };
+
+    #[tokio::test]
+    async fn test_fetch_wireless_clients() {
+        // the wifi package is not installed, its menu is missing
+        let mock = MockRouter::new("admin", "secret")
+            .reply(
+                "/interface/wireless/print",
+                vec![vec![("name", "wlan1"), ("ssid", "guests")]],
+            )
+            .reply(
+                "/interface/wireless/registration-table/print",
+                vec![vec![
+                    ("interface", "wlan1"),
+                    ("mac-address", "B8:27:EB:00:00:01"),
+                    ("signal-strength", "-58@HT20-7"),
+                    ("tx-rate", "65Mbps-20MHz/1S"),
+                    ("rx-rate", "58.5Mbps-20MHz/1S"),
+                    ("uptime", "1h2m3s"),
+                ]],
+            )
+            .reply(
+                "/caps-man/registration-table/print",
+                vec![vec![
+                    ("interface", "cap-office-1"),
+                    ("ssid", "office"),
+                    ("mac-address", "B8:27:EB:00:00:02"),
+                    ("rx-signal", "-71"),
+                    ("uptime", "5s250ms"),
+                ]],
+            )
+            .reply(
+                "/caps-man/radio/print",
+                vec![vec![
+                    ("interface", "cap-office-1"),
+                    ("remote-cap-identity", "ap01"),
+                ]],
+            )
+            .start()
+            .await;
+        let clients = fetch_wireless_clients(&mock.pool(), &mock_device())
+            .await
+            .unwrap();
+        assert_eq!(2, clients.len());
+        let legacy = &clients[0];
+        assert_eq!(RegistrationTable::Wireless, legacy.table());
+        assert_eq!(Some("guests"), legacy.ssid());
+        assert_eq!(Some(-58), legacy.signal_strength());
+        assert_eq!(Some("65Mbps-20MHz/1S"), legacy.tx_rate());
+        assert_eq!(Some(Duration::from_secs(3723)), legacy.uptime());
+        assert_eq!(None, legacy.cap());
+        let capsman = &clients[1];
+        assert_eq!(RegistrationTable::Capsman, capsman.table());
+        assert_eq!(Some("office"), capsman.ssid());
+        assert_eq!(Some(-71), capsman.signal_strength());
+        assert_eq!(Some(Duration::from_millis(5250)), capsman.uptime());
+        assert_eq!(Some("ap01"), capsman.cap());
+    }
+
+    #[test]
+    fn test_merge_clients_of_controller() {
+        let client = |mac_address: &str, cap: Option<&str>| WirelessClient {
+            mac_address: mac_address.to_string(),
+            interface: "wifi1".to_string(),
+            ssid: None,
+            signal_strength: None,
+            tx_rate: None,
+            rx_rate: None,
+            uptime: None,
+            table: RegistrationTable::Wifi,
+            cap: cap.map(str::to_string),
+        };
+        // the mock device is named rt01
+        let device = mock_device();
+        let own = vec![client("B8:27:EB:00:00:01", None)];
+        let managed = vec![
+            client("B8:27:EB:00:00:01", Some("rt01")),
+            client("B8:27:EB:00:00:02", Some("RT01")),
+            client("B8:27:EB:00:00:03", Some("rt02")),
+        ];
+        let clients = merge_clients(&device, &own, &managed);
+        let macs: Vec<&str> = clients.iter().map(|c| c.mac_address()).collect();
+        assert_eq!(vec!["B8:27:EB:00:00:01", "B8:27:EB:00:00:02"], macs);
+        assert_eq!(None, clients[0].cap());
+    }
+}
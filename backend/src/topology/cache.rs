@@ -0,0 +1,247 @@
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant, SystemTime};
+
+use lazy_static::lazy_static;
+use log::{info, warn};
+use tokio::sync::{Mutex, Notify};
+
+use crate::config::config;
+use crate::error::BackendError;
+use crate::topology::model::diff::TopologyChange;
+use crate::topology::model::Topology;
+use crate::topology::query::fetch_topology;
+
+lazy_static! {
+    static ref TOPOLOGY_CACHE: TopologyCache =
+        TopologyCache::new(config().topology_change_history());
+}
+
+/// last good topology, replaced by the refresher and kept on fetch errors
+pub struct TopologyCache {
+    state: RwLock<CacheState>,
+    fetch_lock: Mutex<()>,
+    refresh_request: Notify,
+    history_size: usize,
+}
+
+#[derive(Default)]
+struct CacheState {
+    topology: Option<Arc<Topology>>,
+    fetched_at: Option<Instant>,
+    last_error: Option<(Instant, BackendError)>,
+    failed_fetches: u64,
+    changes: VecDeque<(SystemTime, TopologyChange)>,
+}
+
+/// health of the topology cache
+#[derive(Clone, Debug)]
+pub struct TopologyCacheStatus {
+    age: Option<Duration>,
+    last_error: Option<(Duration, BackendError)>,
+    failed_fetches: u64,
+}
+
+impl TopologyCacheStatus {
+    /// time since the served topology was fetched, `None` if there is none yet
+    pub fn age(&self) -> Option<Duration> {
+        self.age
+    }
+    /// error of the last fetch, `None` if it was successful
+    pub fn last_error(&self) -> Option<&BackendError> {
+        self.last_error.as_ref().map(|(_, error)| error)
+    }
+    /// time since the last failed fetch
+    pub fn last_error_age(&self) -> Option<Duration> {
+        self.last_error.as_ref().map(|(age, _)| *age)
+    }
+    /// count of failed fetches since startup
+    pub fn failed_fetches(&self) -> u64 {
+        self.failed_fetches
+    }
+}
+
+impl TopologyCache {
+    /// keep at most `history_size` changes
+    pub fn new(history_size: usize) -> Self {
+        Self {
+            state: Default::default(),
+            fetch_lock: Default::default(),
+            refresh_request: Notify::new(),
+            history_size,
+        }
+    }
+
+    /// currently served topology
+    pub fn current(&self) -> Option<Arc<Topology>> {
+        self.state.read().unwrap().topology.clone()
+    }
+
+    /// fetch a new topology and swap it in, the last good one stays in place on errors
+    pub async fn refresh<F, Fut>(&self, fetch: F) -> Result<Arc<Topology>, BackendError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Arc<Topology>, BackendError>>,
+    {
+        let _guard = self.fetch_lock.lock().await;
+        self.do_refresh(fetch).await
+    }
+
+    /// the cached topology, fetched first if there is none yet
+    pub async fn get_or_fetch<F, Fut>(&self, fetch: F) -> Result<Arc<Topology>, BackendError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Arc<Topology>, BackendError>>,
+    {
+        if let Some(topology) = self.current() {
+            return Ok(topology);
+        }
+        let _guard = self.fetch_lock.lock().await;
+        // another request may have fetched it while waiting for the lock
+        if let Some(topology) = self.current() {
+            return Ok(topology);
+        }
+        self.do_refresh(fetch).await
+    }
+
+    /// wake up the refresher, requests arriving during a refresh are merged into one
+    pub fn request_refresh(&self) {
+        self.refresh_request.notify_one();
+    }
+
+    /// wait until the interval elapsed or a refresh was requested
+    async fn wait_for_refresh(&self, interval: Duration) {
+        let _ = tokio::time::timeout(interval, self.refresh_request.notified()).await;
+    }
+
+    /// changes detected between consecutive topologies, oldest first
+    pub fn changes(&self, since: Option<SystemTime>) -> Vec<(SystemTime, TopologyChange)> {
+        let state = self.state.read().unwrap();
+        state
+            .changes
+            .iter()
+            .filter(|(time, _)| since.map(|s| *time >= s).unwrap_or(true))
+            .cloned()
+            .collect()
+    }
+
+    pub fn status(&self) -> TopologyCacheStatus {
+        let state = self.state.read().unwrap();
+        TopologyCacheStatus {
+            age: state.fetched_at.map(|t| t.elapsed()),
+            last_error: state
+                .last_error
+                .as_ref()
+                .map(|(t, error)| (t.elapsed(), error.clone())),
+            failed_fetches: state.failed_fetches,
+        }
+    }
+
+    async fn do_refresh<F, Fut>(&self, fetch: F) -> Result<Arc<Topology>, BackendError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Arc<Topology>, BackendError>>,
+    {
+        let result = fetch().await;
+        match &result {
+            Ok(topology) => {
+                info!("Fetched topology successful");
+                // refreshes are serialized by the fetch lock, so the previous topology stays
+                // current while the changes are computed without blocking readers
+                let previous = self.current();
+                let now = SystemTime::now();
+                let changes = previous
+                    .map(|previous| topology.changes_since(&previous))
+                    .unwrap_or_default();
+                if !changes.is_empty() {
+                    info!("Topology changed: {} changes", changes.len());
+                }
+                let mut state = self.state.write().unwrap();
+                state
+                    .changes
+                    .extend(changes.into_iter().map(|change| (now, change)));
+                let overflow = state.changes.len().saturating_sub(self.history_size);
+                state.changes.drain(..overflow);
+                state.topology = Some(topology.clone());
+                state.fetched_at = Some(Instant::now());
+                state.last_error = None;
+            }
+            Err(error) => {
+                warn!("Error fetching topology {error}");
+                let mut state = self.state.write().unwrap();
+                state.last_error = Some((Instant::now(), error.clone()));
+                state.failed_fetches += 1;
+            }
+        }
+        result
+    }
+}
+
+/// the last good topology, only waits for netbox if nothing was fetched so far
+pub async fn get_topology() -> Result<Arc<Topology>, BackendError> {
+    TOPOLOGY_CACHE.get_or_fetch(fetch_topology).await
+}
+
+pub fn topology_cache_status() -> TopologyCacheStatus {
+    TOPOLOGY_CACHE.status()
+}
+
+pub fn topology_changes(since: Option<SystemTime>) -> Vec<(SystemTime, TopologyChange)> {
+    TOPOLOGY_CACHE.changes(since)
+}
+
+/// fetch a new topology in the background without waiting for the refresh interval
+pub fn request_topology_refresh() {
+    TOPOLOGY_CACHE.request_refresh();
+}
+
+/// refresh the topology in the background within the configured interval
+pub fn spawn_topology_refresher() {
+    let interval = config().topology_refresh_interval();
+    tokio::spawn(async move {
+        loop {
+            // errors are recorded in the cache status
+            let _ = TOPOLOGY_CACHE.refresh(fetch_topology).await;
+            TOPOLOGY_CACHE.wait_for_refresh(interval).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::error::BackendError;
+    use crate::topology::cache::TopologyCache;
+    use crate::topology::model::Topology;
+
+    #[tokio::test]
+    async fn test_keep_last_good_topology() {
+        let cache = TopologyCache::new(10);
+        assert!(cache
+            .get_or_fetch(|| async { Err(BackendError::MissingDeviceType()) })
+            .await
+            .is_err());
+        assert!(cache.current().is_none());
+
+        let topology = Topology::builder().build().unwrap();
+        let fetched = topology.clone();
+        cache.refresh(|| async { Ok(fetched) }).await.unwrap();
+        assert!(cache.status().last_error().is_none());
+
+        assert!(cache
+            .refresh(|| async { Err(BackendError::MissingDeviceType()) })
+            .await
+            .is_err());
+        let status = cache.status();
+        assert!(status.last_error().is_some());
+        assert_eq!(2, status.failed_fetches());
+        assert!(status.age().is_some());
+        let served = cache
+            .get_or_fetch(|| async { Err(BackendError::MissingDeviceType()) })
+            .await
+            .unwrap();
+        assert!(Arc::ptr_eq(&topology, &served));
+    }
+}
@@ -3,7 +3,10 @@ use std::ops::Deref;
 
 use graphql_client::GraphQLQuery;
 use serde::de::{Error, Visitor};
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// untyped json value like the custom fields of netbox objects
+pub type GenericScalar = serde_json::Value;
 
 #[derive(Debug)]
 pub struct Decimal(f64);
@@ -48,6 +51,15 @@ impl<'de> Deserialize<'de> for Decimal {
     }
 }
 
+impl Serialize for Decimal {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0.to_string())
+    }
+}
+
 impl Deref for Decimal {
     type Target = f64;
 
@@ -60,7 +72,16 @@ impl Deref for Decimal {
 #[graphql(
     schema_path = "schema/netbox.graphql",
     query_path = "src/topology/fetch_topology.graphql",
-    response_derives = "Debug",
+    response_derives = "Debug,Serialize",
     variables_derives = "Default,Debug"
 )]
 pub struct FetchTopology;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema/netbox.graphql",
+    query_path = "src/topology/fetch_topology.graphql",
+    response_derives = "Debug",
+    variables_derives = "Default,Debug"
+)]
+pub struct FetchObjectIds;
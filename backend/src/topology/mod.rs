@@ -1,3 +1,5 @@
+pub mod cache;
 pub mod graphql_operations;
 pub mod model;
 pub mod query;
+pub mod source;
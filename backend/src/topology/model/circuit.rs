@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::topology::model::{PortIdx, Topology};
+
+/// provider line maintained in netbox circuits
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub struct Circuit {
+    id: u32,
+    cid: String,
+    provider: Provider,
+    circuit_type: String,
+    status: CircuitStatus,
+    commit_rate: Option<u32>,
+    description: String,
+    terminations: Vec<CircuitTermination>,
+}
+
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub struct Provider {
+    id: u32,
+    name: String,
+    account: String,
+}
+
+#[derive(Clone, Copy, Hash, Eq, PartialEq, Debug)]
+pub enum CircuitStatus {
+    Active,
+    Decommissioned,
+    Deprovisioning,
+    Offline,
+    Planned,
+    Provisioning,
+    /// status not known to this application
+    Unknown,
+}
+
+#[derive(Clone, Copy, Hash, Eq, PartialEq, Debug)]
+pub enum TerminationSide {
+    A,
+    Z,
+}
+
+/// one end of a circuit
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub struct CircuitTermination {
+    id: u32,
+    side: TerminationSide,
+    site: Option<usize>,
+    port: Option<PortIdx>,
+    port_speed: Option<u32>,
+    upstream_speed: Option<u32>,
+    xconnect_id: String,
+}
+
+impl Provider {
+    pub fn new(id: u32, name: String, account: String) -> Self {
+        Self { id, name, account }
+    }
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// customer account number at the provider
+    pub fn account(&self) -> &str {
+        &self.account
+    }
+}
+
+impl Circuit {
+    pub fn builder(
+        id: u32,
+        cid: String,
+        provider: Provider,
+        circuit_type: String,
+        status: CircuitStatus,
+    ) -> CircuitBuilder {
+        CircuitBuilder {
+            id,
+            cid,
+            provider,
+            circuit_type,
+            status,
+            commit_rate: None,
+            description: String::new(),
+            terminations: vec![],
+        }
+    }
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    /// circuit id assigned by the provider
+    pub fn cid(&self) -> &str {
+        &self.cid
+    }
+    pub fn provider(&self) -> &Provider {
+        &self.provider
+    }
+    pub fn circuit_type(&self) -> &str {
+        &self.circuit_type
+    }
+    pub fn status(&self) -> CircuitStatus {
+        self.status
+    }
+    /// committed rate in kbit/s
+    pub fn commit_rate(&self) -> Option<u32> {
+        self.commit_rate
+    }
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+    pub fn terminations(&self) -> &Vec<CircuitTermination> {
+        &self.terminations
+    }
+}
+
+impl CircuitTermination {
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn side(&self) -> TerminationSide {
+        self.side
+    }
+    pub fn site(&self) -> Option<usize> {
+        self.site
+    }
+    /// device port the termination is cabled to, may be a port of a patch panel
+    pub fn port(&self) -> Option<PortIdx> {
+        self.port
+    }
+    /// port speed in kbit/s
+    pub fn port_speed(&self) -> Option<u32> {
+        self.port_speed
+    }
+    /// upstream speed in kbit/s, if different from the port speed
+    pub fn upstream_speed(&self) -> Option<u32> {
+        self.upstream_speed
+    }
+    /// id of the cross connect
+    pub fn xconnect_id(&self) -> &str {
+        &self.xconnect_id
+    }
+}
+
+pub struct CircuitBuilder {
+    id: u32,
+    cid: String,
+    provider: Provider,
+    circuit_type: String,
+    status: CircuitStatus,
+    commit_rate: Option<u32>,
+    description: String,
+    terminations: Vec<CircuitTerminationBuilder>,
+}
+
+pub struct CircuitTerminationBuilder {
+    id: u32,
+    side: TerminationSide,
+    site_id: Option<u32>,
+    port: Option<PortIdx>,
+    port_speed: Option<u32>,
+    upstream_speed: Option<u32>,
+    xconnect_id: String,
+}
+
+impl CircuitBuilder {
+    pub fn set_commit_rate(&mut self, commit_rate: u32) {
+        self.commit_rate = Some(commit_rate);
+    }
+    pub fn set_description(&mut self, description: String) {
+        self.description = description;
+    }
+    /// append a termination and return its index within the circuit
+    pub fn append_termination(
+        &mut self,
+        id: u32,
+        side: TerminationSide,
+        site_id: Option<u32>,
+        port_speed: Option<u32>,
+        upstream_speed: Option<u32>,
+        xconnect_id: String,
+    ) -> usize {
+        let idx = self.terminations.len();
+        self.terminations.push(CircuitTerminationBuilder {
+            id,
+            side,
+            site_id,
+            port: None,
+            port_speed,
+            upstream_speed,
+            xconnect_id,
+        });
+        idx
+    }
+    pub fn set_termination_port(&mut self, termination_idx: usize, port: PortIdx) {
+        if let Some(termination) = self.terminations.get_mut(termination_idx) {
+            termination.port = Some(port);
+        }
+    }
+
+    pub(crate) fn build<SM>(self, site_mapper: &SM) -> Circuit
+    where
+        SM: Fn(u32) -> Option<usize>,
+    {
+        Circuit {
+            id: self.id,
+            cid: self.cid,
+            provider: self.provider,
+            circuit_type: self.circuit_type,
+            status: self.status,
+            commit_rate: self.commit_rate,
+            description: self.description,
+            terminations: self
+                .terminations
+                .into_iter()
+                .map(|t| CircuitTermination {
+                    id: t.id,
+                    side: t.side,
+                    site: t.site_id.and_then(site_mapper),
+                    port: t.port,
+                    port_speed: t.port_speed,
+                    upstream_speed: t.upstream_speed,
+                    xconnect_id: t.xconnect_id,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Topology {
+    pub fn get_circuit(self: &Arc<Self>, idx: usize) -> Option<Arc<Circuit>> {
+        self.circuits.get(idx).cloned()
+    }
+    pub fn list_circuits_map<P: Fn(&Arc<Circuit>) -> Option<T>, T>(
+        self: &Arc<Self>,
+        filter: P,
+    ) -> Vec<T> {
+        self.circuits.iter().flat_map(filter).collect()
+    }
+
+    /// interface the termination ends on, following the cabling through patch panels
+    pub fn termination_interface(
+        self: &Arc<Self>,
+        termination: &CircuitTermination,
+    ) -> Option<PortIdx> {
+        let port = termination.port()?;
+        if self.get_port(&port)?.is_pass_through() {
+            self.trace_paths(port)
+                .into_iter()
+                .find_map(|path| path.far_end())
+        } else {
+            Some(port)
+        }
+    }
+
+    /// circuits with a termination at the given site
+    pub fn circuits_of_site(self: &Arc<Self>, site: usize) -> Vec<usize> {
+        self.circuits
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.terminations().iter().any(|t| t.site() == Some(site)))
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// circuits terminated on the given interface
+    pub fn circuits_of_port(self: &Arc<Self>, port: PortIdx) -> Vec<usize> {
+        self.circuits_of_port
+            .get(&port)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn index_circuits_of_port(self: &Arc<Self>) -> HashMap<PortIdx, Vec<usize>> {
+        let mut circuits_of_port: HashMap<PortIdx, Vec<usize>> = HashMap::new();
+        for (circuit_idx, circuit) in self.circuits.iter().enumerate() {
+            for port in circuit
+                .terminations()
+                .iter()
+                .flat_map(|t| self.termination_interface(t))
+            {
+                let circuits = circuits_of_port.entry(port).or_default();
+                if !circuits.contains(&circuit_idx) {
+                    circuits.push(circuit_idx);
+                }
+            }
+        }
+        circuits_of_port
+    }
+}
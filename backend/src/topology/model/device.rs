@@ -1,22 +1,38 @@
 use std::net::IpAddr;
 use std::ops::Deref;
+use std::str::FromStr;
 use std::sync::Arc;
 
-use ipnet::{IpNet, Ipv4Net, Ipv6Net};
+use ipnet::IpNet;
 
 use crate::error::BackendError;
 use crate::error::Result;
+use crate::topology::model::module::Module;
+use crate::topology::model::monitoring::MonitoringSettings;
+use crate::topology::model::power::{PowerOutlet, PowerPort};
+use crate::topology::model::rack::RackPosition;
+use crate::topology::model::virtual_chassis::ChassisMembership;
+use crate::topology::model::vlan::InterfaceVlans;
+use crate::topology::model::TopologyError;
 
 #[derive(Clone, Hash, Eq, PartialEq, Debug)]
 pub struct Device {
     name: String,
     id: u32,
     ports: Vec<Arc<DevicePort>>,
+    power_ports: Vec<PowerPort>,
+    power_outlets: Vec<PowerOutlet>,
     has_routeros: bool,
     location: Option<usize>,
     site: Option<usize>,
+    rack: Option<usize>,
+    rack_position: Option<RackPosition>,
     device_type: usize,
     device_category: DeviceCategory,
+    monitoring: MonitoringSettings,
+    tenant: Option<usize>,
+    virtual_chassis: Option<ChassisMembership>,
+    modules: Vec<Module>,
 }
 
 impl Device {
@@ -29,47 +45,123 @@ impl Device {
     pub fn has_routeros(&self) -> bool {
         self.has_routeros
     }
+    pub fn category(&self) -> DeviceCategory {
+        self.device_category
+    }
+    /// the netbox monitoring settings win, otherwise devices without a known category are pinged if they run RouterOS
+    pub fn can_ping(&self) -> bool {
+        if let Some(enabled) = self.monitoring.enabled() {
+            return enabled;
+        }
+        match self.device_category {
+            DeviceCategory::Unknown => self.has_routeros,
+            category => category.can_ping(),
+        }
+    }
+    pub fn monitoring(&self) -> &MonitoringSettings {
+        &self.monitoring
+    }
+    /// tenant assigned to the device itself
+    pub fn tenant(&self) -> Option<usize> {
+        self.tenant
+    }
+    /// membership in a stack of devices
+    pub fn virtual_chassis(&self) -> Option<ChassisMembership> {
+        self.virtual_chassis
+    }
+    /// modules installed in the module bays of this device
+    pub fn modules(&self) -> &Vec<Module> {
+        &self.modules
+    }
+    /// address to ping, the configured target or the loopback address
+    pub fn ping_target(&self) -> Option<IpAddr> {
+        self.monitoring
+            .ping_target()
+            .or_else(|| self.get_loopback_address())
+    }
 
+    /// loopback address, the global table is preferred over vrfs and ipv6 over ipv4
     pub fn get_loopback_address(&self) -> Option<IpAddr> {
         self.ports
             .iter()
-            .flat_map(|p| match p.deref() {
-                DevicePort::Interface {
-                    id: _,
-                    name: _,
-                    v4_address,
-                    v6_address,
-                    loopback: true,
-                } => v6_address
-                    .iter()
-                    .map(|a| IpAddr::V6(a.addr()))
-                    .chain(v4_address.iter().map(|a| IpAddr::V4(a.addr())))
-                    .next(),
-                _ => None,
-            })
-            .next()
+            .flat_map(|p| p.addresses().iter())
+            .filter(|a| a.role == AddressRole::Loopback)
+            .min_by_key(|a| (a.vrf.is_some(), a.net.addr().is_ipv4()))
+            .map(|a| a.net.addr())
     }
 
     pub fn ports(self: &Arc<Self>) -> Vec<Arc<DevicePort>> {
         self.ports.iter().cloned().collect()
     }
+    pub fn get_port(&self, idx: usize) -> Option<Arc<DevicePort>> {
+        self.ports.get(idx).cloned()
+    }
+    pub fn power_ports(&self) -> &Vec<PowerPort> {
+        &self.power_ports
+    }
+    pub fn power_outlets(&self) -> &Vec<PowerOutlet> {
+        &self.power_outlets
+    }
+    /// find the front port mapped onto the given rear port position
+    pub fn front_port_of_rear(&self, rear_idx: usize, position: Option<u32>) -> Option<usize> {
+        let mut candidates =
+            self.ports
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, port)| match port.deref() {
+                    DevicePort::FrontPort {
+                        rear_port_idx,
+                        rear_port_position,
+                        ..
+                    } if *rear_port_idx == rear_idx
+                        && position.map(|p| p == *rear_port_position).unwrap_or(true) =>
+                    {
+                        Some(idx)
+                    }
+                    _ => None,
+                });
+        let first = candidates.next();
+        // without a known position only an unambiguous mapping is valid
+        if candidates.next().is_some() {
+            None
+        } else {
+            first
+        }
+    }
     pub fn location(&self) -> Option<usize> {
         self.location
     }
+    pub fn site(&self) -> Option<usize> {
+        self.site
+    }
     pub fn device_type(&self) -> usize {
         self.device_type
     }
+    pub fn rack(&self) -> Option<usize> {
+        self.rack
+    }
+    pub fn rack_position(&self) -> Option<RackPosition> {
+        self.rack_position
+    }
 }
 
 pub struct DeviceBuilder {
     id: u32,
     name: String,
     ports: Vec<DevicePort>,
+    power_ports: Vec<PowerPort>,
+    power_outlets: Vec<PowerOutlet>,
     has_routeros: bool,
     site_id: Option<u32>,
     location_id: Option<u32>,
+    rack_id: Option<u32>,
+    rack_position: Option<RackPosition>,
     device_type: Option<u32>,
     device_category: Option<DeviceCategory>,
+    monitoring: MonitoringSettings,
+    tenant_id: Option<u32>,
+    virtual_chassis: Option<(u32, Option<u32>, Option<u32>)>,
+    modules: Vec<Module>,
 }
 
 impl DeviceBuilder {
@@ -81,24 +173,34 @@ impl DeviceBuilder {
         &mut self,
         id: u32,
         name: String,
-        v4_address: Option<Ipv4Net>,
-        v6_address: Option<Ipv6Net>,
-        loopback: bool,
+        addresses: Vec<InterfaceAddress>,
     ) -> usize {
         self.ports.push(DevicePort::Interface {
             id,
             name,
-            v4_address,
-            v6_address,
-            loopback,
+            addresses,
+            vlans: InterfaceVlans::default(),
         });
         self.ports.len() - 1
     }
-    pub fn append_front_port(&mut self, id: u32, name: String, rear_port_idx: usize) -> usize {
+    /// replace the vlan membership of a previously appended interface
+    pub fn set_vlans(&mut self, port_idx: usize, vlans: InterfaceVlans) {
+        if let Some(DevicePort::Interface { vlans: current, .. }) = self.ports.get_mut(port_idx) {
+            *current = vlans;
+        }
+    }
+    pub fn append_front_port(
+        &mut self,
+        id: u32,
+        name: String,
+        rear_port_idx: usize,
+        rear_port_position: u32,
+    ) -> usize {
         self.ports.push(DevicePort::FrontPort {
             id,
             name,
             rear_port_idx,
+            rear_port_position,
         });
         self.ports.len() - 1
     }
@@ -108,6 +210,21 @@ impl DeviceBuilder {
         self.ports.len() - 1
     }
 
+    pub fn append_power_port(&mut self, id: u32, name: String) -> usize {
+        self.power_ports.push(PowerPort::new(id, name));
+        self.power_ports.len() - 1
+    }
+    pub fn append_power_outlet(
+        &mut self,
+        id: u32,
+        name: String,
+        power_port: Option<usize>,
+    ) -> usize {
+        self.power_outlets
+            .push(PowerOutlet::new(id, name, power_port));
+        self.power_outlets.len() - 1
+    }
+
     pub fn append_port(&mut self, port: DevicePort) -> usize {
         self.ports.push(port);
         self.ports.len() - 1
@@ -117,35 +234,79 @@ impl DeviceBuilder {
         self.location_id = Some(id);
     }
     pub fn set_site(&mut self, id: u32) {
-        self.location_id = Some(id);
+        self.site_id = Some(id);
+    }
+    pub fn set_rack(&mut self, id: u32, position: Option<RackPosition>) {
+        self.rack_id = Some(id);
+        self.rack_position = position;
     }
     pub fn set_category(&mut self, category: DeviceCategory) {
         self.device_category = Some(category);
     }
+    pub fn set_monitoring(&mut self, monitoring: MonitoringSettings) {
+        self.monitoring = monitoring;
+    }
+    pub fn set_tenant(&mut self, id: u32) {
+        self.tenant_id = Some(id);
+    }
+    pub fn set_virtual_chassis(
+        &mut self,
+        chassis_id: u32,
+        position: Option<u32>,
+        priority: Option<u32>,
+    ) {
+        self.virtual_chassis = Some((chassis_id, position, priority));
+    }
+    pub fn virtual_chassis_id(&self) -> Option<u32> {
+        self.virtual_chassis.map(|(id, _, _)| id)
+    }
+    pub fn append_module(&mut self, module: Module) {
+        self.modules.push(module);
+    }
 
-    pub(crate) fn build<LM, SM, TM>(
+    pub(crate) fn build<LM, SM, RM, TM, VM, OM>(
         self,
         location_mapper: &LM,
         site_mapper: &SM,
+        rack_mapper: &RM,
         type_mapper: &TM,
+        chassis_mapper: &VM,
+        tenant_mapper: &OM,
     ) -> Result<Device>
     where
         LM: Fn(u32) -> Option<usize>,
         SM: Fn(u32) -> Option<usize>,
+        RM: Fn(u32) -> Option<usize>,
         TM: Fn(u32) -> Option<usize>,
+        VM: Fn(u32) -> Option<usize>,
+        OM: Fn(u32) -> Option<usize>,
     {
         Ok(Device {
             id: self.id,
             name: self.name,
             ports: self.ports.into_iter().map(Arc::new).collect(),
+            power_ports: self.power_ports,
+            power_outlets: self.power_outlets,
             has_routeros: self.has_routeros,
             location: self.location_id.and_then(location_mapper),
             site: self.site_id.and_then(site_mapper),
+            rack: self.rack_id.and_then(rack_mapper),
+            rack_position: self.rack_position,
             device_type: self
                 .device_type
                 .and_then(type_mapper)
                 .ok_or(BackendError::MissingDeviceType())?,
             device_category: self.device_category.unwrap_or_default(),
+            monitoring: self.monitoring,
+            tenant: self.tenant_id.and_then(tenant_mapper),
+            virtual_chassis: self.virtual_chassis.and_then(|(id, position, priority)| {
+                Some(ChassisMembership::new(
+                    chassis_mapper(id)?,
+                    position,
+                    priority,
+                ))
+            }),
+            modules: self.modules,
         })
     }
     pub fn new(id: u32, name: String, has_routeros: bool) -> Self {
@@ -153,16 +314,30 @@ impl DeviceBuilder {
             id,
             name,
             ports: vec![],
+            power_ports: vec![],
+            power_outlets: vec![],
             has_routeros,
             site_id: None,
             location_id: None,
+            rack_id: None,
+            rack_position: None,
             device_type: None,
             device_category: None,
+            monitoring: MonitoringSettings::default(),
+            tenant_id: None,
+            virtual_chassis: None,
+            modules: vec![],
         }
     }
     pub fn ports(&self) -> &Vec<DevicePort> {
         &self.ports
     }
+    pub fn power_ports(&self) -> &Vec<PowerPort> {
+        &self.power_ports
+    }
+    pub fn power_outlets(&self) -> &Vec<PowerOutlet> {
+        &self.power_outlets
+    }
 }
 
 #[derive(Clone, Hash, Eq, PartialEq, Debug)]
@@ -170,14 +345,14 @@ pub enum DevicePort {
     Interface {
         id: u32,
         name: String,
-        v4_address: Option<Ipv4Net>,
-        v6_address: Option<Ipv6Net>,
-        loopback: bool,
+        addresses: Vec<InterfaceAddress>,
+        vlans: InterfaceVlans,
     },
     FrontPort {
         id: u32,
         name: String,
         rear_port_idx: usize,
+        rear_port_position: u32,
     },
     RearPort {
         id: u32,
@@ -193,23 +368,86 @@ impl DevicePort {
             DevicePort::RearPort { name, .. } => name,
         }
     }
+    pub fn is_pass_through(&self) -> bool {
+        match self {
+            DevicePort::Interface { .. } => false,
+            DevicePort::FrontPort { .. } => true,
+            DevicePort::RearPort { .. } => true,
+        }
+    }
+    /// all addresses assigned to this port, empty for front and rear ports
+    pub fn addresses(&self) -> &[InterfaceAddress] {
+        match self {
+            DevicePort::Interface { addresses, .. } => addresses,
+            DevicePort::FrontPort { .. } => &[],
+            DevicePort::RearPort { .. } => &[],
+        }
+    }
+    /// vlan membership, `None` for front and rear ports
+    pub fn vlans(&self) -> Option<&InterfaceVlans> {
+        match self {
+            DevicePort::Interface { vlans, .. } => Some(vlans),
+            DevicePort::FrontPort { .. } => None,
+            DevicePort::RearPort { .. } => None,
+        }
+    }
     pub fn list_nets(&self) -> Vec<IpNet> {
+        self.addresses().iter().map(|a| a.net).collect()
+    }
+}
+
+/// ip address assigned to an interface
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub struct InterfaceAddress {
+    net: IpNet,
+    role: AddressRole,
+    vrf: Option<String>,
+}
+
+impl InterfaceAddress {
+    pub fn new(net: IpNet, role: AddressRole, vrf: Option<String>) -> Self {
+        Self { net, role, vrf }
+    }
+    pub fn net(&self) -> IpNet {
+        self.net
+    }
+    pub fn role(&self) -> AddressRole {
+        self.role
+    }
+    /// name of the vrf, `None` for the global table
+    pub fn vrf(&self) -> Option<&str> {
+        self.vrf.as_deref()
+    }
+}
+
+/// functional role of an address as maintained in netbox
+#[derive(Clone, Copy, Hash, Eq, PartialEq, Debug)]
+pub enum AddressRole {
+    /// address without explicit role
+    Primary,
+    Secondary,
+    Loopback,
+    Anycast,
+    Vip,
+    Vrrp,
+    Hsrp,
+    Glbp,
+    Carp,
+}
+
+impl AddressRole {
+    /// addresses with this role are intended to be present on multiple interfaces
+    pub fn is_shared(&self) -> bool {
         match self {
-            DevicePort::Interface {
-                v4_address,
-                v6_address,
-                ..
-            } => v4_address
-                .map(IpNet::V4)
-                .into_iter()
-                .chain(v6_address.map(IpNet::V6).into_iter())
-                .collect(),
-            DevicePort::FrontPort { .. } => {
-                vec![]
-            }
-            DevicePort::RearPort { .. } => {
-                vec![]
-            }
+            AddressRole::Primary => false,
+            AddressRole::Secondary => false,
+            AddressRole::Loopback => false,
+            AddressRole::Anycast => true,
+            AddressRole::Vip => true,
+            AddressRole::Vrrp => true,
+            AddressRole::Hsrp => true,
+            AddressRole::Glbp => true,
+            AddressRole::Carp => true,
         }
     }
 }
@@ -249,6 +487,7 @@ pub enum DeviceCategory {
     PatchPanel,
     Server,
     WallConnector,
+    AccessPoint,
     Unknown,
 }
 
@@ -258,6 +497,24 @@ impl Default for DeviceCategory {
     }
 }
 
+impl FromStr for DeviceCategory {
+    type Err = TopologyError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "switch" => Ok(DeviceCategory::Switch),
+            "router" => Ok(DeviceCategory::Router),
+            "user-device" => Ok(DeviceCategory::UserDevice),
+            "patch-panel" => Ok(DeviceCategory::PatchPanel),
+            "server" => Ok(DeviceCategory::Server),
+            "wall-connector" => Ok(DeviceCategory::WallConnector),
+            "access-point" => Ok(DeviceCategory::AccessPoint),
+            "unknown" => Ok(DeviceCategory::Unknown),
+            unknown => Err(TopologyError::UnknownDeviceCategory(unknown.to_string())),
+        }
+    }
+}
+
 impl DeviceCategory {
     pub fn can_ping(&self) -> bool {
         match self {
@@ -267,7 +524,73 @@ impl DeviceCategory {
             DeviceCategory::PatchPanel => false,
             DeviceCategory::Server => true,
             DeviceCategory::WallConnector => false,
+            DeviceCategory::AccessPoint => true,
             DeviceCategory::Unknown => false,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::topology::model::device::{AddressRole, DeviceBuilder, InterfaceAddress};
+    use crate::topology::model::validation::TopologyIssue;
+    use crate::topology::model::{DeviceType, Topology};
+
+    #[test]
+    fn test_keep_site_and_location_apart() {
+        let mut topology_builder = Topology::builder();
+        topology_builder.append_device_type(DeviceType::new("crs326".to_string(), 1, true));
+        let site_idx = topology_builder.append_site(20, "zurich".to_string(), String::new());
+        let location_idx = topology_builder.append_location(10, "basement".to_string(), vec![]);
+        let mut device_builder = DeviceBuilder::new(1, "sw01".to_string(), true);
+        device_builder.set_device_type(1);
+        device_builder.set_location(10);
+        device_builder.set_site(20);
+        let device_idx = topology_builder.append_device(device_builder);
+        let topology = topology_builder.build().unwrap();
+        let device = topology.get_device(device_idx).unwrap();
+        assert_eq!(Some(site_idx), device.site());
+        assert_eq!(Some(location_idx), device.location());
+    }
+
+    #[test]
+    fn test_prefer_global_loopback() {
+        let loopback = |net: &str, vrf: Option<&str>| {
+            InterfaceAddress::new(
+                net.parse().unwrap(),
+                AddressRole::Loopback,
+                vrf.map(str::to_string),
+            )
+        };
+        let mut topology_builder = Topology::builder();
+        topology_builder.append_device_type(DeviceType::new("rb5009".to_string(), 1, true));
+        let mut mgmt_only = DeviceBuilder::new(1, "rt01".to_string(), true);
+        mgmt_only.set_device_type(1);
+        mgmt_only.append_interface(
+            1,
+            "lo".to_string(),
+            vec![loopback("10.0.0.1/32", Some("mgmt"))],
+        );
+        let mgmt_only = topology_builder.append_device(mgmt_only);
+        let mut both = DeviceBuilder::new(2, "rt02".to_string(), true);
+        both.set_device_type(1);
+        both.append_interface(
+            2,
+            "lo".to_string(),
+            vec![
+                loopback("fd00::2/128", Some("mgmt")),
+                loopback("10.0.0.2/32", None),
+            ],
+        );
+        let both = topology_builder.append_device(both);
+        let topology = topology_builder.build().unwrap();
+
+        let address = |idx| topology.get_device(idx).unwrap().get_loopback_address();
+        assert_eq!(Some("10.0.0.1".parse().unwrap()), address(mgmt_only));
+        assert_eq!(Some("10.0.0.2".parse().unwrap()), address(both));
+        assert!(!topology.issues().iter().any(|issue| matches!(
+            issue,
+            TopologyIssue::MissingLoopback { device } if *device == mgmt_only
+        )));
+    }
+}
@@ -0,0 +1,364 @@
+use std::collections::BTreeMap;
+
+use crate::topology::model::device::{AddressRole, Device, DevicePort};
+use crate::topology::model::link::LinkMedium;
+use crate::topology::model::{PortIdx, Topology};
+
+#[derive(Clone, Copy, Hash, Eq, PartialEq, Debug)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub enum ChangedObject {
+    Device,
+    Port,
+    Link,
+    Address,
+}
+
+/// single difference between two consecutive topologies
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub struct TopologyChange {
+    kind: ChangeKind,
+    object: ChangedObject,
+    device_id: Option<u32>,
+    description: String,
+}
+
+impl TopologyChange {
+    pub fn kind(&self) -> ChangeKind {
+        self.kind
+    }
+    pub fn object(&self) -> ChangedObject {
+        self.object
+    }
+    /// netbox id of the affected device, `None` for links
+    pub fn device_id(&self) -> Option<u32> {
+        self.device_id
+    }
+    /// human readable description including the modified attributes
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+}
+
+/// comparable state of a single object, independent of the indexes within a topology
+struct ObjectState {
+    device_id: Option<u32>,
+    label: String,
+    attributes: Vec<(&'static str, String)>,
+}
+
+impl Topology {
+    /// changes leading from the previous topology to this one
+    pub fn changes_since(&self, previous: &Topology) -> Vec<TopologyChange> {
+        let old_objects = previous.object_states();
+        let new_objects = self.object_states();
+        let mut changes = Vec::new();
+        for ((object, key), old) in &old_objects {
+            let change = match new_objects.get(&(*object, key.clone())) {
+                None => Some((ChangeKind::Removed, old, format!("{} removed", old.label))),
+                Some(new) if new.attributes != old.attributes => {
+                    let modified: Vec<_> = old
+                        .attributes
+                        .iter()
+                        .zip(new.attributes.iter())
+                        .filter(|(o, n)| o != n)
+                        .map(|((name, o), (_, n))| format!("{name}: {o} -> {n}"))
+                        .collect();
+                    Some((
+                        ChangeKind::Modified,
+                        new,
+                        format!("{} modified: {}", new.label, modified.join(", ")),
+                    ))
+                }
+                Some(_) => None,
+            };
+            if let Some((kind, state, description)) = change {
+                changes.push(TopologyChange {
+                    kind,
+                    object: *object,
+                    device_id: state.device_id,
+                    description,
+                });
+            }
+        }
+        for ((object, key), new) in &new_objects {
+            if !old_objects.contains_key(&(*object, key.clone())) {
+                changes.push(TopologyChange {
+                    kind: ChangeKind::Added,
+                    object: *object,
+                    device_id: new.device_id,
+                    description: format!("{} added", new.label),
+                });
+            }
+        }
+        changes.sort_by_key(|c| c.object);
+        changes
+    }
+
+    fn object_states(&self) -> BTreeMap<(ChangedObject, String), ObjectState> {
+        let mut objects = BTreeMap::new();
+        for device in &self.devices {
+            objects.insert(
+                (ChangedObject::Device, device.id().to_string()),
+                ObjectState {
+                    device_id: Some(device.id()),
+                    label: format!("Device {}", device.name()),
+                    attributes: self.device_attributes(device),
+                },
+            );
+            for port in device.ports().iter() {
+                let port_label = format!("{}/{}", device.name(), port.get_name());
+                objects.insert(
+                    (ChangedObject::Port, port_key(device, port)),
+                    ObjectState {
+                        device_id: Some(device.id()),
+                        label: format!("Port {port_label}"),
+                        attributes: port_attributes(device, port),
+                    },
+                );
+                for address in port.addresses() {
+                    let vrf = address.vrf().unwrap_or("global");
+                    objects.insert(
+                        (
+                            ChangedObject::Address,
+                            format!("{}/{vrf}/{}", port_key(device, port), address.net()),
+                        ),
+                        ObjectState {
+                            device_id: Some(device.id()),
+                            label: format!("Address {} ({vrf}) on {port_label}", address.net()),
+                            attributes: vec![("role", role_name(address.role()).to_string())],
+                        },
+                    );
+                }
+            }
+        }
+        for link in &self.links {
+            let (Some(first), Some(last)) = (link.path().first(), link.path().last()) else {
+                continue;
+            };
+            let mut ends = [first.left_port(), last.right_port()];
+            ends.sort_by_key(|port| self.port_key_of(port));
+            let (medium, attributes) = match link.medium() {
+                LinkMedium::Cable(cable_id) => (
+                    cable_id
+                        .map(|id| format!("cable {id}"))
+                        .unwrap_or_else(|| "cable".to_string()),
+                    vec![],
+                ),
+                LinkMedium::Wireless(wireless) => (
+                    format!("wireless link {}", wireless.id()),
+                    vec![
+                        ("ssid", wireless.ssid().to_string()),
+                        ("status", format!("{:?}", wireless.status())),
+                    ],
+                ),
+            };
+            objects.insert(
+                (
+                    ChangedObject::Link,
+                    format!(
+                        "{medium}/{}/{}",
+                        self.port_key_of(&ends[0]),
+                        self.port_key_of(&ends[1])
+                    ),
+                ),
+                ObjectState {
+                    device_id: None,
+                    label: format!(
+                        "Link {} - {} ({medium})",
+                        self.port_label(&ends[0]),
+                        self.port_label(&ends[1])
+                    ),
+                    attributes,
+                },
+            );
+        }
+        objects
+    }
+
+    fn device_attributes(&self, device: &Device) -> Vec<(&'static str, String)> {
+        let type_name = self
+            .device_types
+            .get(device.device_type())
+            .map(|t| t.name().to_string());
+        let site_name = device
+            .site()
+            .and_then(|idx| self.sites.get(idx))
+            .map(|s| s.name().to_string());
+        let location_name = device
+            .location()
+            .and_then(|idx| self.locations.get(idx))
+            .map(|l| l.name().to_string());
+        let rack_name = device
+            .rack()
+            .and_then(|idx| self.racks.get(idx))
+            .map(|r| r.name().to_string());
+        vec![
+            ("name", device.name().to_string()),
+            ("type", type_name.unwrap_or_default()),
+            ("category", format!("{:?}", device.category())),
+            ("site", site_name.unwrap_or_default()),
+            ("location", location_name.unwrap_or_default()),
+            ("rack", rack_name.unwrap_or_default()),
+            (
+                "position",
+                device
+                    .rack_position()
+                    .map(|p| p.to_string())
+                    .unwrap_or_default(),
+            ),
+            ("monitored", device.can_ping().to_string()),
+            (
+                "tenant",
+                device
+                    .tenant()
+                    .and_then(|idx| self.tenants.get(idx))
+                    .map(|t| t.name().to_string())
+                    .unwrap_or_default(),
+            ),
+        ]
+    }
+
+    fn port_key_of(&self, port: &PortIdx) -> String {
+        self.devices
+            .get(port.device_idx())
+            .and_then(|d| Some(port_key(d, &*d.get_port(port.port_idx())?)))
+            .unwrap_or_default()
+    }
+
+    fn port_label(&self, port: &PortIdx) -> String {
+        self.devices
+            .get(port.device_idx())
+            .and_then(|d| {
+                let port = d.get_port(port.port_idx())?;
+                Some(format!("{}/{}", d.name(), port.get_name()))
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// netbox ids of device and port, port ids are only unique per port type
+fn port_key(device: &Device, port: &DevicePort) -> String {
+    let port_id = match port {
+        DevicePort::Interface { id, .. } => format!("interface:{id}"),
+        DevicePort::FrontPort { id, .. } => format!("front:{id}"),
+        DevicePort::RearPort { id, .. } => format!("rear:{id}"),
+    };
+    format!("{}/{port_id}", device.id())
+}
+
+fn port_attributes(device: &Device, port: &DevicePort) -> Vec<(&'static str, String)> {
+    let mut attributes = vec![("name", port.get_name().to_string())];
+    match port {
+        DevicePort::Interface { vlans, .. } => {
+            attributes.push((
+                "mode",
+                vlans.mode().map(|m| format!("{m:?}")).unwrap_or_default(),
+            ));
+            attributes.push((
+                "untagged vlan",
+                vlans.untagged().map(|v| v.to_string()).unwrap_or_default(),
+            ));
+            attributes.push(("tagged vlans", format!("{:?}", vlans.tagged())));
+        }
+        DevicePort::FrontPort {
+            rear_port_idx,
+            rear_port_position,
+            ..
+        } => {
+            let rear_name = device
+                .get_port(*rear_port_idx)
+                .map(|p| p.get_name().to_string());
+            attributes.push(("rear port", rear_name.unwrap_or_default()));
+            attributes.push(("position", rear_port_position.to_string()));
+        }
+        DevicePort::RearPort { .. } => {}
+    }
+    attributes
+}
+
+fn role_name(role: AddressRole) -> &'static str {
+    match role {
+        AddressRole::Primary => "primary",
+        AddressRole::Secondary => "secondary",
+        AddressRole::Loopback => "loopback",
+        AddressRole::Anycast => "anycast",
+        AddressRole::Vip => "vip",
+        AddressRole::Vrrp => "vrrp",
+        AddressRole::Hsrp => "hsrp",
+        AddressRole::Glbp => "glbp",
+        AddressRole::Carp => "carp",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::topology::model::device::{AddressRole, DeviceBuilder, InterfaceAddress};
+    use crate::topology::model::diff::{ChangeKind, ChangedObject};
+    use crate::topology::model::link::LinkBuilder;
+    use crate::topology::model::{DeviceType, Topology};
+
+    fn build(switch_name: &str, address: &str, cabled: bool) -> std::sync::Arc<Topology> {
+        let mut topology_builder = Topology::builder();
+        topology_builder.append_device_type(DeviceType::new("crs326".to_string(), 1, true));
+        let mut devices = Vec::new();
+        for (id, name) in [(1, "rt01"), (2, switch_name)] {
+            let mut device_builder = DeviceBuilder::new(id, name.to_string(), true);
+            device_builder.set_device_type(1);
+            device_builder.append_interface(
+                id * 10,
+                "ether1".to_string(),
+                vec![InterfaceAddress::new(
+                    address.parse().unwrap(),
+                    AddressRole::Primary,
+                    None,
+                )],
+            );
+            devices.push(topology_builder.append_device(device_builder));
+        }
+        if cabled {
+            let mut link_builder = LinkBuilder::new();
+            link_builder.set_cable_id(7);
+            link_builder
+                .append_segment(topology_builder.devices(), devices[0], 0, devices[1], 0)
+                .unwrap();
+            topology_builder.append_link(link_builder.build());
+        }
+        topology_builder.build().unwrap()
+    }
+
+    #[test]
+    fn test_detect_changes() {
+        let old = build("sw01", "10.0.0.1/24", true);
+        assert!(old.changes_since(&old).is_empty());
+
+        let new = build("sw02", "10.0.1.1/24", false);
+        let changes = new.changes_since(&old);
+        let summary: Vec<_> = changes.iter().map(|c| (c.object(), c.kind())).collect();
+        assert_eq!(
+            vec![
+                (ChangedObject::Device, ChangeKind::Modified),
+                (ChangedObject::Link, ChangeKind::Removed),
+                (ChangedObject::Address, ChangeKind::Removed),
+                (ChangedObject::Address, ChangeKind::Removed),
+                (ChangedObject::Address, ChangeKind::Added),
+                (ChangedObject::Address, ChangeKind::Added),
+            ],
+            summary
+        );
+        assert_eq!(
+            "Device sw02 modified: name: sw01 -> sw02",
+            changes[0].description()
+        );
+        assert_eq!(Some(2), changes[0].device_id());
+        assert_eq!(
+            "Link rt01/ether1 - sw01/ether1 (cable 7) removed",
+            changes[1].description()
+        );
+    }
+}
@@ -3,20 +3,105 @@ use crate::topology::model::TopologyError;
 
 #[derive(Clone, Hash, Eq, PartialEq, Debug)]
 pub struct Link {
+    medium: LinkMedium,
     path: Vec<LinkSegment>,
 }
 
+/// physical medium a link is established over
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub enum LinkMedium {
+    /// cable, with its netbox id if the link was built from netbox
+    Cable(Option<u32>),
+    /// radio link between two wireless interfaces
+    Wireless(WirelessLink),
+}
+
+/// metadata of a netbox wireless link
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub struct WirelessLink {
+    id: u32,
+    ssid: String,
+    status: WirelessLinkStatus,
+    description: String,
+}
+
+impl WirelessLink {
+    pub fn new(id: u32, ssid: String, status: WirelessLinkStatus, description: String) -> Self {
+        Self {
+            id,
+            ssid,
+            status,
+            description,
+        }
+    }
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn ssid(&self) -> &str {
+        &self.ssid
+    }
+    pub fn status(&self) -> WirelessLinkStatus {
+        self.status
+    }
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+}
+
+#[derive(Clone, Copy, Hash, Eq, PartialEq, Debug)]
+pub enum WirelessLinkStatus {
+    Connected,
+    Planned,
+    Decommissioning,
+    /// status not known to this application, reported as topology issue
+    Unknown,
+}
+
 impl Link {
     pub fn path(&self) -> &Vec<LinkSegment> {
         &self.path
     }
+    pub fn medium(&self) -> &LinkMedium {
+        &self.medium
+    }
+    pub fn cable_id(&self) -> Option<u32> {
+        match &self.medium {
+            LinkMedium::Cable(cable_id) => *cable_id,
+            LinkMedium::Wireless(_) => None,
+        }
+    }
+    pub fn wireless(&self) -> Option<&WirelessLink> {
+        match &self.medium {
+            LinkMedium::Cable(_) => None,
+            LinkMedium::Wireless(wireless) => Some(wireless),
+        }
+    }
+    /// find the port on the other end of the segment connected to the given port
+    pub fn peer_of(&self, port: &PortIdx) -> Option<PortIdx> {
+        self.path.iter().find_map(|segment| {
+            if segment.left_port == *port {
+                Some(segment.right_port)
+            } else if segment.right_port == *port {
+                Some(segment.left_port)
+            } else {
+                None
+            }
+        })
+    }
 }
 
 pub struct LinkBuilder {
+    medium: LinkMedium,
     path: Vec<LinkSegment>,
 }
 
 impl LinkBuilder {
+    pub fn set_cable_id(&mut self, cable_id: u32) {
+        self.medium = LinkMedium::Cable(Some(cable_id));
+    }
+    pub fn set_wireless(&mut self, wireless: WirelessLink) {
+        self.medium = LinkMedium::Wireless(wireless);
+    }
     pub fn append_segment(
         &mut self,
         devices: &[DeviceBuilder],
@@ -31,7 +116,7 @@ impl LinkBuilder {
         if left_device.ports().len() <= left_port {
             return Err(TopologyError::MissingPortReference {
                 device_idx: left_device_idx,
-                port_idx: left_device_idx,
+                port_idx: left_port,
             });
         }
         let right_device = devices
@@ -40,7 +125,7 @@ impl LinkBuilder {
         if right_device.ports().len() <= right_port {
             return Err(TopologyError::MissingPortReference {
                 device_idx: right_device_idx,
-                port_idx: right_device_idx,
+                port_idx: right_port,
             });
         }
         if let Some(last_segment) = self.path.last() {
@@ -59,10 +144,16 @@ impl LinkBuilder {
         Ok(self.path.len() - 1)
     }
     pub fn build(self) -> Link {
-        Link { path: self.path }
+        Link {
+            medium: self.medium,
+            path: self.path,
+        }
     }
     pub fn new() -> Self {
-        Self { path: vec![] }
+        Self {
+            medium: LinkMedium::Cable(None),
+            path: vec![],
+        }
     }
 }
 
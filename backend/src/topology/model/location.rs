@@ -3,14 +3,21 @@ pub struct Location {
     id: u32,
     name: String,
     site: Option<usize>,
+    parent: Option<usize>,
+    children: Vec<usize>,
     devices: Vec<usize>,
+    racks: Vec<usize>,
+    tenant: Option<usize>,
 }
 
 pub struct LocationBuilder {
     id: u32,
     name: String,
     site: Option<usize>,
+    parent_id: Option<u32>,
     devices: Vec<usize>,
+    racks: Vec<usize>,
+    tenant_id: Option<u32>,
 }
 
 impl LocationBuilder {
@@ -18,13 +25,45 @@ impl LocationBuilder {
         self.site = Some(site_idx);
         self
     }
-    pub fn build(mut self) -> Location {
+    pub fn racks(&mut self, racks: Vec<usize>) -> &mut Self {
+        self.racks = racks;
+        self
+    }
+    pub fn parent(&mut self, parent_id: u32) -> &mut Self {
+        self.parent_id = Some(parent_id);
+        self
+    }
+    pub fn tenant(&mut self, tenant_id: u32) -> &mut Self {
+        self.tenant_id = Some(tenant_id);
+        self
+    }
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn parent_id(&self) -> Option<u32> {
+        self.parent_id
+    }
+    pub fn build<LM, TM>(
+        mut self,
+        location_mapper: &LM,
+        tenant_mapper: &TM,
+        children: Vec<usize>,
+    ) -> Location
+    where
+        LM: Fn(u32) -> Option<usize>,
+        TM: Fn(u32) -> Option<usize>,
+    {
         self.devices.shrink_to_fit();
+        self.racks.shrink_to_fit();
         Location {
             id: self.id,
             name: self.name,
             site: self.site,
+            parent: self.parent_id.and_then(location_mapper),
+            children,
             devices: self.devices,
+            racks: self.racks,
+            tenant: self.tenant_id.and_then(tenant_mapper),
         }
     }
 }
@@ -35,7 +74,10 @@ impl Location {
             id,
             name,
             site: None,
+            parent_id: None,
             devices,
+            racks: vec![],
+            tenant_id: None,
         }
     }
 
@@ -49,8 +91,24 @@ impl Location {
     pub fn site(&self) -> Option<usize> {
         self.site
     }
+    /// enclosing location
+    pub fn parent(&self) -> Option<usize> {
+        self.parent
+    }
+    /// locations nested directly below this one
+    pub fn children(&self) -> &Vec<usize> {
+        &self.children
+    }
 
     pub fn devices(&self) -> &Vec<usize> {
         &self.devices
     }
+
+    pub fn racks(&self) -> &Vec<usize> {
+        &self.racks
+    }
+    /// tenant assigned to the location itself
+    pub fn tenant(&self) -> Option<usize> {
+        self.tenant
+    }
 }
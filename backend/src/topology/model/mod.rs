@@ -1,25 +1,53 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::net::IpAddr;
 use std::sync::Arc;
 
+use ipnet::IpNet;
 use log::warn;
 use thiserror::Error;
 
 pub use device::{Device, DevicePort, PortIdx};
-use link::Link;
+pub use link::Link;
 pub use site::Site;
 use site::SiteBuilder;
 
 use crate::error::Result;
+use crate::topology::model::circuit::{Circuit, CircuitBuilder};
 use crate::topology::model::device::DeviceBuilder;
 pub use crate::topology::model::device_type::DeviceType;
 pub use crate::topology::model::location::Location;
 use crate::topology::model::location::LocationBuilder;
+use crate::topology::model::power::{PowerFeed, PowerFeedBuilder, PowerSource};
+pub use crate::topology::model::prefix::Prefix;
+use crate::topology::model::prefix::PrefixBuilder;
+pub use crate::topology::model::rack::Rack;
+use crate::topology::model::rack::{RackBuilder, RackMount};
+pub use crate::topology::model::region::Region;
+use crate::topology::model::region::RegionBuilder;
+pub use crate::topology::model::tenant::Tenant;
+use crate::topology::model::validation::{validate, TopologyIssue};
+use crate::topology::model::virtual_chassis::{VirtualChassis, VirtualChassisBuilder};
+use crate::topology::model::vlan::{InterfaceMode, VlanBuilder};
+pub use crate::topology::model::vlan::{Vlan, VlanGroup};
 
+pub mod circuit;
 pub mod device;
 pub mod device_type;
+pub mod diff;
 pub mod link;
 pub mod location;
+pub mod module;
+pub mod monitoring;
+pub mod path;
+pub mod power;
+pub mod prefix;
+pub mod rack;
+pub mod region;
 pub mod site;
+pub mod tenant;
+pub mod validation;
+pub mod virtual_chassis;
+pub mod vlan;
 
 #[derive(Error, Debug)]
 pub enum TopologyError {
@@ -32,6 +60,12 @@ pub enum TopologyError {
         last_device: usize,
         current_device: usize,
     },
+    #[error("Unknown device category: {0}")]
+    UnknownDeviceCategory(String),
+    #[error("Invalid role mapping {0}, expected role=category")]
+    InvalidRoleMapping(String),
+    #[error("Unknown criticality: {0}")]
+    UnknownCriticality(String),
 }
 
 #[derive(Debug)]
@@ -41,10 +75,31 @@ pub struct Topology {
     links: Vec<Arc<Link>>,
     sites: Vec<Arc<Site>>,
     locations: Vec<Arc<Location>>,
-    link_index: HashMap<PortIdx, usize>,
+    racks: Vec<Arc<Rack>>,
+    vlans: Vec<Arc<Vlan>>,
+    vlan_groups: Vec<Arc<VlanGroup>>,
+    prefixes: Vec<Arc<Prefix>>,
+    regions: Vec<Arc<Region>>,
+    site_groups: Vec<Arc<Region>>,
+    power_feeds: Vec<Arc<PowerFeed>>,
+    circuits: Vec<Arc<Circuit>>,
+    virtual_chassis: Vec<Arc<VirtualChassis>>,
+    tenants: Vec<Arc<Tenant>>,
+    link_index: HashMap<PortIdx, Vec<usize>>,
     device_index: HashMap<u32, usize>,
     site_index: HashMap<u32, usize>,
     location_index: HashMap<u32, usize>,
+    rack_index: HashMap<u32, usize>,
+    vlan_index: HashMap<u32, usize>,
+    ports_of_vlan: HashMap<usize, Vec<PortIdx>>,
+    region_index: HashMap<u32, usize>,
+    tenant_index: HashMap<u32, usize>,
+    prefix_index: HashMap<(Option<String>, IpNet), usize>,
+    address_index: HashMap<(Option<String>, IpAddr), Vec<PortIdx>>,
+    power_sources: HashMap<(usize, usize), PowerSource>,
+    power_consumers: HashMap<PowerSource, Vec<(usize, usize)>>,
+    circuits_of_port: HashMap<PortIdx, Vec<usize>>,
+    issues: Vec<TopologyIssue>,
 }
 
 impl Topology {
@@ -60,6 +115,9 @@ impl Topology {
     pub fn get_device_by_id(self: &Arc<Self>, key: u32) -> Option<Arc<Device>> {
         self.get_device(*self.device_index.get(&key)?)
     }
+    pub fn get_device_idx(self: &Arc<Self>, key: u32) -> Option<usize> {
+        self.device_index.get(&key).copied()
+    }
     pub fn list_devices(self: &Arc<Self>) -> Vec<Arc<Device>> {
         self.devices.clone()
     }
@@ -76,6 +134,9 @@ impl Topology {
     pub fn get_site_by_id<'a>(self: &'a Arc<Self>, key: u32) -> Option<&'a Arc<Site>> {
         self.get_site(*self.site_index.get(&key)?)
     }
+    pub fn get_site_idx(self: &Arc<Self>, key: u32) -> Option<usize> {
+        self.site_index.get(&key).copied()
+    }
     pub fn list_sites(self: &Arc<Self>) -> Vec<Arc<Site>> {
         self.list_sites_map(|s| Some(s.clone()))
     }
@@ -88,6 +149,18 @@ impl Topology {
     pub fn get_location(self: &Arc<Self>, idx: usize) -> Option<Arc<Location>> {
         self.locations.get(idx).cloned()
     }
+    /// devices on the location and all locations nested below it
+    pub fn devices_within_location(self: &Arc<Self>, idx: usize) -> Vec<usize> {
+        let mut devices = Vec::new();
+        let mut pending = vec![idx];
+        while let Some(idx) = pending.pop() {
+            if let Some(location) = self.locations.get(idx) {
+                devices.extend(location.devices());
+                pending.extend(location.children());
+            }
+        }
+        devices
+    }
     pub fn list_locations_map<P: Fn(&Arc<Location>) -> Option<T>, T>(
         self: &Arc<Self>,
         filter: P,
@@ -97,6 +170,79 @@ impl Topology {
     pub fn get_location_by_id(self: &Arc<Self>, key: u32) -> Option<Arc<Location>> {
         self.get_location(self.location_index.get(&key)?.clone())
     }
+    pub fn get_location_idx(self: &Arc<Self>, key: u32) -> Option<usize> {
+        self.location_index.get(&key).copied()
+    }
+    pub fn get_rack(self: &Arc<Self>, idx: usize) -> Option<Arc<Rack>> {
+        self.racks.get(idx).cloned()
+    }
+    pub fn get_rack_by_id(self: &Arc<Self>, key: u32) -> Option<Arc<Rack>> {
+        self.get_rack(*self.rack_index.get(&key)?)
+    }
+    pub fn list_racks_map<P: Fn(&Arc<Rack>) -> Option<T>, T>(
+        self: &Arc<Self>,
+        filter: P,
+    ) -> Vec<T> {
+        self.racks.iter().flat_map(filter).collect()
+    }
+    pub fn get_link(self: &Arc<Self>, idx: usize) -> Option<Arc<Link>> {
+        self.links.get(idx).cloned()
+    }
+    /// links of the port, a cable with a single termination on one end connects it to all
+    /// terminations on the other end
+    pub fn get_links_of_port(self: &Arc<Self>, port: &PortIdx) -> Vec<Arc<Link>> {
+        self.link_index
+            .get(port)
+            .map(|links| links.iter().flat_map(|idx| self.get_link(*idx)).collect())
+            .unwrap_or_default()
+    }
+    pub fn list_links(self: &Arc<Self>) -> Vec<Arc<Link>> {
+        self.links.clone()
+    }
+    pub fn get_vlan(self: &Arc<Self>, idx: usize) -> Option<Arc<Vlan>> {
+        self.vlans.get(idx).cloned()
+    }
+    pub fn get_vlan_by_id(self: &Arc<Self>, key: u32) -> Option<Arc<Vlan>> {
+        self.get_vlan(*self.vlan_index.get(&key)?)
+    }
+    pub fn get_vlan_idx(self: &Arc<Self>, key: u32) -> Option<usize> {
+        self.vlan_index.get(&key).copied()
+    }
+    pub fn list_vlans_map<P: Fn(&Arc<Vlan>) -> Option<T>, T>(
+        self: &Arc<Self>,
+        filter: P,
+    ) -> Vec<T> {
+        self.vlans.iter().flat_map(filter).collect()
+    }
+    pub fn get_vlan_group(self: &Arc<Self>, idx: usize) -> Option<Arc<VlanGroup>> {
+        self.vlan_groups.get(idx).cloned()
+    }
+    /// interfaces carrying the vlan tagged or untagged
+    pub fn ports_of_vlan(self: &Arc<Self>, idx: usize) -> &[PortIdx] {
+        self.ports_of_vlan
+            .get(&idx)
+            .map(|ports| ports.as_slice())
+            .unwrap_or_default()
+    }
+    pub fn get_region(self: &Arc<Self>, idx: usize) -> Option<Arc<Region>> {
+        self.regions.get(idx).cloned()
+    }
+    pub fn get_region_by_id(self: &Arc<Self>, key: u32) -> Option<Arc<Region>> {
+        self.get_region(*self.region_index.get(&key)?)
+    }
+    pub fn list_regions_map<P: Fn(&Arc<Region>) -> Option<T>, T>(
+        self: &Arc<Self>,
+        filter: P,
+    ) -> Vec<T> {
+        self.regions.iter().flat_map(filter).collect()
+    }
+    pub fn get_site_group(self: &Arc<Self>, idx: usize) -> Option<Arc<Region>> {
+        self.site_groups.get(idx).cloned()
+    }
+    /// inconsistencies found while building the topology
+    pub fn issues(&self) -> &Vec<TopologyIssue> {
+        &self.issues
+    }
 }
 
 #[derive(Default)]
@@ -106,6 +252,17 @@ pub struct TopologyBuilder {
     links: Vec<Link>,
     sites: Vec<SiteBuilder>,
     locations: Vec<LocationBuilder>,
+    racks: Vec<RackBuilder>,
+    vlans: Vec<VlanBuilder>,
+    prefixes: Vec<PrefixBuilder>,
+    regions: Vec<RegionBuilder>,
+    site_groups: Vec<RegionBuilder>,
+    power_feeds: Vec<PowerFeedBuilder>,
+    power_connections: Vec<((usize, usize), PowerSource)>,
+    circuits: Vec<CircuitBuilder>,
+    virtual_chassis: Vec<VirtualChassisBuilder>,
+    tenants: BTreeMap<u32, Tenant>,
+    issues: Vec<TopologyIssue>,
 }
 
 impl TopologyBuilder {
@@ -130,6 +287,109 @@ impl TopologyBuilder {
         self.locations.len() - 1
     }
 
+    pub fn append_vlan(&mut self, vlan: VlanBuilder) -> usize {
+        self.vlans.push(vlan);
+        self.vlans.len() - 1
+    }
+    pub fn append_region(&mut self, region: RegionBuilder) -> usize {
+        self.regions.push(region);
+        self.regions.len() - 1
+    }
+    pub fn append_site_group(&mut self, site_group: RegionBuilder) -> usize {
+        self.site_groups.push(site_group);
+        self.site_groups.len() - 1
+    }
+    pub fn set_parent_of_location(&mut self, location_idx: usize, parent_id: u32) {
+        if let Some(location) = self.locations.get_mut(location_idx) {
+            location.parent(parent_id);
+        } else {
+            warn!("Location {location_idx} not found");
+        }
+    }
+    pub fn set_region_of_site(&mut self, site_idx: usize, region_id: u32) {
+        if let Some(site) = self.sites.get_mut(site_idx) {
+            site.set_region(region_id);
+        } else {
+            warn!("Site {site_idx} not found");
+        }
+    }
+    /// tenants are embedded into the objects they own, so the first appended one of an id is kept
+    pub fn append_tenant(&mut self, tenant: Tenant) {
+        self.tenants.entry(tenant.id()).or_insert(tenant);
+    }
+    pub fn set_tenant_of_site(&mut self, site_idx: usize, tenant_id: u32) {
+        if let Some(site) = self.sites.get_mut(site_idx) {
+            site.set_tenant(tenant_id);
+        } else {
+            warn!("Site {site_idx} not found");
+        }
+    }
+    pub fn set_tenant_of_location(&mut self, location_idx: usize, tenant_id: u32) {
+        if let Some(location) = self.locations.get_mut(location_idx) {
+            location.tenant(tenant_id);
+        } else {
+            warn!("Location {location_idx} not found");
+        }
+    }
+    pub fn set_group_of_site(&mut self, site_idx: usize, group_id: u32) {
+        if let Some(site) = self.sites.get_mut(site_idx) {
+            site.set_group(group_id);
+        } else {
+            warn!("Site {site_idx} not found");
+        }
+    }
+    pub fn append_power_feed(&mut self, power_feed: PowerFeedBuilder) -> usize {
+        self.power_feeds.push(power_feed);
+        self.power_feeds.len() - 1
+    }
+    /// connect a power port of a device to its source
+    pub fn append_power_connection(
+        &mut self,
+        device: usize,
+        power_port: usize,
+        source: PowerSource,
+    ) {
+        self.power_connections.push(((device, power_port), source));
+    }
+    pub fn append_circuit(&mut self, circuit: CircuitBuilder) -> usize {
+        self.circuits.push(circuit);
+        self.circuits.len() - 1
+    }
+    /// record the device port a circuit termination is cabled to
+    pub fn set_circuit_termination_port(
+        &mut self,
+        circuit_idx: usize,
+        termination_idx: usize,
+        port: PortIdx,
+    ) {
+        if let Some(circuit) = self.circuits.get_mut(circuit_idx) {
+            circuit.set_termination_port(termination_idx, port);
+        }
+    }
+    pub fn append_virtual_chassis(&mut self, virtual_chassis: VirtualChassisBuilder) -> usize {
+        self.virtual_chassis.push(virtual_chassis);
+        self.virtual_chassis.len() - 1
+    }
+    pub fn append_prefix(&mut self, prefix: PrefixBuilder) -> usize {
+        self.prefixes.push(prefix);
+        self.prefixes.len() - 1
+    }
+    pub fn append_vlan_group(&mut self, site_idx: usize, id: u32, name: String, slug: String) {
+        if let Some(site) = self.sites.get_mut(site_idx) {
+            site.append_vlan_group(id, name, slug);
+        } else {
+            warn!("Site {site_idx} not found");
+        }
+    }
+
+    pub fn append_issue(&mut self, issue: TopologyIssue) {
+        self.issues.push(issue);
+    }
+    pub fn append_rack(&mut self, rack: RackBuilder) -> usize {
+        self.racks.push(rack);
+        self.racks.len() - 1
+    }
+
     pub fn set_site_of_location(&mut self, location_idx: usize, site_idx: usize) {
         Self::modify_site_of_location(&mut self.locations, location_idx, site_idx)
     }
@@ -153,6 +413,25 @@ impl TopologyBuilder {
             device_type_index.insert(device_type.id(), idx);
             device_types.push(Arc::new(device_type));
         }
+        let mut tenants = Vec::with_capacity(self.tenants.len());
+        let mut tenant_index = HashMap::new();
+        for (tenant_idx, tenant) in self.tenants.into_values().enumerate() {
+            tenant_index.insert(tenant.id(), tenant_idx);
+            tenants.push(Arc::new(tenant));
+        }
+        let tenant_mapper = |id| tenant_index.get(&id).copied();
+
+        let mut rack_index = HashMap::new();
+        let mut racks_of_location: HashMap<u32, Vec<usize>> = HashMap::new();
+        for (rack_idx, rack) in self.racks.iter().enumerate() {
+            rack_index.insert(rack.id(), rack_idx);
+            if let Some(location_id) = rack.location_id() {
+                racks_of_location
+                    .entry(location_id)
+                    .or_default()
+                    .push(rack_idx);
+            }
+        }
 
         let mut locations = Vec::with_capacity(self.locations.len());
         let mut location_index = HashMap::new();
@@ -165,91 +444,358 @@ impl TopologyBuilder {
                 Self::modify_site_of_location(&mut self.locations, location_idx, site_idx);
             }
         }
-        for location in self.locations {
+        for (location_idx, location) in self.locations.iter().enumerate() {
+            location_index.insert(location.id(), location_idx);
+        }
+        let location_mapper = |id| location_index.get(&id).copied();
+        let mut children_of_location: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (location_idx, location) in self.locations.iter().enumerate() {
+            if let Some(parent_idx) = location.parent_id().and_then(location_mapper) {
+                children_of_location
+                    .entry(parent_idx)
+                    .or_default()
+                    .push(location_idx);
+            }
+        }
+        for mut location in self.locations {
             let location_idx = locations.len();
-            let location = location.build();
+            if let Some(racks) = racks_of_location.remove(&location.id()) {
+                location.racks(racks);
+            }
+            let children = children_of_location
+                .remove(&location_idx)
+                .unwrap_or_default();
+            let location = location.build(&location_mapper, &tenant_mapper, children);
             if let Some(site_idx) = location.site() {
                 locations_of_site
                     .entry(site_idx)
                     .or_default()
                     .insert(location_idx);
             }
-            location_index.insert(location.id(), location_idx);
             locations.push(Arc::new(location));
         }
 
+        let mut vlan_group_index = HashMap::new();
+        let mut vlan_groups_of_site: HashMap<usize, Vec<usize>> = HashMap::new();
+        let mut vlan_group_entries = Vec::new();
+        for (site_idx, site) in self.sites.iter().enumerate() {
+            for (id, name, slug) in site.vlan_groups().iter().cloned() {
+                let group_idx = vlan_group_entries.len();
+                vlan_group_index.insert(id, group_idx);
+                vlan_groups_of_site
+                    .entry(site_idx)
+                    .or_default()
+                    .push(group_idx);
+                vlan_group_entries.push((id, name, slug, site_idx));
+            }
+        }
+
+        let mut sites_of_region: HashMap<u32, Vec<usize>> = HashMap::new();
+        let mut sites_of_group: HashMap<u32, Vec<usize>> = HashMap::new();
+        for (site_idx, site) in self.sites.iter().enumerate() {
+            if let Some(region_id) = site.region_id() {
+                sites_of_region.entry(region_id).or_default().push(site_idx);
+            }
+            if let Some(group_id) = site.group_id() {
+                sites_of_group.entry(group_id).or_default().push(site_idx);
+            }
+        }
+        let (regions, region_index) = Self::build_regions(self.regions, sites_of_region);
+        let (site_groups, site_group_index) = Self::build_regions(self.site_groups, sites_of_group);
+
         let mut sites = Vec::with_capacity(self.sites.len());
         let mut site_index = HashMap::new();
+        let region_mapper = |id| region_index.get(&id).copied();
+        let site_group_mapper = |id| site_group_index.get(&id).copied();
         for (site_idx, site) in self.sites.into_iter().enumerate() {
             let locations = locations_of_site.remove(&site_idx).unwrap_or_default();
-            let (id, name, address) = site.destruct();
-            sites.push(Arc::new(Site::new(id, name, address, locations)));
-            site_index.insert(id, site_idx);
+            let vlan_groups = vlan_groups_of_site.remove(&site_idx).unwrap_or_default();
+            let site = site.build(
+                &region_mapper,
+                &site_group_mapper,
+                &tenant_mapper,
+                locations,
+                vlan_groups,
+            );
+            site_index.insert(site.id(), site_idx);
+            sites.push(Arc::new(site));
         }
+
+        let mut vlans = Vec::with_capacity(self.vlans.len());
+        let mut vlan_index = HashMap::new();
+        let mut vlans_of_group: HashMap<usize, Vec<usize>> = HashMap::new();
+        let group_mapper = |id| vlan_group_index.get(&id).copied();
+        let site_mapper = |id| site_index.get(&id).copied();
+        for vlan in self.vlans {
+            let vlan = vlan.build(&group_mapper, &site_mapper);
+            let vlan_idx = vlans.len();
+            if let Some(group_idx) = vlan.group() {
+                vlans_of_group.entry(group_idx).or_default().push(vlan_idx);
+            }
+            vlan_index.insert(vlan.id(), vlan_idx);
+            vlans.push(Arc::new(vlan));
+        }
+        // site a vlan is available on, directly or through its group, `None` for global vlans
+        let site_of_vlan: Vec<Option<usize>> = vlans
+            .iter()
+            .map(|vlan| {
+                vlan.site().or_else(|| {
+                    vlan.group()
+                        .and_then(|group_idx| vlan_group_entries.get(group_idx))
+                        .map(|(_, _, _, site_idx)| *site_idx)
+                })
+            })
+            .collect();
+        let vlan_groups = vlan_group_entries
+            .into_iter()
+            .enumerate()
+            .map(|(group_idx, (id, name, slug, site_idx))| {
+                let vlans = vlans_of_group.remove(&group_idx).unwrap_or_default();
+                Arc::new(VlanGroup::new(id, name, slug, site_idx, vlans))
+            })
+            .collect();
+
         let mut devices = Vec::with_capacity(self.devices.len());
         let mut device_index = HashMap::new();
-        let location_mapper = |id| location_index.get(&id).copied();
         let site_mapper = |id| site_index.get(&id).copied();
+        let rack_mapper = |id| rack_index.get(&id).copied();
         let type_mapper = |id| device_type_index.get(&id).copied();
+        let chassis_index: HashMap<u32, usize> = self
+            .virtual_chassis
+            .iter()
+            .enumerate()
+            .map(|(idx, chassis)| (chassis.id(), idx))
+            .collect();
+        let chassis_mapper = |id| chassis_index.get(&id).copied();
+        let mut members_of_chassis: HashMap<usize, Vec<(Option<u32>, usize)>> = HashMap::new();
+        let mut devices_of_rack: HashMap<usize, Vec<RackMount>> = HashMap::new();
+        let mut ports_of_vlan: HashMap<usize, Vec<PortIdx>> = HashMap::new();
+        let mut address_index: HashMap<_, Vec<PortIdx>> = HashMap::new();
         for device_builder in self.devices {
-            let device = device_builder.build(&location_mapper, &site_mapper, &type_mapper)?;
-            device_index.insert(device.id(), devices.len());
-            devices.push(Arc::new(device));
+            let device = Arc::new(device_builder.build(
+                &location_mapper,
+                &site_mapper,
+                &rack_mapper,
+                &type_mapper,
+                &chassis_mapper,
+                &tenant_mapper,
+            )?);
+            let device_idx = devices.len();
+            for (port_idx, port) in device.ports().iter().enumerate() {
+                for address in port.addresses() {
+                    address_index
+                        .entry((address.vrf().map(str::to_string), address.net().addr()))
+                        .or_default()
+                        .push(PortIdx::new(device_idx, port_idx));
+                }
+                let Some(port_vlans) = port.vlans() else {
+                    continue;
+                };
+                let mut port_vlan_idxs: Vec<usize> = port_vlans
+                    .untagged()
+                    .iter()
+                    .chain(port_vlans.tagged())
+                    .flat_map(|vlan_id| vlan_index.get(vlan_id).copied())
+                    .collect();
+                if port_vlans.mode() == Some(InterfaceMode::TaggedAll) {
+                    port_vlan_idxs.extend(site_of_vlan.iter().enumerate().filter_map(
+                        |(vlan_idx, site)| {
+                            (site.is_none() || *site == device.site()).then_some(vlan_idx)
+                        },
+                    ));
+                    port_vlan_idxs.sort_unstable();
+                    port_vlan_idxs.dedup();
+                }
+                for vlan_idx in port_vlan_idxs {
+                    ports_of_vlan
+                        .entry(vlan_idx)
+                        .or_default()
+                        .push(PortIdx::new(device_idx, port_idx));
+                }
+            }
+            if let Some(rack_idx) = device.rack() {
+                devices_of_rack
+                    .entry(rack_idx)
+                    .or_default()
+                    .push(RackMount::new(device.rack_position(), device_idx));
+            }
+            if let Some(membership) = device.virtual_chassis() {
+                members_of_chassis
+                    .entry(membership.chassis())
+                    .or_default()
+                    .push((membership.position(), device_idx));
+            }
+            device_index.insert(device.id(), device_idx);
+            devices.push(device);
+        }
+        let mut racks = Vec::with_capacity(self.racks.len());
+        for (rack_idx, rack) in self.racks.into_iter().enumerate() {
+            let mounted = devices_of_rack.remove(&rack_idx).unwrap_or_default();
+            racks.push(Arc::new(rack.build(
+                &location_mapper,
+                &site_mapper,
+                mounted,
+            )));
+        }
+        let mut prefixes = Vec::with_capacity(self.prefixes.len());
+        let mut prefix_index = HashMap::new();
+        for (prefix_idx, prefix) in self.prefixes.into_iter().enumerate() {
+            let prefix = prefix.build(&site_mapper, &tenant_mapper);
+            // keep the first of duplicate prefixes, the others are reported by the validation
+            prefix_index
+                .entry((prefix.vrf().map(str::to_string), prefix.net()))
+                .or_insert(prefix_idx);
+            prefixes.push(Arc::new(prefix));
+        }
+        let power_feeds = self
+            .power_feeds
+            .into_iter()
+            .map(|feed| Arc::new(feed.build(&site_mapper, &rack_mapper)))
+            .collect();
+        let circuits = self
+            .circuits
+            .into_iter()
+            .map(|circuit| Arc::new(circuit.build(&site_mapper)))
+            .collect();
+        let device_mapper = |id| device_index.get(&id).copied();
+        let virtual_chassis = self
+            .virtual_chassis
+            .into_iter()
+            .enumerate()
+            .map(|(chassis_idx, chassis)| {
+                let mut members = members_of_chassis.remove(&chassis_idx).unwrap_or_default();
+                members.sort();
+                let members = members.into_iter().map(|(_, idx)| idx).collect();
+                Arc::new(chassis.build(&device_mapper, members))
+            })
+            .collect();
+        let mut power_sources = HashMap::new();
+        let mut power_consumers: HashMap<PowerSource, Vec<(usize, usize)>> = HashMap::new();
+        for (port, source) in self.power_connections {
+            power_sources.insert(port, source);
+            power_consumers.entry(source).or_default().push(port);
         }
         let mut links = Vec::with_capacity(self.links.len());
-        let mut link_index = HashMap::new();
+        let mut link_index: HashMap<PortIdx, Vec<usize>> = HashMap::new();
         for (link_idx, link) in self.links.into_iter().enumerate() {
             for segment in link.path().iter() {
                 for port in segment.ports() {
-                    link_index.entry(*port).or_insert(link_idx);
+                    let links = link_index.entry(*port).or_default();
+                    if !links.contains(&link_idx) {
+                        links.push(link_idx);
+                    }
                 }
             }
             links.push(Arc::new(link));
         }
 
-        Ok(Arc::new(Topology {
+        let mut topology = Topology {
             device_types,
             devices,
             links,
             sites,
             locations,
+            racks,
+            vlans,
+            vlan_groups,
+            prefixes,
+            regions,
+            site_groups,
+            power_feeds,
+            circuits,
+            virtual_chassis,
+            tenants,
             link_index,
             device_index,
             site_index,
             location_index,
-        }))
+            rack_index,
+            vlan_index,
+            ports_of_vlan,
+            region_index,
+            tenant_index,
+            prefix_index,
+            address_index,
+            power_sources,
+            power_consumers,
+            circuits_of_port: HashMap::new(),
+            issues: vec![],
+        };
+        let mut issues = self.issues;
+        issues.append(&mut validate(&topology));
+        topology.issues = issues;
+        let mut topology = Arc::new(topology);
+        // terminations are traced through the cabling, which needs the completed topology
+        let circuits_of_port = topology.index_circuits_of_port();
+        if let Some(topology) = Arc::get_mut(&mut topology) {
+            topology.circuits_of_port = circuits_of_port;
+        }
+        Ok(topology)
     }
     pub fn devices(&self) -> &Vec<DeviceBuilder> {
         &self.devices
     }
+
+    fn build_regions(
+        builders: Vec<RegionBuilder>,
+        mut sites_of_region: HashMap<u32, Vec<usize>>,
+    ) -> (Vec<Arc<Region>>, HashMap<u32, usize>) {
+        let region_index: HashMap<u32, usize> = builders
+            .iter()
+            .enumerate()
+            .map(|(idx, region)| (region.id(), idx))
+            .collect();
+        let region_mapper = |id| region_index.get(&id).copied();
+        let mut children_of_region: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (region_idx, region) in builders.iter().enumerate() {
+            if let Some(parent_idx) = region.parent_id().and_then(region_mapper) {
+                children_of_region
+                    .entry(parent_idx)
+                    .or_default()
+                    .push(region_idx);
+            }
+        }
+        let regions = builders
+            .into_iter()
+            .enumerate()
+            .map(|(region_idx, region)| {
+                let children = children_of_region.remove(&region_idx).unwrap_or_default();
+                let sites = sites_of_region.remove(&region.id()).unwrap_or_default();
+                Arc::new(region.build(&region_mapper, children, sites))
+            })
+            .collect();
+        (regions, region_index)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::topology::model::device::DeviceBuilder;
+    use crate::topology::model::device::{AddressRole, DeviceBuilder, InterfaceAddress, PortIdx};
     use crate::topology::model::link::LinkBuilder;
-    use crate::topology::model::Topology;
+    use crate::topology::model::{DeviceType, Topology};
 
     #[test]
     fn test_build_topology() {
         let mut topology_builder = Topology::builder();
+        topology_builder.append_device_type(DeviceType::new("rb5009".to_string(), 1, true));
         let mut rt01_ports = Vec::new();
         let rt01_idx = {
             let mut device_builder = DeviceBuilder::new(1, "rt01".to_string(), true);
+            device_builder.set_device_type(1);
             device_builder.append_interface(
                 1,
                 "loopback".to_string(),
-                Some("172.16.0.1/32".parse().unwrap()),
-                None,
-                true,
+                vec![InterfaceAddress::new(
+                    "172.16.0.1/32".parse().unwrap(),
+                    AddressRole::Loopback,
+                    None,
+                )],
             );
             for port_idx in 1..=8 {
                 rt01_ports.push(device_builder.append_interface(
                     port_idx + 1,
                     format!("e{port_idx:02}"),
-                    None,
-                    None,
-                    false,
+                    vec![],
                 ));
             }
             topology_builder.append_device(device_builder)
@@ -257,20 +803,21 @@ mod tests {
         let mut rt02_ports = Vec::new();
         let rt02_idx = {
             let mut device_builder = DeviceBuilder::new(2, "rt02".to_string(), true);
+            device_builder.set_device_type(1);
             device_builder.append_interface(
                 10,
                 "loopback".to_string(),
-                Some("172.16.0.2/32".parse().unwrap()),
-                None,
-                true,
+                vec![InterfaceAddress::new(
+                    "172.16.0.2/32".parse().unwrap(),
+                    AddressRole::Loopback,
+                    None,
+                )],
             );
             for port_idx in 1..=8 {
                 rt02_ports.push(device_builder.append_interface(
                     port_idx + 11,
                     format!("e{port_idx:02}"),
-                    None,
-                    None,
-                    false,
+                    vec![],
                 ));
             }
             topology_builder.append_device(device_builder)
@@ -287,11 +834,50 @@ mod tests {
             )
             .unwrap();
         topology_builder.append_link(link_builder.build());
+        // breakout cable, one port connected to two ports on the other end
+        for rt02_port in [rt02_ports[2], rt02_ports[3]] {
+            let mut link_builder = LinkBuilder::new();
+            link_builder
+                .append_segment(
+                    topology_builder.devices(),
+                    rt01_idx,
+                    rt01_ports[2],
+                    rt02_idx,
+                    rt02_port,
+                )
+                .unwrap();
+            topology_builder.append_link(link_builder.build());
+        }
 
-        let topology = topology_builder.build();
+        let topology = topology_builder.build().unwrap();
 
         println!("Topology: {topology:#?}");
 
-        topology.get_device(2);
+        assert_eq!(
+            Some("rt02"),
+            topology.get_device(rt02_idx).as_deref().map(|d| d.name())
+        );
+        let rt01_port = PortIdx::new(rt01_idx, rt01_ports[0]);
+        let rt02_port = PortIdx::new(rt02_idx, rt02_ports[0]);
+        let links = topology.get_links_of_port(&rt01_port);
+        assert_eq!(1, links.len());
+        assert_eq!(Some(rt02_port), links[0].peer_of(&rt01_port));
+        assert_eq!(Some(rt01_port), links[0].peer_of(&rt02_port));
+        assert!(topology
+            .get_links_of_port(&PortIdx::new(rt01_idx, rt01_ports[1]))
+            .is_empty());
+        let breakout = PortIdx::new(rt01_idx, rt01_ports[2]);
+        let peers: Vec<_> = topology
+            .get_links_of_port(&breakout)
+            .iter()
+            .flat_map(|link| link.peer_of(&breakout))
+            .collect();
+        assert_eq!(
+            vec![
+                PortIdx::new(rt02_idx, rt02_ports[2]),
+                PortIdx::new(rt02_idx, rt02_ports[3])
+            ],
+            peers
+        );
     }
 }
@@ -0,0 +1,109 @@
+/// hardware module installed in a module bay of a device, e.g. a SFP transceiver
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub struct Module {
+    id: u32,
+    bay: ModuleBay,
+    module_type: ModuleType,
+    serial: String,
+    asset_tag: Option<String>,
+    status: ModuleStatus,
+}
+
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub struct ModuleBay {
+    id: u32,
+    name: String,
+    position: String,
+}
+
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub struct ModuleType {
+    model: String,
+    manufacturer: String,
+    part_number: String,
+}
+
+#[derive(Clone, Copy, Hash, Eq, PartialEq, Debug)]
+pub enum ModuleStatus {
+    Active,
+    Decommissioning,
+    Failed,
+    Offline,
+    Planned,
+    Staged,
+    /// status not known to this application, reported as topology issue
+    Unknown,
+}
+
+impl Module {
+    pub fn new(
+        id: u32,
+        bay: ModuleBay,
+        module_type: ModuleType,
+        serial: String,
+        asset_tag: Option<String>,
+        status: ModuleStatus,
+    ) -> Self {
+        Self {
+            id,
+            bay,
+            module_type,
+            serial,
+            asset_tag,
+            status,
+        }
+    }
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn bay(&self) -> &ModuleBay {
+        &self.bay
+    }
+    pub fn module_type(&self) -> &ModuleType {
+        &self.module_type
+    }
+    pub fn serial(&self) -> &str {
+        &self.serial
+    }
+    pub fn asset_tag(&self) -> Option<&str> {
+        self.asset_tag.as_deref()
+    }
+    pub fn status(&self) -> ModuleStatus {
+        self.status
+    }
+}
+
+impl ModuleBay {
+    pub fn new(id: u32, name: String, position: String) -> Self {
+        Self { id, name, position }
+    }
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// identifier used when renaming the components of the installed module
+    pub fn position(&self) -> &str {
+        &self.position
+    }
+}
+
+impl ModuleType {
+    pub fn new(model: String, manufacturer: String, part_number: String) -> Self {
+        Self {
+            model,
+            manufacturer,
+            part_number,
+        }
+    }
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+    pub fn manufacturer(&self) -> &str {
+        &self.manufacturer
+    }
+    pub fn part_number(&self) -> &str {
+        &self.part_number
+    }
+}
@@ -0,0 +1,80 @@
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::topology::model::TopologyError;
+
+/// netbox custom field switching monitoring of a device on or off
+pub const FIELD_ENABLED: &str = "monitoring";
+/// netbox custom field with an address to ping instead of the loopback
+pub const FIELD_PING_TARGET: &str = "monitoring_ping_target";
+/// netbox custom field with the criticality of a device
+pub const FIELD_CRITICALITY: &str = "monitoring_criticality";
+/// netbox custom field with the check interval in seconds
+pub const FIELD_CHECK_INTERVAL: &str = "monitoring_interval";
+/// netbox tag enabling monitoring if the custom field is not set
+pub const TAG_ENABLED: &str = "monitoring";
+/// netbox tag disabling monitoring if the custom field is not set
+pub const TAG_DISABLED: &str = "no-monitoring";
+
+/// per device monitoring settings maintained in netbox
+#[derive(Clone, Hash, Eq, PartialEq, Debug, Default)]
+pub struct MonitoringSettings {
+    enabled: Option<bool>,
+    ping_target: Option<IpAddr>,
+    criticality: Criticality,
+    check_interval: Option<Duration>,
+}
+
+impl MonitoringSettings {
+    /// explicit on/off switch, `None` falls back to the device category
+    pub fn enabled(&self) -> Option<bool> {
+        self.enabled
+    }
+    /// address to ping instead of the loopback address
+    pub fn ping_target(&self) -> Option<IpAddr> {
+        self.ping_target
+    }
+    pub fn criticality(&self) -> Criticality {
+        self.criticality
+    }
+    /// interval between two checks, `None` for the default
+    pub fn check_interval(&self) -> Option<Duration> {
+        self.check_interval
+    }
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = Some(enabled);
+    }
+    pub fn set_ping_target(&mut self, ping_target: IpAddr) {
+        self.ping_target = Some(ping_target);
+    }
+    pub fn set_criticality(&mut self, criticality: Criticality) {
+        self.criticality = criticality;
+    }
+    pub fn set_check_interval(&mut self, check_interval: Duration) {
+        self.check_interval = Some(check_interval);
+    }
+}
+
+#[derive(Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, Debug, Default)]
+pub enum Criticality {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Critical,
+}
+
+impl FromStr for Criticality {
+    type Err = TopologyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "low" => Ok(Criticality::Low),
+            "normal" => Ok(Criticality::Normal),
+            "high" => Ok(Criticality::High),
+            "critical" => Ok(Criticality::Critical),
+            unknown => Err(TopologyError::UnknownCriticality(unknown.to_string())),
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use std::ops::Deref;
+use std::sync::Arc;
+
+use crate::topology::model::device::{DevicePort, PortIdx};
+use crate::topology::model::Topology;
+
+/// Ports passed by tracing the cabling starting at a single port
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub struct CablePath {
+    hops: Vec<PortIdx>,
+    links: Vec<usize>,
+    far_end: Option<PortIdx>,
+}
+
+impl CablePath {
+    /// all passed ports in order, starting with the traced port
+    pub fn hops(&self) -> &Vec<PortIdx> {
+        &self.hops
+    }
+    /// crossed links in order
+    pub fn links(&self) -> &Vec<usize> {
+        &self.links
+    }
+    /// interface at the end of the path (if the path ends on an interface)
+    pub fn far_end(&self) -> Option<PortIdx> {
+        self.far_end
+    }
+}
+
+impl Topology {
+    pub fn get_port(self: &Arc<Self>, port: &PortIdx) -> Option<Arc<DevicePort>> {
+        self.devices
+            .get(port.device_idx())?
+            .get_port(port.port_idx())
+    }
+
+    /// follow the cabling from the given port through front and rear ports until an interface or an open end is reached
+    ///
+    /// A front or rear port without a cable is passed through its device first, so tracing an
+    /// unpatched wall connector finds the switch port behind it. Ports connected by a breakout
+    /// cable to several ports branch the trace, one path is returned per branch.
+    pub fn trace_paths(self: &Arc<Self>, start: PortIdx) -> Vec<CablePath> {
+        let cross_link = !self
+            .get_port(&start)
+            .map(|p| p.is_pass_through())
+            .unwrap_or(false)
+            || self.link_index.contains_key(&start);
+        let mut pending = vec![(vec![start], Vec::new(), Vec::new(), cross_link)];
+        let mut paths = Vec::new();
+        while let Some((hops, links, mut positions, cross_link)) = pending.pop() {
+            let current = hops[hops.len() - 1];
+            let next: Vec<(PortIdx, Option<usize>)> = if cross_link {
+                self.link_index
+                    .get(&current)
+                    .into_iter()
+                    .flatten()
+                    .flat_map(|link_idx| {
+                        let peer = self.links.get(*link_idx)?.peer_of(&current)?;
+                        Some((peer, Some(*link_idx)))
+                    })
+                    .collect()
+            } else {
+                self.pass_through(current, &mut positions)
+                    .map(|port| (port, None))
+                    .into_iter()
+                    .collect()
+            };
+            let next: Vec<_> = next
+                .into_iter()
+                .filter(|(port, _)| !hops.contains(port))
+                .collect();
+            if next.is_empty() {
+                let far_end = Some(current).filter(|port| {
+                    *port != start
+                        && self
+                            .get_port(port)
+                            .map(|p| !p.is_pass_through())
+                            .unwrap_or(false)
+                });
+                paths.push(CablePath {
+                    hops,
+                    links,
+                    far_end,
+                });
+                continue;
+            }
+            // pushed in reverse, so the paths are returned in the order of the links
+            for (port, link_idx) in next.into_iter().rev() {
+                let mut hops = hops.clone();
+                hops.push(port);
+                let mut links = links.clone();
+                links.extend(link_idx);
+                pending.push((hops, links, positions.clone(), !cross_link));
+            }
+        }
+        paths
+    }
+
+    fn pass_through(self: &Arc<Self>, port: PortIdx, positions: &mut Vec<u32>) -> Option<PortIdx> {
+        let device_idx = port.device_idx();
+        match self.get_port(&port)?.deref() {
+            DevicePort::FrontPort {
+                rear_port_idx,
+                rear_port_position,
+                ..
+            } => {
+                positions.push(*rear_port_position);
+                Some(PortIdx::new(device_idx, *rear_port_idx))
+            }
+            DevicePort::RearPort { .. } => self
+                .devices
+                .get(device_idx)?
+                .front_port_of_rear(port.port_idx(), positions.pop())
+                .map(|front_idx| PortIdx::new(device_idx, front_idx)),
+            DevicePort::Interface { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::topology::model::device::{DeviceBuilder, PortIdx};
+    use crate::topology::model::link::LinkBuilder;
+    use crate::topology::model::{DeviceType, TopologyBuilder};
+
+    fn connect(
+        topology_builder: &mut TopologyBuilder,
+        left: (usize, usize),
+        right: (usize, usize),
+    ) {
+        let mut link_builder = LinkBuilder::new();
+        link_builder
+            .append_segment(topology_builder.devices(), left.0, left.1, right.0, right.1)
+            .unwrap();
+        topology_builder.append_link(link_builder.build());
+    }
+
+    #[test]
+    fn test_trace_wall_connector_to_switch() {
+        let mut topology_builder = TopologyBuilder::default();
+        topology_builder.append_device_type(DeviceType::new("generic".to_string(), 1, false));
+
+        let mut switch = DeviceBuilder::new(1, "sw01".to_string(), true);
+        switch.set_device_type(1);
+        let switch_ports: Vec<_> = (1..=4)
+            .map(|idx| switch.append_interface(idx, format!("e{idx:02}"), vec![]))
+            .collect();
+        let switch_idx = topology_builder.append_device(switch);
+
+        let mut panel = DeviceBuilder::new(2, "pp01".to_string(), false);
+        panel.set_device_type(1);
+        let panel_rear = panel.append_rear_port(10, "r1".to_string());
+        let panel_front = panel.append_front_port(11, "f1".to_string(), panel_rear, 1);
+        let panel_idx = topology_builder.append_device(panel);
+
+        let mut socket = DeviceBuilder::new(3, "3.14".to_string(), false);
+        socket.set_device_type(1);
+        let socket_rear = socket.append_rear_port(20, "r".to_string());
+        let socket_front = socket.append_front_port(21, "f".to_string(), socket_rear, 1);
+        let socket_idx = topology_builder.append_device(socket);
+
+        connect(
+            &mut topology_builder,
+            (socket_idx, socket_rear),
+            (panel_idx, panel_rear),
+        );
+        connect(
+            &mut topology_builder,
+            (panel_idx, panel_front),
+            (switch_idx, switch_ports[2]),
+        );
+        let topology = topology_builder.build().unwrap();
+
+        let paths = topology.trace_paths(PortIdx::new(socket_idx, socket_front));
+        assert_eq!(1, paths.len());
+        let path = &paths[0];
+        assert_eq!(
+            &vec![
+                PortIdx::new(socket_idx, socket_front),
+                PortIdx::new(socket_idx, socket_rear),
+                PortIdx::new(panel_idx, panel_rear),
+                PortIdx::new(panel_idx, panel_front),
+                PortIdx::new(switch_idx, switch_ports[2]),
+            ],
+            path.hops()
+        );
+        assert_eq!(
+            Some(PortIdx::new(switch_idx, switch_ports[2])),
+            path.far_end()
+        );
+
+        let reverse = &topology.trace_paths(PortIdx::new(switch_idx, switch_ports[2]))[0];
+        assert_eq!(5, reverse.hops().len());
+        assert_eq!(None, reverse.far_end());
+
+        let unconnected = &topology.trace_paths(PortIdx::new(switch_idx, switch_ports[0]))[0];
+        assert_eq!(1, unconnected.hops().len());
+        assert_eq!(None, unconnected.far_end());
+    }
+
+    #[test]
+    fn test_trace_breakout_cable() {
+        let mut topology_builder = TopologyBuilder::default();
+        topology_builder.append_device_type(DeviceType::new("generic".to_string(), 1, false));
+
+        let mut switch = DeviceBuilder::new(1, "sw01".to_string(), true);
+        switch.set_device_type(1);
+        let uplink = switch.append_interface(1, "qsfp1".to_string(), vec![]);
+        let switch_idx = topology_builder.append_device(switch);
+
+        let mut server = DeviceBuilder::new(2, "srv01".to_string(), true);
+        server.set_device_type(1);
+        let server_ports: Vec<_> = (1..=2)
+            .map(|idx| server.append_interface(10 + idx, format!("eth{idx}"), vec![]))
+            .collect();
+        let server_idx = topology_builder.append_device(server);
+
+        for server_port in &server_ports {
+            connect(
+                &mut topology_builder,
+                (switch_idx, uplink),
+                (server_idx, *server_port),
+            );
+        }
+        let topology = topology_builder.build().unwrap();
+
+        let far_ends: Vec<_> = topology
+            .trace_paths(PortIdx::new(switch_idx, uplink))
+            .iter()
+            .map(|path| path.far_end())
+            .collect();
+        assert_eq!(
+            vec![
+                Some(PortIdx::new(server_idx, server_ports[0])),
+                Some(PortIdx::new(server_idx, server_ports[1]))
+            ],
+            far_ends
+        );
+        let back = topology.trace_paths(PortIdx::new(server_idx, server_ports[1]));
+        assert_eq!(1, back.len());
+        assert_eq!(Some(PortIdx::new(switch_idx, uplink)), back[0].far_end());
+    }
+}
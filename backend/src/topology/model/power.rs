@@ -0,0 +1,361 @@
+use std::collections::{BTreeSet, HashSet, VecDeque};
+use std::sync::Arc;
+
+use crate::topology::model::Topology;
+
+/// power inlet of a device
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub struct PowerPort {
+    id: u32,
+    name: String,
+}
+
+impl PowerPort {
+    pub fn new(id: u32, name: String) -> Self {
+        Self { id, name }
+    }
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// power outlet of a device
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub struct PowerOutlet {
+    id: u32,
+    name: String,
+    power_port: Option<usize>,
+}
+
+impl PowerOutlet {
+    pub fn new(id: u32, name: String, power_port: Option<usize>) -> Self {
+        Self {
+            id,
+            name,
+            power_port,
+        }
+    }
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// power port of the same device feeding this outlet, `None` if not maintained
+    pub fn power_port(&self) -> Option<usize> {
+        self.power_port
+    }
+}
+
+/// power circuit leaving a power panel
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub struct PowerFeed {
+    id: u32,
+    name: String,
+    panel: String,
+    site: Option<usize>,
+    rack: Option<usize>,
+    status: PowerFeedStatus,
+}
+
+#[derive(Clone, Copy, Hash, Eq, PartialEq, Debug)]
+pub enum PowerFeedStatus {
+    Active,
+    Failed,
+    Offline,
+    Planned,
+    /// status not known to this application, reported as topology issue
+    Unknown,
+}
+
+impl PowerFeed {
+    pub fn builder(
+        id: u32,
+        name: String,
+        panel: String,
+        status: PowerFeedStatus,
+    ) -> PowerFeedBuilder {
+        PowerFeedBuilder {
+            id,
+            name,
+            panel,
+            status,
+            site_id: None,
+            rack_id: None,
+        }
+    }
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// name of the power panel the feed originates from
+    pub fn panel(&self) -> &str {
+        &self.panel
+    }
+    pub fn site(&self) -> Option<usize> {
+        self.site
+    }
+    pub fn rack(&self) -> Option<usize> {
+        self.rack
+    }
+    pub fn status(&self) -> PowerFeedStatus {
+        self.status
+    }
+}
+
+pub struct PowerFeedBuilder {
+    id: u32,
+    name: String,
+    panel: String,
+    status: PowerFeedStatus,
+    site_id: Option<u32>,
+    rack_id: Option<u32>,
+}
+
+impl PowerFeedBuilder {
+    pub fn set_site(&mut self, id: u32) {
+        self.site_id = Some(id);
+    }
+    pub fn set_rack(&mut self, id: u32) {
+        self.rack_id = Some(id);
+    }
+
+    pub(crate) fn build<SM, RM>(self, site_mapper: &SM, rack_mapper: &RM) -> PowerFeed
+    where
+        SM: Fn(u32) -> Option<usize>,
+        RM: Fn(u32) -> Option<usize>,
+    {
+        PowerFeed {
+            id: self.id,
+            name: self.name,
+            panel: self.panel,
+            site: self.site_id.and_then(site_mapper),
+            rack: self.rack_id.and_then(rack_mapper),
+            status: self.status,
+        }
+    }
+}
+
+/// origin of the power delivered to a power port
+#[derive(Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub enum PowerSource {
+    /// outlet of another device like a pdu or ups
+    Outlet { device: usize, outlet: usize },
+    /// power feed of a power panel
+    Feed(usize),
+}
+
+impl Topology {
+    pub fn get_power_feed(self: &Arc<Self>, idx: usize) -> Option<Arc<PowerFeed>> {
+        self.power_feeds.get(idx).cloned()
+    }
+    pub fn list_power_feeds_map<P: Fn(&Arc<PowerFeed>) -> Option<T>, T>(
+        self: &Arc<Self>,
+        filter: P,
+    ) -> Vec<T> {
+        self.power_feeds.iter().flat_map(filter).collect()
+    }
+    /// source connected to a single power port of a device
+    pub fn power_source_of_port(
+        self: &Arc<Self>,
+        device: usize,
+        port: usize,
+    ) -> Option<PowerSource> {
+        self.power_sources.get(&(device, port)).copied()
+    }
+
+    /// all sources the device depends on, from the directly connected outlets up to the feeds
+    pub fn powered_by(self: &Arc<Self>, device: usize) -> Vec<PowerSource> {
+        let mut sources = Vec::new();
+        let mut visited = HashSet::new();
+        let mut pending: VecDeque<_> = self.direct_sources(device, None).into();
+        while let Some(source) = pending.pop_front() {
+            if !visited.insert(source) {
+                continue;
+            }
+            sources.push(source);
+            if let PowerSource::Outlet { device, outlet } = source {
+                let inlet = self
+                    .devices
+                    .get(device)
+                    .and_then(|d| d.power_outlets().get(outlet))
+                    .and_then(|o| o.power_port());
+                pending.extend(self.direct_sources(device, inlet));
+            }
+        }
+        sources
+    }
+
+    /// devices connected directly to the given source
+    pub fn powered_devices(self: &Arc<Self>, source: PowerSource) -> Vec<usize> {
+        self.power_consumers
+            .get(&source)
+            .map(|ports| {
+                ports
+                    .iter()
+                    .map(|(device, _)| *device)
+                    .collect::<BTreeSet<_>>()
+            })
+            .unwrap_or_default()
+            .into_iter()
+            .collect()
+    }
+
+    /// all devices drawing power from the given device, directly or through further pdus
+    ///
+    /// outlets of further pdus are only followed if they are fed by the inlet the power arrives at,
+    /// or if their inlet is not maintained
+    pub fn powers(self: &Arc<Self>, device: usize) -> Vec<usize> {
+        let mut result = BTreeSet::new();
+        let mut visited = HashSet::new();
+        let mut pending = VecDeque::from([(device, None)]);
+        while let Some((current, inlet)) = pending.pop_front() {
+            let Some(current_device) = self.devices.get(current) else {
+                continue;
+            };
+            for (outlet, power_outlet) in current_device.power_outlets().iter().enumerate() {
+                if inlet.is_some() && power_outlet.power_port().is_some_and(|p| Some(p) != inlet) {
+                    continue;
+                }
+                let source = PowerSource::Outlet {
+                    device: current,
+                    outlet,
+                };
+                for (consumer, port) in self.power_consumers.get(&source).into_iter().flatten() {
+                    if *consumer != device && visited.insert((*consumer, *port)) {
+                        result.insert(*consumer);
+                        pending.push_back((*consumer, Some(*port)));
+                    }
+                }
+            }
+        }
+        result.into_iter().collect()
+    }
+
+    /// sources of one specific or of all power ports of a device
+    fn direct_sources(self: &Arc<Self>, device: usize, port: Option<usize>) -> Vec<PowerSource> {
+        let port_count = self
+            .devices
+            .get(device)
+            .map(|d| d.power_ports().len())
+            .unwrap_or_default();
+        port.map(|p| p..p + 1)
+            .unwrap_or(0..port_count)
+            .flat_map(|p| self.power_sources.get(&(device, p)).copied())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::topology::model::device::DeviceBuilder;
+    use crate::topology::model::power::{PowerFeed, PowerFeedStatus, PowerSource};
+    use crate::topology::model::{DeviceType, TopologyBuilder};
+
+    fn device(topology_builder: &mut TopologyBuilder, id: u32, inlets: usize) -> usize {
+        let mut device_builder = DeviceBuilder::new(id, format!("dev{id}"), false);
+        device_builder.set_device_type(1);
+        for inlet in 0..inlets {
+            device_builder.append_power_port(id * 10 + inlet as u32, format!("PSU{inlet}"));
+        }
+        topology_builder.append_device(device_builder)
+    }
+
+    fn pdu(topology_builder: &mut TopologyBuilder, id: u32, outlets: &[Option<usize>]) -> usize {
+        let mut device_builder = DeviceBuilder::new(id, format!("pdu{id}"), false);
+        device_builder.set_device_type(1);
+        let inlets = outlets.iter().flatten().max().map(|i| i + 1).unwrap_or(1);
+        for inlet in 0..inlets {
+            device_builder.append_power_port(id * 10 + inlet as u32, format!("Inlet {inlet}"));
+        }
+        for (outlet, inlet) in outlets.iter().enumerate() {
+            device_builder.append_power_outlet(
+                id * 100 + outlet as u32,
+                format!("Outlet {outlet}"),
+                *inlet,
+            );
+        }
+        topology_builder.append_device(device_builder)
+    }
+
+    fn outlet(device: usize, outlet: usize) -> PowerSource {
+        PowerSource::Outlet { device, outlet }
+    }
+
+    #[test]
+    fn test_follow_power_chain() {
+        let mut topology_builder = TopologyBuilder::default();
+        topology_builder.append_device_type(DeviceType::new("generic".to_string(), 1, false));
+        let feed = topology_builder.append_power_feed(PowerFeed::builder(
+            1,
+            "Feed A".to_string(),
+            "Panel 1".to_string(),
+            PowerFeedStatus::Active,
+        ));
+        let ups = pdu(&mut topology_builder, 1, &[Some(0)]);
+        let pdu = pdu(&mut topology_builder, 2, &[None, None]);
+        let server = device(&mut topology_builder, 3, 1);
+        topology_builder.append_power_connection(ups, 0, PowerSource::Feed(feed));
+        topology_builder.append_power_connection(pdu, 0, outlet(ups, 0));
+        topology_builder.append_power_connection(server, 0, outlet(pdu, 1));
+        let topology = topology_builder.build().unwrap();
+
+        assert_eq!(
+            vec![outlet(pdu, 1), outlet(ups, 0), PowerSource::Feed(feed)],
+            topology.powered_by(server)
+        );
+        assert_eq!(vec![pdu, server], topology.powers(ups));
+        assert_eq!(vec![ups], topology.powered_devices(PowerSource::Feed(feed)));
+    }
+
+    #[test]
+    fn test_stop_at_power_loop() {
+        let mut topology_builder = TopologyBuilder::default();
+        topology_builder.append_device_type(DeviceType::new("generic".to_string(), 1, false));
+        let left = pdu(&mut topology_builder, 1, &[Some(0)]);
+        let right = pdu(&mut topology_builder, 2, &[Some(0)]);
+        topology_builder.append_power_connection(left, 0, outlet(right, 0));
+        topology_builder.append_power_connection(right, 0, outlet(left, 0));
+        let topology = topology_builder.build().unwrap();
+
+        assert_eq!(
+            vec![outlet(right, 0), outlet(left, 0)],
+            topology.powered_by(left)
+        );
+        assert_eq!(vec![right], topology.powers(left));
+    }
+
+    #[test]
+    fn test_follow_only_outlets_of_inlet() {
+        let mut topology_builder = TopologyBuilder::default();
+        topology_builder.append_device_type(DeviceType::new("generic".to_string(), 1, false));
+        let ups_a = pdu(&mut topology_builder, 1, &[None]);
+        let ups_b = pdu(&mut topology_builder, 2, &[None]);
+        // outlet 0 is fed by inlet 0, outlet 1 by inlet 1
+        let pdu = pdu(&mut topology_builder, 3, &[Some(0), Some(1)]);
+        let server_a = device(&mut topology_builder, 4, 1);
+        let server_b = device(&mut topology_builder, 5, 1);
+        topology_builder.append_power_connection(pdu, 0, outlet(ups_a, 0));
+        topology_builder.append_power_connection(pdu, 1, outlet(ups_b, 0));
+        topology_builder.append_power_connection(server_a, 0, outlet(pdu, 0));
+        topology_builder.append_power_connection(server_b, 0, outlet(pdu, 1));
+        let topology = topology_builder.build().unwrap();
+
+        assert_eq!(vec![pdu, server_a], topology.powers(ups_a));
+        assert_eq!(vec![pdu, server_b], topology.powers(ups_b));
+        assert_eq!(vec![server_a, server_b], topology.powers(pdu));
+        assert_eq!(
+            vec![outlet(pdu, 1), outlet(ups_b, 0)],
+            topology.powered_by(server_b)
+        );
+        assert_eq!(
+            vec![outlet(ups_a, 0), outlet(ups_b, 0)],
+            topology.powered_by(pdu)
+        );
+    }
+}
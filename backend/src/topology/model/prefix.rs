@@ -0,0 +1,222 @@
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use ipnet::IpNet;
+
+use crate::topology::model::{PortIdx, Topology};
+
+/// ip prefix maintained in netbox ipam
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub struct Prefix {
+    id: u32,
+    net: IpNet,
+    vrf: Option<String>,
+    site: Option<usize>,
+    vlan: Option<u32>,
+    tenant: Option<usize>,
+}
+
+impl Prefix {
+    pub fn builder(id: u32, net: IpNet) -> PrefixBuilder {
+        PrefixBuilder {
+            id,
+            net,
+            vrf: None,
+            site_id: None,
+            vlan_id: None,
+            tenant_id: None,
+        }
+    }
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn net(&self) -> IpNet {
+        self.net
+    }
+    /// name of the vrf, `None` for the global table
+    pub fn vrf(&self) -> Option<&str> {
+        self.vrf.as_deref()
+    }
+    pub fn site(&self) -> Option<usize> {
+        self.site
+    }
+    /// netbox id of the vlan this prefix is used in
+    pub fn vlan(&self) -> Option<u32> {
+        self.vlan
+    }
+    /// tenant assigned to the prefix itself
+    pub fn tenant(&self) -> Option<usize> {
+        self.tenant
+    }
+}
+
+pub struct PrefixBuilder {
+    id: u32,
+    net: IpNet,
+    vrf: Option<String>,
+    site_id: Option<u32>,
+    vlan_id: Option<u32>,
+    tenant_id: Option<u32>,
+}
+
+impl PrefixBuilder {
+    pub fn set_vrf(&mut self, vrf: String) {
+        self.vrf = Some(vrf);
+    }
+    pub fn set_site(&mut self, id: u32) {
+        self.site_id = Some(id);
+    }
+    pub fn set_vlan(&mut self, id: u32) {
+        self.vlan_id = Some(id);
+    }
+    pub fn set_tenant(&mut self, id: u32) {
+        self.tenant_id = Some(id);
+    }
+
+    pub(crate) fn build<SM, TM>(self, site_mapper: &SM, tenant_mapper: &TM) -> Prefix
+    where
+        SM: Fn(u32) -> Option<usize>,
+        TM: Fn(u32) -> Option<usize>,
+    {
+        Prefix {
+            id: self.id,
+            net: self.net.trunc(),
+            vrf: self.vrf,
+            site: self.site_id.and_then(site_mapper),
+            vlan: self.vlan_id,
+            tenant: self.tenant_id.and_then(tenant_mapper),
+        }
+    }
+}
+
+/// everything known about a single ip address
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub struct AddressLookup {
+    address: IpAddr,
+    prefix: Option<usize>,
+    ports: Vec<PortIdx>,
+    site: Option<usize>,
+}
+
+impl AddressLookup {
+    pub fn address(&self) -> IpAddr {
+        self.address
+    }
+    /// longest matching prefix
+    pub fn prefix(&self) -> Option<usize> {
+        self.prefix
+    }
+    /// interfaces the address is assigned to
+    pub fn ports(&self) -> &Vec<PortIdx> {
+        &self.ports
+    }
+    /// site of the prefix, or of the owning device if the prefix has none
+    pub fn site(&self) -> Option<usize> {
+        self.site
+    }
+}
+
+impl Topology {
+    pub fn get_prefix(self: &Arc<Self>, idx: usize) -> Option<Arc<Prefix>> {
+        self.prefixes.get(idx).cloned()
+    }
+    pub fn list_prefixes_map<P: Fn(&Arc<Prefix>) -> Option<T>, T>(
+        self: &Arc<Self>,
+        filter: P,
+    ) -> Vec<T> {
+        self.prefixes.iter().flat_map(filter).collect()
+    }
+
+    /// find the longest matching prefix and the owning interfaces of an address within a vrf
+    ///
+    /// `None` searches only the global table
+    pub fn lookup_address(self: &Arc<Self>, address: IpAddr, vrf: Option<&str>) -> AddressLookup {
+        let vrf = vrf.map(str::to_string);
+        let max_len = match address {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        let prefix = (0..=max_len).rev().find_map(|len| {
+            let net = IpNet::new(address, len).ok()?.trunc();
+            self.prefix_index.get(&(vrf.clone(), net)).copied()
+        });
+        let ports = self
+            .address_index
+            .get(&(vrf, address))
+            .cloned()
+            .unwrap_or_default();
+        let site = prefix
+            .and_then(|idx| self.prefixes.get(idx)?.site())
+            .or_else(|| {
+                ports
+                    .iter()
+                    .find_map(|port| self.devices.get(port.device_idx())?.site())
+            });
+        AddressLookup {
+            address,
+            prefix,
+            ports,
+            site,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::topology::model::device::{AddressRole, DeviceBuilder, InterfaceAddress, PortIdx};
+    use crate::topology::model::prefix::Prefix;
+    use crate::topology::model::validation::TopologyIssue;
+    use crate::topology::model::{DeviceType, Topology};
+
+    #[test]
+    fn test_lookup_longest_prefix() {
+        let mut topology_builder = Topology::builder();
+        topology_builder.append_device_type(DeviceType::new("rb5009".to_string(), 1, true));
+        let site_idx = topology_builder.append_site(20, "zurich".to_string(), String::new());
+        let mut supernet = Prefix::builder(1, "10.0.0.0/16".parse().unwrap());
+        supernet.set_site(20);
+        let supernet = topology_builder.append_prefix(supernet);
+        let subnet =
+            topology_builder.append_prefix(Prefix::builder(2, "10.0.1.0/24".parse().unwrap()));
+        topology_builder.append_prefix(Prefix::builder(3, "10.0.1.0/24".parse().unwrap()));
+        let mut mgmt = Prefix::builder(4, "10.0.1.0/25".parse().unwrap());
+        mgmt.set_vrf("mgmt".to_string());
+        let mgmt = topology_builder.append_prefix(mgmt);
+
+        let mut device_builder = DeviceBuilder::new(1, "rt01".to_string(), true);
+        device_builder.set_device_type(1);
+        let port_idx = device_builder.append_interface(
+            1,
+            "ether1".to_string(),
+            vec![InterfaceAddress::new(
+                "10.0.1.1/24".parse().unwrap(),
+                AddressRole::Primary,
+                None,
+            )],
+        );
+        let device_idx = topology_builder.append_device(device_builder);
+        let topology = topology_builder.build().unwrap();
+
+        let lookup = topology.lookup_address("10.0.1.1".parse().unwrap(), None);
+        assert_eq!(Some(subnet), lookup.prefix());
+        assert_eq!(&vec![PortIdx::new(device_idx, port_idx)], lookup.ports());
+        assert_eq!(None, lookup.site());
+        let lookup = topology.lookup_address("10.0.2.1".parse().unwrap(), None);
+        assert_eq!(Some(supernet), lookup.prefix());
+        assert_eq!(Some(site_idx), lookup.site());
+        let lookup = topology.lookup_address("10.0.1.1".parse().unwrap(), Some("mgmt"));
+        assert_eq!(Some(mgmt), lookup.prefix());
+        assert!(lookup.ports().is_empty());
+        assert_eq!(
+            None,
+            topology
+                .lookup_address("10.0.1.200".parse().unwrap(), Some("mgmt"))
+                .prefix()
+        );
+        assert!(topology.issues().contains(&TopologyIssue::DuplicatePrefix {
+            net: "10.0.1.0/24".parse().unwrap(),
+            vrf: None,
+            prefix_ids: vec![2, 3],
+        }));
+    }
+}
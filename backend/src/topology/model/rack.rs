@@ -0,0 +1,167 @@
+use std::fmt::{Display, Formatter};
+
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub struct Rack {
+    id: u32,
+    name: String,
+    u_height: u32,
+    location: Option<usize>,
+    site: Option<usize>,
+    devices: Vec<RackMount>,
+}
+
+/// lowest unit occupied by a device, counted in half units as netbox allows mounting at 0.5U offsets
+#[derive(Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub struct RackPosition(u32);
+
+impl RackPosition {
+    /// position from the decimal netbox value, rounded to half units
+    pub fn from_units(units: f64) -> Option<Self> {
+        let half_units = (units * 2.0).round();
+        (half_units.is_finite() && half_units >= 0.0 && half_units <= u32::MAX as f64)
+            .then_some(RackPosition(half_units as u32))
+    }
+    pub fn units(&self) -> f64 {
+        self.0 as f64 / 2.0
+    }
+}
+
+impl Display for RackPosition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.units())
+    }
+}
+
+/// device mounted into a rack
+#[derive(Copy, Clone, Hash, Eq, PartialEq, Debug)]
+pub struct RackMount {
+    position: Option<RackPosition>,
+    device: usize,
+}
+
+impl RackMount {
+    pub fn new(position: Option<RackPosition>, device: usize) -> Self {
+        Self { position, device }
+    }
+    /// lowest unit occupied by the device, `None` for devices without a position (e.g. zero-U)
+    pub fn position(&self) -> Option<RackPosition> {
+        self.position
+    }
+    pub fn device(&self) -> usize {
+        self.device
+    }
+}
+
+pub struct RackBuilder {
+    id: u32,
+    name: String,
+    u_height: u32,
+    location_id: Option<u32>,
+    site_id: Option<u32>,
+}
+
+impl RackBuilder {
+    pub fn set_location(&mut self, id: u32) {
+        self.location_id = Some(id);
+    }
+    pub fn set_site(&mut self, id: u32) {
+        self.site_id = Some(id);
+    }
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn location_id(&self) -> Option<u32> {
+        self.location_id
+    }
+
+    pub(crate) fn build<LM, SM>(
+        self,
+        location_mapper: &LM,
+        site_mapper: &SM,
+        mut devices: Vec<RackMount>,
+    ) -> Rack
+    where
+        LM: Fn(u32) -> Option<usize>,
+        SM: Fn(u32) -> Option<usize>,
+    {
+        devices.sort_by_key(|mount| (mount.position, mount.device));
+        devices.shrink_to_fit();
+        Rack {
+            id: self.id,
+            name: self.name,
+            u_height: self.u_height,
+            location: self.location_id.and_then(location_mapper),
+            site: self.site_id.and_then(site_mapper),
+            devices,
+        }
+    }
+}
+
+impl Rack {
+    pub fn builder(id: u32, name: String, u_height: u32) -> RackBuilder {
+        RackBuilder {
+            id,
+            name,
+            u_height,
+            location_id: None,
+            site_id: None,
+        }
+    }
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn u_height(&self) -> u32 {
+        self.u_height
+    }
+    pub fn location(&self) -> Option<usize> {
+        self.location
+    }
+    pub fn site(&self) -> Option<usize> {
+        self.site
+    }
+    /// mounted devices ordered by their position
+    pub fn devices(&self) -> &Vec<RackMount> {
+        &self.devices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::topology::model::device::DeviceBuilder;
+    use crate::topology::model::rack::RackPosition;
+    use crate::topology::model::{DeviceType, Rack, Topology};
+
+    #[test]
+    fn test_order_devices_by_half_unit_position() {
+        let mut topology_builder = Topology::builder();
+        topology_builder.append_device_type(DeviceType::new("crs326".to_string(), 1, true));
+        topology_builder.append_rack(Rack::builder(5, "rack-a".to_string(), 42));
+        for (id, position) in [(1, Some(12.5)), (2, None), (3, Some(12.0)), (4, Some(1.0))] {
+            let mut device_builder = DeviceBuilder::new(id, format!("dev{id}"), false);
+            device_builder.set_device_type(1);
+            device_builder.set_rack(5, position.and_then(RackPosition::from_units));
+            topology_builder.append_device(device_builder);
+        }
+        let topology = topology_builder.build().unwrap();
+        let rack = topology.get_rack_by_id(5).unwrap();
+        let mounted: Vec<_> = rack
+            .devices()
+            .iter()
+            .map(|mount| {
+                (
+                    topology.get_device(mount.device()).unwrap().id(),
+                    mount.position().map(|p| p.units()),
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![(2, None), (4, Some(1.0)), (3, Some(12.0)), (1, Some(12.5))],
+            mounted
+        );
+        assert_eq!("12.5", RackPosition::from_units(12.5).unwrap().to_string());
+        assert_eq!(None, RackPosition::from_units(-1.0));
+    }
+}
@@ -0,0 +1,125 @@
+/// nested collection of sites, used for netbox regions and site groups
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub struct Region {
+    id: u32,
+    name: String,
+    slug: String,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    sites: Vec<usize>,
+}
+
+impl Region {
+    pub fn builder(id: u32, name: String, slug: String) -> RegionBuilder {
+        RegionBuilder {
+            id,
+            name,
+            slug,
+            parent_id: None,
+        }
+    }
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn slug(&self) -> &str {
+        &self.slug
+    }
+    pub fn parent(&self) -> Option<usize> {
+        self.parent
+    }
+    pub fn children(&self) -> &Vec<usize> {
+        &self.children
+    }
+    /// sites directly assigned, without those of the children
+    pub fn sites(&self) -> &Vec<usize> {
+        &self.sites
+    }
+}
+
+pub struct RegionBuilder {
+    id: u32,
+    name: String,
+    slug: String,
+    parent_id: Option<u32>,
+}
+
+impl RegionBuilder {
+    pub fn set_parent(&mut self, id: u32) {
+        self.parent_id = Some(id);
+    }
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn parent_id(&self) -> Option<u32> {
+        self.parent_id
+    }
+
+    pub(crate) fn build<RM>(
+        self,
+        region_mapper: &RM,
+        children: Vec<usize>,
+        sites: Vec<usize>,
+    ) -> Region
+    where
+        RM: Fn(u32) -> Option<usize>,
+    {
+        Region {
+            id: self.id,
+            name: self.name,
+            slug: self.slug,
+            parent: self.parent_id.and_then(region_mapper),
+            children,
+            sites,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::topology::model::region::Region;
+    use crate::topology::model::Topology;
+
+    #[test]
+    fn test_build_region_tree() {
+        let mut topology_builder = Topology::builder();
+        let europe = topology_builder.append_region(Region::builder(
+            1,
+            "Europe".to_string(),
+            "europe".to_string(),
+        ));
+        let mut switzerland = Region::builder(2, "Switzerland".to_string(), "ch".to_string());
+        switzerland.set_parent(1);
+        let switzerland = topology_builder.append_region(switzerland);
+        let mut orphan = Region::builder(3, "Orphan".to_string(), "orphan".to_string());
+        orphan.set_parent(99);
+        let orphan = topology_builder.append_region(orphan);
+        let campus = topology_builder.append_site_group(Region::builder(
+            1,
+            "Campus".to_string(),
+            "campus".to_string(),
+        ));
+        let zurich = topology_builder.append_site(20, "zurich".to_string(), String::new());
+        topology_builder.set_region_of_site(zurich, 2);
+        topology_builder.set_group_of_site(zurich, 1);
+        let topology = topology_builder.build().unwrap();
+
+        let region = topology.get_region(europe).unwrap();
+        assert_eq!(&vec![switzerland], region.children());
+        assert!(region.sites().is_empty());
+        let region = topology.get_region(switzerland).unwrap();
+        assert_eq!(Some(europe), region.parent());
+        assert_eq!(&vec![zurich], region.sites());
+        assert_eq!(None, topology.get_region(orphan).unwrap().parent());
+        // regions and site groups have separate id spaces
+        assert_eq!(
+            &vec![zurich],
+            topology.get_site_group(campus).unwrap().sites()
+        );
+        let site = topology.get_site(zurich).unwrap();
+        assert_eq!(Some(switzerland), site.region());
+        assert_eq!(Some(campus), site.group());
+    }
+}
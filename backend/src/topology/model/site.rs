@@ -6,24 +6,15 @@ pub struct Site {
     name: String,
     address: String,
     locations: Vec<usize>,
+    vlan_groups: Vec<usize>,
+    region: Option<usize>,
+    group: Option<usize>,
+    tenant: Option<usize>,
 }
 
 impl Site {
-    pub(crate) fn new(id: u32, name: String, address: String, locations: HashSet<usize>) -> Site {
-        Site {
-            id,
-            name,
-            address,
-            locations: locations.into_iter().collect(),
-        }
-    }
     pub fn builder(id: u32, name: String, address: String) -> SiteBuilder {
-        SiteBuilder {
-            id,
-            name,
-            address,
-            locations: vec![],
-        }
+        SiteBuilder::new(id, name, address)
     }
     pub fn id(&self) -> u32 {
         self.id
@@ -37,6 +28,19 @@ impl Site {
     pub fn locations(&self) -> &Vec<usize> {
         &self.locations
     }
+    pub fn vlan_groups(&self) -> &Vec<usize> {
+        &self.vlan_groups
+    }
+    pub fn region(&self) -> Option<usize> {
+        self.region
+    }
+    pub fn group(&self) -> Option<usize> {
+        self.group
+    }
+    /// tenant assigned to the site
+    pub fn tenant(&self) -> Option<usize> {
+        self.tenant
+    }
 }
 
 pub struct SiteBuilder {
@@ -44,6 +48,10 @@ pub struct SiteBuilder {
     name: String,
     address: String,
     locations: Vec<(u32, String)>,
+    vlan_groups: Vec<(u32, String, String)>,
+    region_id: Option<u32>,
+    group_id: Option<u32>,
+    tenant_id: Option<u32>,
 }
 
 impl SiteBuilder {
@@ -53,8 +61,33 @@ impl SiteBuilder {
             name,
             address,
             locations: vec![],
+            vlan_groups: vec![],
+            region_id: None,
+            group_id: None,
+            tenant_id: None,
         }
     }
+    pub fn set_region(&mut self, id: u32) {
+        self.region_id = Some(id);
+    }
+    pub fn set_group(&mut self, id: u32) {
+        self.group_id = Some(id);
+    }
+    pub fn set_tenant(&mut self, id: u32) {
+        self.tenant_id = Some(id);
+    }
+    pub fn region_id(&self) -> Option<u32> {
+        self.region_id
+    }
+    pub fn group_id(&self) -> Option<u32> {
+        self.group_id
+    }
+    pub fn append_vlan_group(&mut self, id: u32, name: String, slug: String) {
+        self.vlan_groups.push((id, name, slug));
+    }
+    pub fn vlan_groups(&self) -> &Vec<(u32, String, String)> {
+        &self.vlan_groups
+    }
     pub(crate) fn append_location(&mut self, id: u32, name: String) {
         self.locations.push((id, name));
     }
@@ -62,8 +95,29 @@ impl SiteBuilder {
     pub fn id(&self) -> u32 {
         self.id
     }
-    pub fn destruct(self) -> (u32, String, String) {
-        (self.id, self.name, self.address)
+    pub(crate) fn build<RM, GM, TM>(
+        self,
+        region_mapper: &RM,
+        group_mapper: &GM,
+        tenant_mapper: &TM,
+        locations: HashSet<usize>,
+        vlan_groups: Vec<usize>,
+    ) -> Site
+    where
+        RM: Fn(u32) -> Option<usize>,
+        GM: Fn(u32) -> Option<usize>,
+        TM: Fn(u32) -> Option<usize>,
+    {
+        Site {
+            id: self.id,
+            name: self.name,
+            address: self.address,
+            locations: locations.into_iter().collect(),
+            vlan_groups,
+            region: self.region_id.and_then(region_mapper),
+            group: self.group_id.and_then(group_mapper),
+            tenant: self.tenant_id.and_then(tenant_mapper),
+        }
     }
     pub fn locations(&self) -> &Vec<(u32, String)> {
         &self.locations
@@ -0,0 +1,163 @@
+use std::sync::Arc;
+
+use crate::topology::model::{Device, Location, Prefix, Topology};
+
+/// owner of sites, locations, devices and prefixes
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub struct Tenant {
+    id: u32,
+    name: String,
+    slug: String,
+    group: Option<String>,
+}
+
+impl Tenant {
+    pub fn new(id: u32, name: String, slug: String, group: Option<String>) -> Self {
+        Self {
+            id,
+            name,
+            slug,
+            group,
+        }
+    }
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn slug(&self) -> &str {
+        &self.slug
+    }
+    /// name of the tenant group
+    pub fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+}
+
+impl Topology {
+    pub fn get_tenant(self: &Arc<Self>, idx: usize) -> Option<Arc<Tenant>> {
+        self.tenants.get(idx).cloned()
+    }
+    pub fn get_tenant_by_id(self: &Arc<Self>, key: u32) -> Option<Arc<Tenant>> {
+        self.get_tenant(*self.tenant_index.get(&key)?)
+    }
+    /// all tenants assigned to any site, location, device or prefix
+    pub fn list_tenants(self: &Arc<Self>) -> Vec<Arc<Tenant>> {
+        self.tenants.clone()
+    }
+
+    /// tenant of the location, inherited from the enclosing locations or the site if not set
+    pub fn tenant_of_location(self: &Arc<Self>, location: &Location) -> Option<usize> {
+        let mut current = location;
+        // the depth limit protects against loops in the location tree
+        for _ in 0..self.locations.len() {
+            if let Some(tenant) = current.tenant() {
+                return Some(tenant);
+            }
+            match current.parent().and_then(|idx| self.locations.get(idx)) {
+                Some(parent) => current = parent,
+                None => break,
+            }
+        }
+        self.sites.get(location.site()?)?.tenant()
+    }
+    /// tenant of the device, inherited from its location or site if not set
+    pub fn tenant_of_device(self: &Arc<Self>, device: &Device) -> Option<usize> {
+        device
+            .tenant()
+            .or_else(|| self.tenant_of_location(self.locations.get(device.location()?)?))
+            .or_else(|| self.sites.get(device.site()?)?.tenant())
+    }
+    /// tenant of the prefix, inherited from its site if not set
+    pub fn tenant_of_prefix(self: &Arc<Self>, prefix: &Prefix) -> Option<usize> {
+        prefix
+            .tenant()
+            .or_else(|| self.sites.get(prefix.site()?)?.tenant())
+    }
+    /// true if no tenant slug is requested or the tenant has the requested slug
+    pub fn tenant_matches(self: &Arc<Self>, tenant: Option<usize>, slug: Option<&str>) -> bool {
+        match slug {
+            None => true,
+            Some(slug) => tenant
+                .and_then(|idx| self.tenants.get(idx))
+                .map(|t| t.slug() == slug)
+                .unwrap_or(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::topology::model::device::DeviceBuilder;
+    use crate::topology::model::{DeviceType, Prefix, Tenant, Topology};
+
+    #[test]
+    fn test_inherit_tenant() {
+        let tenant =
+            |id: u32, slug: &str| Tenant::new(id, slug.to_string(), slug.to_string(), None);
+        let mut topology_builder = Topology::builder();
+        topology_builder.append_device_type(DeviceType::new("crs326".to_string(), 1, true));
+        // tenants are embedded into every object and therefore appended multiple times
+        for _ in 0..2 {
+            topology_builder.append_tenant(tenant(90, "owner"));
+        }
+        topology_builder.append_tenant(tenant(91, "lessee"));
+        topology_builder.append_tenant(tenant(92, "guest"));
+        let site_idx = topology_builder.append_site(20, "zurich".to_string(), String::new());
+        topology_builder.set_tenant_of_site(site_idx, 90);
+        let floor = topology_builder.append_location(10, "floor".to_string(), vec![]);
+        topology_builder.set_site_of_location(floor, site_idx);
+        topology_builder.set_tenant_of_location(floor, 91);
+        let room = topology_builder.append_location(11, "room".to_string(), vec![0, 2]);
+        topology_builder.set_site_of_location(room, site_idx);
+        topology_builder.set_parent_of_location(room, 10);
+        for (id, location_id, tenant_id) in [
+            (1, Some(11), None),
+            (2, None, None),
+            (3, Some(11), Some(92)),
+        ] {
+            let mut device_builder = DeviceBuilder::new(id, format!("sw0{id}"), true);
+            device_builder.set_device_type(1);
+            device_builder.set_site(20);
+            if let Some(location_id) = location_id {
+                device_builder.set_location(location_id);
+            }
+            if let Some(tenant_id) = tenant_id {
+                device_builder.set_tenant(tenant_id);
+            }
+            topology_builder.append_device(device_builder);
+        }
+        let mut prefix_builder = Prefix::builder(1, "10.0.0.0/24".parse().unwrap());
+        prefix_builder.set_site(20);
+        topology_builder.append_prefix(prefix_builder);
+        let topology = topology_builder.build().unwrap();
+
+        assert_eq!(3, topology.list_tenants().len());
+        let slug_of = |tenant: Option<usize>| {
+            tenant.and_then(|idx| Some(topology.get_tenant(idx)?.slug().to_string()))
+        };
+        let device_tenant =
+            |id| slug_of(topology.tenant_of_device(&topology.get_device_by_id(id).unwrap()));
+        assert_eq!(Some("lessee".to_string()), device_tenant(1));
+        assert_eq!(Some("owner".to_string()), device_tenant(2));
+        assert_eq!(Some("guest".to_string()), device_tenant(3));
+        let room = topology.get_location_by_id(11).unwrap();
+        assert_eq!(None, room.tenant());
+        assert_eq!(
+            Some("lessee".to_string()),
+            slug_of(topology.tenant_of_location(&room))
+        );
+        let prefix = topology.get_prefix(0).unwrap();
+        assert_eq!(
+            Some("owner".to_string()),
+            slug_of(topology.tenant_of_prefix(&prefix))
+        );
+        assert!(topology.tenant_matches(
+            topology.tenant_of_device(&topology.get_device_by_id(2).unwrap()),
+            Some("owner")
+        ));
+        assert!(!topology.tenant_matches(None, Some("owner")));
+        assert!(topology.tenant_matches(None, None));
+    }
+}
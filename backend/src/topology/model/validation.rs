@@ -0,0 +1,236 @@
+use std::collections::{BTreeMap, HashMap};
+use std::net::IpAddr;
+use std::ops::Deref;
+
+use ipnet::IpNet;
+
+use crate::topology::model::device::{DevicePort, PortIdx};
+use crate::topology::model::Topology;
+
+/// Inconsistency found in the inventory data
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub enum TopologyIssue {
+    /// RouterOS device without an address marked as loopback
+    MissingLoopback { device: usize },
+    /// same ip address assigned to multiple interfaces within one vrf
+    DuplicateAddress {
+        address: IpAddr,
+        vrf: Option<String>,
+        ports: Vec<PortIdx>,
+    },
+    /// same prefix maintained multiple times within one vrf, only the first one is used for lookups
+    DuplicatePrefix {
+        net: IpNet,
+        vrf: Option<String>,
+        prefix_ids: Vec<u32>,
+    },
+    /// device not assigned to a location
+    MissingLocation { device: usize },
+    /// cable terminates on a port not known in the topology
+    UnknownCableTermination { cable_id: u32, port_id: u32 },
+    /// cable could not be converted into a link
+    InvalidCable { cable_id: u32, reason: String },
+    /// wireless link could not be converted into a link
+    InvalidWirelessLink {
+        wireless_link_id: u32,
+        reason: String,
+    },
+    /// power feed with values not known to this application
+    InvalidPowerFeed { power_feed_id: u32, reason: String },
+    /// provider circuit with values not known to this application
+    InvalidCircuit { circuit_id: u32, reason: String },
+    /// module installed in a device with values not known to this application
+    InvalidModule {
+        device: usize,
+        module_id: u32,
+        reason: String,
+    },
+    /// monitoring custom field of a device with an unusable value
+    InvalidMonitoringSetting { device: usize, reason: String },
+    /// rear port behind the given front ports has no cable
+    UncabledRearPort {
+        rear_port: PortIdx,
+        front_ports: Vec<PortIdx>,
+    },
+}
+
+impl TopologyIssue {
+    /// devices affected by this issue
+    pub fn devices(&self) -> Vec<usize> {
+        match self {
+            TopologyIssue::MissingLoopback { device } => vec![*device],
+            TopologyIssue::MissingLocation { device } => vec![*device],
+            TopologyIssue::InvalidMonitoringSetting { device, .. } => vec![*device],
+            TopologyIssue::InvalidModule { device, .. } => vec![*device],
+            TopologyIssue::DuplicateAddress { ports, .. } => {
+                let mut devices: Vec<_> = ports.iter().map(|p| p.device_idx()).collect();
+                devices.dedup();
+                devices
+            }
+            TopologyIssue::UncabledRearPort { rear_port, .. } => vec![rear_port.device_idx()],
+            TopologyIssue::DuplicatePrefix { .. } => vec![],
+            TopologyIssue::UnknownCableTermination { .. } => vec![],
+            TopologyIssue::InvalidCable { .. } => vec![],
+            TopologyIssue::InvalidWirelessLink { .. } => vec![],
+            TopologyIssue::InvalidPowerFeed { .. } => vec![],
+            TopologyIssue::InvalidCircuit { .. } => vec![],
+        }
+    }
+    /// ports affected by this issue
+    pub fn ports(&self) -> Vec<PortIdx> {
+        match self {
+            TopologyIssue::DuplicateAddress { ports, .. } => ports.clone(),
+            TopologyIssue::UncabledRearPort {
+                rear_port,
+                front_ports,
+            } => Some(*rear_port)
+                .into_iter()
+                .chain(front_ports.iter().copied())
+                .collect(),
+            _ => vec![],
+        }
+    }
+    pub fn cable_id(&self) -> Option<u32> {
+        match self {
+            TopologyIssue::UnknownCableTermination { cable_id, .. } => Some(*cable_id),
+            TopologyIssue::InvalidCable { cable_id, .. } => Some(*cable_id),
+            _ => None,
+        }
+    }
+}
+
+/// check the completely built topology for inconsistencies
+pub(crate) fn validate(topology: &Topology) -> Vec<TopologyIssue> {
+    let mut issues = Vec::new();
+    let mut ports_of_address: BTreeMap<(Option<String>, IpAddr), Vec<PortIdx>> = BTreeMap::new();
+    for (device_idx, device) in topology.devices.iter().enumerate() {
+        if device.has_routeros() && device.get_loopback_address().is_none() {
+            issues.push(TopologyIssue::MissingLoopback { device: device_idx });
+        }
+        if device.location().is_none() {
+            issues.push(TopologyIssue::MissingLocation { device: device_idx });
+        }
+        let mut front_ports_of_rear: HashMap<usize, Vec<PortIdx>> = HashMap::new();
+        for (port_idx, port) in device.ports().into_iter().enumerate() {
+            let port_ref = PortIdx::new(device_idx, port_idx);
+            // anycast and fhrp addresses are expected to show up more than once
+            for address in port.addresses().iter().filter(|a| !a.role().is_shared()) {
+                ports_of_address
+                    .entry((address.vrf().map(str::to_string), address.net().addr()))
+                    .or_default()
+                    .push(port_ref);
+            }
+            if let DevicePort::FrontPort { rear_port_idx, .. } = port.deref() {
+                front_ports_of_rear
+                    .entry(*rear_port_idx)
+                    .or_default()
+                    .push(port_ref);
+            }
+        }
+        let mut uncabled: Vec<_> = front_ports_of_rear
+            .into_iter()
+            .filter(|(rear_idx, _)| {
+                !topology
+                    .link_index
+                    .contains_key(&PortIdx::new(device_idx, *rear_idx))
+            })
+            .collect();
+        uncabled.sort_by_key(|(rear_idx, _)| *rear_idx);
+        for (rear_idx, front_ports) in uncabled {
+            issues.push(TopologyIssue::UncabledRearPort {
+                rear_port: PortIdx::new(device_idx, rear_idx),
+                front_ports,
+            });
+        }
+    }
+    let mut prefixes_of_net: BTreeMap<(Option<String>, IpNet), Vec<u32>> = BTreeMap::new();
+    for prefix in topology.prefixes.iter() {
+        prefixes_of_net
+            .entry((prefix.vrf().map(str::to_string), prefix.net()))
+            .or_default()
+            .push(prefix.id());
+    }
+    for ((vrf, net), prefix_ids) in prefixes_of_net {
+        if prefix_ids.len() > 1 {
+            issues.push(TopologyIssue::DuplicatePrefix {
+                net,
+                vrf,
+                prefix_ids,
+            });
+        }
+    }
+    for ((vrf, address), ports) in ports_of_address {
+        if ports.len() > 1 {
+            issues.push(TopologyIssue::DuplicateAddress {
+                address,
+                vrf,
+                ports,
+            });
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::topology::model::device::{AddressRole, DeviceBuilder, InterfaceAddress, PortIdx};
+    use crate::topology::model::validation::TopologyIssue;
+    use crate::topology::model::{DeviceType, TopologyBuilder};
+
+    #[test]
+    fn test_report_missing_loopback_and_duplicate_address() {
+        let mut topology_builder = TopologyBuilder::default();
+        topology_builder.append_device_type(DeviceType::new("generic".to_string(), 1, false));
+
+        let mut router = DeviceBuilder::new(1, "rt01".to_string(), true);
+        router.set_device_type(1);
+        let router_port = router.append_interface(
+            10,
+            "ether1".to_string(),
+            vec![
+                InterfaceAddress::new("10.0.0.1/24".parse().unwrap(), AddressRole::Primary, None),
+                InterfaceAddress::new("10.0.1.1/24".parse().unwrap(), AddressRole::Anycast, None),
+                InterfaceAddress::new(
+                    "10.0.2.1/24".parse().unwrap(),
+                    AddressRole::Secondary,
+                    Some("mgmt".to_string()),
+                ),
+            ],
+        );
+        let router_idx = topology_builder.append_device(router);
+
+        let mut server = DeviceBuilder::new(2, "srv01".to_string(), false);
+        server.set_device_type(1);
+        let server_port = server.append_interface(
+            20,
+            "eth0".to_string(),
+            vec![
+                InterfaceAddress::new("10.0.0.1/24".parse().unwrap(), AddressRole::Primary, None),
+                InterfaceAddress::new("10.0.1.1/24".parse().unwrap(), AddressRole::Anycast, None),
+                InterfaceAddress::new("10.0.2.1/24".parse().unwrap(), AddressRole::Primary, None),
+            ],
+        );
+        let server_idx = topology_builder.append_device(server);
+
+        let topology = topology_builder.build().unwrap();
+        let issues = topology.issues();
+        assert!(issues.contains(&TopologyIssue::MissingLoopback { device: router_idx }));
+        assert!(issues.contains(&TopologyIssue::MissingLocation { device: server_idx }));
+        assert!(issues.contains(&TopologyIssue::DuplicateAddress {
+            address: "10.0.0.1".parse().unwrap(),
+            vrf: None,
+            ports: vec![
+                PortIdx::new(router_idx, router_port),
+                PortIdx::new(server_idx, server_port)
+            ],
+        }));
+        // anycast and addresses in different vrfs are no duplicates
+        assert_eq!(
+            1,
+            issues
+                .iter()
+                .filter(|i| matches!(i, TopologyIssue::DuplicateAddress { .. }))
+                .count()
+        );
+    }
+}
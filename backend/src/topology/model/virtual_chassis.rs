@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use crate::topology::model::Topology;
+
+/// stack of devices managed as one, e.g. CRS switches
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub struct VirtualChassis {
+    id: u32,
+    name: String,
+    domain: String,
+    master: Option<usize>,
+    members: Vec<usize>,
+}
+
+/// place of a device within its virtual chassis
+#[derive(Clone, Copy, Hash, Eq, PartialEq, Debug)]
+pub struct ChassisMembership {
+    chassis: usize,
+    position: Option<u32>,
+    priority: Option<u32>,
+}
+
+impl VirtualChassis {
+    pub fn builder(id: u32, name: String, domain: String) -> VirtualChassisBuilder {
+        VirtualChassisBuilder {
+            id,
+            name,
+            domain,
+            master_id: None,
+        }
+    }
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn domain(&self) -> &str {
+        &self.domain
+    }
+    /// device controlling the chassis
+    pub fn master(&self) -> Option<usize> {
+        self.master
+    }
+    /// member devices ordered by their position
+    pub fn members(&self) -> &Vec<usize> {
+        &self.members
+    }
+}
+
+impl ChassisMembership {
+    pub fn new(chassis: usize, position: Option<u32>, priority: Option<u32>) -> Self {
+        Self {
+            chassis,
+            position,
+            priority,
+        }
+    }
+    pub fn chassis(&self) -> usize {
+        self.chassis
+    }
+    pub fn position(&self) -> Option<u32> {
+        self.position
+    }
+    /// priority within the master election
+    pub fn priority(&self) -> Option<u32> {
+        self.priority
+    }
+}
+
+pub struct VirtualChassisBuilder {
+    id: u32,
+    name: String,
+    domain: String,
+    master_id: Option<u32>,
+}
+
+impl VirtualChassisBuilder {
+    pub fn set_master(&mut self, device_id: u32) {
+        self.master_id = Some(device_id);
+    }
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub(crate) fn build<DM>(self, device_mapper: &DM, members: Vec<usize>) -> VirtualChassis
+    where
+        DM: Fn(u32) -> Option<usize>,
+    {
+        VirtualChassis {
+            id: self.id,
+            name: self.name,
+            domain: self.domain,
+            master: self.master_id.and_then(device_mapper),
+            members,
+        }
+    }
+}
+
+impl Topology {
+    pub fn get_virtual_chassis(self: &Arc<Self>, idx: usize) -> Option<Arc<VirtualChassis>> {
+        self.virtual_chassis.get(idx).cloned()
+    }
+    pub fn list_virtual_chassis_map<P: Fn(&Arc<VirtualChassis>) -> Option<T>, T>(
+        self: &Arc<Self>,
+        filter: P,
+    ) -> Vec<T> {
+        self.virtual_chassis.iter().flat_map(filter).collect()
+    }
+}
@@ -0,0 +1,186 @@
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub struct Vlan {
+    id: u32,
+    vid: u16,
+    name: String,
+    group: Option<usize>,
+    site: Option<usize>,
+}
+
+impl Vlan {
+    pub fn builder(id: u32, vid: u16, name: String) -> VlanBuilder {
+        VlanBuilder {
+            id,
+            vid,
+            name,
+            group_id: None,
+            site_id: None,
+        }
+    }
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    /// 802.1q vlan id
+    pub fn vid(&self) -> u16 {
+        self.vid
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn group(&self) -> Option<usize> {
+        self.group
+    }
+    pub fn site(&self) -> Option<usize> {
+        self.site
+    }
+}
+
+pub struct VlanBuilder {
+    id: u32,
+    vid: u16,
+    name: String,
+    group_id: Option<u32>,
+    site_id: Option<u32>,
+}
+
+impl VlanBuilder {
+    pub fn set_group(&mut self, id: u32) {
+        self.group_id = Some(id);
+    }
+    pub fn set_site(&mut self, id: u32) {
+        self.site_id = Some(id);
+    }
+    pub fn group_id(&self) -> Option<u32> {
+        self.group_id
+    }
+
+    pub(crate) fn build<GM, SM>(self, group_mapper: &GM, site_mapper: &SM) -> Vlan
+    where
+        GM: Fn(u32) -> Option<usize>,
+        SM: Fn(u32) -> Option<usize>,
+    {
+        Vlan {
+            id: self.id,
+            vid: self.vid,
+            name: self.name,
+            group: self.group_id.and_then(group_mapper),
+            site: self.site_id.and_then(site_mapper),
+        }
+    }
+}
+
+/// group of vlans assigned to a site
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub struct VlanGroup {
+    id: u32,
+    name: String,
+    slug: String,
+    site: usize,
+    vlans: Vec<usize>,
+}
+
+impl VlanGroup {
+    pub(crate) fn new(id: u32, name: String, slug: String, site: usize, vlans: Vec<usize>) -> Self {
+        Self {
+            id,
+            name,
+            slug,
+            site,
+            vlans,
+        }
+    }
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn slug(&self) -> &str {
+        &self.slug
+    }
+    pub fn site(&self) -> usize {
+        self.site
+    }
+    pub fn vlans(&self) -> &Vec<usize> {
+        &self.vlans
+    }
+}
+
+/// 802.1q mode of an interface
+#[derive(Clone, Copy, Hash, Eq, PartialEq, Debug)]
+pub enum InterfaceMode {
+    Access,
+    Tagged,
+    /// tagged member of all vlans available on the site of the device, including global vlans
+    TaggedAll,
+}
+
+/// vlan membership of an interface, vlans are referenced by their netbox id
+#[derive(Clone, Hash, Eq, PartialEq, Debug, Default)]
+pub struct InterfaceVlans {
+    mode: Option<InterfaceMode>,
+    untagged: Option<u32>,
+    tagged: Vec<u32>,
+}
+
+impl InterfaceVlans {
+    pub fn new(mode: Option<InterfaceMode>, untagged: Option<u32>, tagged: Vec<u32>) -> Self {
+        Self {
+            mode,
+            untagged,
+            tagged,
+        }
+    }
+    pub fn mode(&self) -> Option<InterfaceMode> {
+        self.mode
+    }
+    pub fn untagged(&self) -> Option<u32> {
+        self.untagged
+    }
+    pub fn tagged(&self) -> &Vec<u32> {
+        &self.tagged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::topology::model::device::{DeviceBuilder, PortIdx};
+    use crate::topology::model::vlan::{InterfaceMode, InterfaceVlans, Vlan};
+    use crate::topology::model::{DeviceType, Topology};
+
+    #[test]
+    fn test_tagged_all_ports_of_vlan() {
+        let mut topology_builder = Topology::builder();
+        topology_builder.append_device_type(DeviceType::new("crs326".to_string(), 1, true));
+        let zurich = topology_builder.append_site(20, "zurich".to_string(), String::new());
+        topology_builder.append_site(21, "bern".to_string(), String::new());
+        topology_builder.append_vlan_group(zurich, 30, "zurich".to_string(), "zurich".to_string());
+        let global = topology_builder.append_vlan(Vlan::builder(1, 10, "mgmt".to_string()));
+        let mut site_vlan = Vlan::builder(2, 20, "office".to_string());
+        site_vlan.set_site(20);
+        let site_vlan = topology_builder.append_vlan(site_vlan);
+        let mut group_vlan = Vlan::builder(3, 30, "guests".to_string());
+        group_vlan.set_group(30);
+        let group_vlan = topology_builder.append_vlan(group_vlan);
+        let mut other_site_vlan = Vlan::builder(4, 40, "office".to_string());
+        other_site_vlan.set_site(21);
+        let other_site_vlan = topology_builder.append_vlan(other_site_vlan);
+
+        let mut device_builder = DeviceBuilder::new(1, "sw01".to_string(), true);
+        device_builder.set_device_type(1);
+        device_builder.set_site(20);
+        let port_idx = device_builder.append_interface(1, "sfp1".to_string(), vec![]);
+        device_builder.set_vlans(
+            port_idx,
+            InterfaceVlans::new(Some(InterfaceMode::TaggedAll), Some(1), vec![]),
+        );
+        let device_idx = topology_builder.append_device(device_builder);
+        let topology = topology_builder.build().unwrap();
+
+        let port = PortIdx::new(device_idx, port_idx);
+        for vlan_idx in [global, site_vlan, group_vlan] {
+            assert_eq!(&[port], topology.ports_of_vlan(vlan_idx));
+        }
+        assert!(topology.ports_of_vlan(other_site_vlan).is_empty());
+    }
+}
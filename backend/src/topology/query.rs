@@ -3,8 +3,8 @@ use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
-use cached::proc_macro::cached;
 use graphql_client::{GraphQLQuery, Response};
 use ipnet::IpNet;
 use log::{debug, info, warn};
@@ -13,16 +13,106 @@ use thiserror::Error;
 
 use crate::config::config;
 use crate::error::{BackendError, GraphqlError};
-use crate::topology::graphql_operations::fetch_topology::IpamIPAddressRoleChoices;
-use crate::topology::graphql_operations::FetchTopology;
-use crate::topology::model::device::DeviceBuilder;
+use crate::topology::graphql_operations::fetch_topology::{
+    CircuitsCircuitStatusChoices, CircuitsCircuitTerminationTermSideChoices,
+    DcimCableTerminationCableEndChoices, DcimInterfaceModeChoices, DcimModuleStatusChoices,
+    DcimPowerFeedStatusChoices, FetchTopologyCableListTerminationsTermination,
+    IpamIPAddressRoleChoices, ResponseData, TenantFields, WirelessWirelessLinkStatusChoices,
+};
+use crate::topology::model::circuit::{Circuit, CircuitStatus, Provider, TerminationSide};
+use crate::topology::model::device::{
+    AddressRole, DeviceBuilder, DeviceCategory, InterfaceAddress, PortIdx,
+};
 use crate::topology::model::device_type::DeviceType;
-use crate::topology::model::Topology;
+use crate::topology::model::link::{LinkBuilder, WirelessLink, WirelessLinkStatus};
+use crate::topology::model::module::{Module, ModuleBay, ModuleStatus, ModuleType};
+use crate::topology::model::monitoring::{
+    Criticality, MonitoringSettings, FIELD_CHECK_INTERVAL, FIELD_CRITICALITY, FIELD_ENABLED,
+    FIELD_PING_TARGET, TAG_DISABLED, TAG_ENABLED,
+};
+use crate::topology::model::power::{PowerFeed, PowerFeedStatus, PowerSource};
+use crate::topology::model::rack::RackPosition;
+use crate::topology::model::validation::TopologyIssue;
+use crate::topology::model::virtual_chassis::VirtualChassis;
+use crate::topology::model::vlan::{InterfaceMode, InterfaceVlans};
+use crate::topology::model::{Prefix, Rack, Region, Tenant, Topology, TopologyBuilder, Vlan};
+use crate::topology::source::TopologySource;
 
 enum PortType {
     Interface,
     Front,
     Rear,
+    PowerPort,
+    PowerOutlet,
+    PowerFeed,
+    CircuitTermination,
+}
+
+/// cable end of a cable carrying power instead of data
+#[derive(Copy, Clone, Debug)]
+enum PowerEnd {
+    Port(usize, usize),
+    Source(PowerSource),
+}
+
+/// pair multi terminated cable ends, a single termination connects to all on the other end
+fn pair_cable_ends<A: Copy, B: Copy>(a_ends: &[A], b_ends: &[B]) -> Option<Vec<(A, B)>> {
+    if a_ends.len() == b_ends.len() {
+        Some(a_ends.iter().copied().zip(b_ends.iter().copied()).collect())
+    } else if a_ends.len() == 1 || b_ends.len() == 1 {
+        Some(
+            a_ends
+                .iter()
+                .flat_map(|a| b_ends.iter().map(|b| (*a, *b)))
+                .collect(),
+        )
+    } else {
+        None
+    }
+}
+
+/// read the monitoring settings from the custom fields and tags of a device, returns the rejected values too
+fn monitoring_settings(
+    custom_fields: Option<&serde_json::Value>,
+    tags: &[&str],
+) -> (MonitoringSettings, Vec<String>) {
+    let mut settings = MonitoringSettings::default();
+    let mut errors = Vec::new();
+    let field = |name| {
+        custom_fields
+            .and_then(|f| f.get(name))
+            .filter(|v| !v.is_null())
+    };
+    match field(FIELD_ENABLED).map(|v| v.as_bool()) {
+        Some(Some(enabled)) => settings.set_enabled(enabled),
+        Some(None) => errors.push(format!("{FIELD_ENABLED} is not a boolean")),
+        None if tags.contains(&TAG_DISABLED) => settings.set_enabled(false),
+        None if tags.contains(&TAG_ENABLED) => settings.set_enabled(true),
+        None => {}
+    }
+    if let Some(value) = field(FIELD_PING_TARGET) {
+        match value
+            .as_str()
+            .map(|v| v.split('/').next().unwrap_or(v).parse())
+        {
+            Some(Ok(target)) => settings.set_ping_target(target),
+            _ => errors.push(format!("{FIELD_PING_TARGET}: invalid address {value}")),
+        }
+    }
+    if let Some(value) = field(FIELD_CRITICALITY) {
+        match value.as_str().map(|v| v.parse::<Criticality>()) {
+            Some(Ok(criticality)) => settings.set_criticality(criticality),
+            Some(Err(error)) => errors.push(format!("{FIELD_CRITICALITY}: {error}")),
+            None => errors.push(format!("{FIELD_CRITICALITY} is not a string")),
+        }
+    }
+    if let Some(value) = field(FIELD_CHECK_INTERVAL) {
+        match value.as_u64().filter(|s| *s > 0) {
+            Some(seconds) => settings.set_check_interval(Duration::from_secs(seconds)),
+            None => errors.push(format!("{FIELD_CHECK_INTERVAL}: invalid interval {value}")),
+        }
+    }
+    (settings, errors)
 }
 
 #[derive(Debug, Error, Clone)]
@@ -46,28 +136,61 @@ impl FromStr for PortType {
     }
 }
 
-#[cached(result, time = 30, time_refresh)]
-pub async fn get_topology() -> Result<Arc<Topology>, BackendError> {
-    let result = do_fetch_topology().await;
-    match result.as_ref() {
-        Ok(_) => {
-            info!("Fetched topology successful");
-        }
-        Err(error) => {
-            warn!("Error fetching topology {error}");
+/// fetch the topology from the configured source
+pub async fn fetch_topology() -> Result<Arc<Topology>, BackendError> {
+    let netbox_topology = TopologySource::from_config()?.fetch().await?;
+    build_topology(netbox_topology, &|role| {
+        config().device_category_of_role(role)
+    })
+}
+
+fn address_role(role: Option<&IpamIPAddressRoleChoices>) -> AddressRole {
+    match role {
+        None => AddressRole::Primary,
+        Some(IpamIPAddressRoleChoices::SECONDARY) => AddressRole::Secondary,
+        Some(IpamIPAddressRoleChoices::LOOPBACK) => AddressRole::Loopback,
+        Some(IpamIPAddressRoleChoices::ANYCAST) => AddressRole::Anycast,
+        Some(IpamIPAddressRoleChoices::VIP) => AddressRole::Vip,
+        Some(IpamIPAddressRoleChoices::VRRP) => AddressRole::Vrrp,
+        Some(IpamIPAddressRoleChoices::HSRP) => AddressRole::Hsrp,
+        Some(IpamIPAddressRoleChoices::GLBP) => AddressRole::Glbp,
+        Some(IpamIPAddressRoleChoices::CARP) => AddressRole::Carp,
+        Some(IpamIPAddressRoleChoices::Other(role)) => {
+            warn!("Unknown address role {role}");
+            AddressRole::Primary
         }
     }
-    return result;
 }
-async fn do_fetch_topology() -> Result<Arc<Topology>, BackendError> {
+
+/// register the tenant embedded into an object, returns its netbox id
+fn tenant(topo_builder: &mut TopologyBuilder, fields: TenantFields) -> Result<u32, BackendError> {
+    let id = fields.id.parse()?;
+    topo_builder.append_tenant(Tenant::new(
+        id,
+        fields.name,
+        fields.slug,
+        fields.group.map(|group| group.name),
+    ));
+    Ok(id)
+}
+
+pub(crate) fn build_topology<CM>(
+    netbox_topology: ResponseData,
+    category_mapper: &CM,
+) -> Result<Arc<Topology>, BackendError>
+where
+    CM: Fn(&str) -> Option<DeviceCategory>,
+{
     let mut topo_builder = Topology::builder();
     let mut device_id_map = HashMap::new();
     let mut device_interface_map = HashMap::new();
     let mut device_front_map = HashMap::new();
     let mut device_rear_map = HashMap::new();
+    let mut device_power_port_map = HashMap::new();
+    let mut device_power_outlet_map = HashMap::new();
     let mut devices_of_location: HashMap<_, Vec<_>> = HashMap::new();
+    let mut virtual_chassis_ids = HashSet::new();
 
-    let netbox_topology = query_netbox::<FetchTopology>(Default::default()).await?;
     let mut routeros_device_types = HashSet::new();
     let flatten = netbox_topology.device_type_list.into_iter().flatten();
     for type_entry in flatten {
@@ -95,45 +218,108 @@ async fn do_fetch_topology() -> Result<Arc<Topology>, BackendError> {
             has_routeros,
         );
         device_builder.set_device_type(device_type_id);
+        if let Some(category) = category_mapper(&device_entry.device_role.slug) {
+            device_builder.set_category(category);
+        }
+        let tags: Vec<_> = device_entry
+            .tags
+            .iter()
+            .flatten()
+            .flatten()
+            .map(|tag| tag.slug.as_str())
+            .collect();
+        let (monitoring, monitoring_errors) =
+            monitoring_settings(device_entry.custom_fields.as_ref(), &tags);
+        device_builder.set_monitoring(monitoring);
 
         let mut if_idx = Vec::with_capacity(device_entry.interfaces.len());
         for if_port in device_entry.interfaces {
             let id: u32 = if_port.id.parse()?;
             let name = if_port.name;
-            let mut ipv6_address = None;
-            let mut ipv4_address = None;
-            let mut is_loopback = false;
-            for ip_address in if_port.ip_addresses.iter().flat_map(|a| a.iter()) {
-                if let Some(address_entry) = ip_address.as_ref() {
-                    if let Ok(address) = address_entry.address.parse() {
-                        match address {
-                            IpNet::V4(a) => ipv4_address = Some(a),
-                            IpNet::V6(a) => ipv6_address = Some(a),
-                        }
-                    }
-                    if let Some(IpamIPAddressRoleChoices::LOOPBACK) = address_entry.role.as_ref() {
-                        is_loopback = true;
-                    }
+            let mut addresses = Vec::new();
+            for address_entry in if_port.ip_addresses.iter().flatten().flatten() {
+                match address_entry.address.parse::<IpNet>() {
+                    Ok(net) => addresses.push(InterfaceAddress::new(
+                        net,
+                        address_role(address_entry.role.as_ref()),
+                        address_entry.vrf.as_ref().map(|vrf| vrf.name.clone()),
+                    )),
+                    Err(error) => warn!(
+                        "Device {}: invalid address {} on {name}: {error}",
+                        device_entry.id, address_entry.address
+                    ),
                 }
             }
-            if_idx.push((
-                id,
-                device_builder.append_interface(id, name, ipv4_address, ipv6_address, is_loopback),
-            ));
+            let port_idx = device_builder.append_interface(id, name, addresses);
+            let mode = match if_port.mode {
+                None => None,
+                Some(DcimInterfaceModeChoices::ACCESS) => Some(InterfaceMode::Access),
+                Some(DcimInterfaceModeChoices::TAGGED) => Some(InterfaceMode::Tagged),
+                Some(DcimInterfaceModeChoices::TAGGED_ALL) => Some(InterfaceMode::TaggedAll),
+                Some(DcimInterfaceModeChoices::Other(mode)) => {
+                    warn!("Device {}: unknown interface mode {mode}", device_entry.id);
+                    None
+                }
+            };
+            let untagged = match if_port.untagged_vlan {
+                Some(vlan) => Some(vlan.id.parse()?),
+                None => None,
+            };
+            let tagged = if_port
+                .tagged_vlans
+                .into_iter()
+                .map(|vlan| vlan.id.parse())
+                .collect::<Result<_, _>>()?;
+            device_builder.set_vlans(port_idx, InterfaceVlans::new(mode, untagged, tagged));
+            if_idx.push((id, port_idx));
         }
-        let mut rear_idx_list = Vec::with_capacity(device_entry.frontports.len());
+        let mut rear_idx_list = HashMap::new();
         let mut front_idx_list = Vec::with_capacity(device_entry.frontports.len());
         for front_port in device_entry.frontports {
             let rear_port = front_port.rear_port;
             let rear_id = rear_port.id.parse()?;
-            let rear_idx = device_builder.append_rear_port(rear_id, rear_port.name);
-            rear_idx_list.push((rear_id, rear_idx));
+            // a rear port with multiple positions is referenced by several front ports
+            let rear_idx = *rear_idx_list
+                .entry(rear_id)
+                .or_insert_with(|| device_builder.append_rear_port(rear_id, rear_port.name));
             let front_id = front_port.id.parse()?;
-            let front_idx = device_builder.append_front_port(front_id, front_port.name, rear_idx);
+            let front_idx = device_builder.append_front_port(
+                front_id,
+                front_port.name,
+                rear_idx,
+                front_port.rear_port_position.try_into().unwrap_or(1),
+            );
             front_idx_list.push((front_id, front_idx));
         }
 
+        let mut power_port_idx_list = HashMap::new();
+        for power_port in device_entry.powerports {
+            let power_port_id: u32 = power_port.id.parse()?;
+            power_port_idx_list.insert(
+                power_port_id,
+                device_builder.append_power_port(power_port_id, power_port.name),
+            );
+        }
+        let mut power_outlet_idx_list = Vec::with_capacity(device_entry.poweroutlets.len());
+        for power_outlet in device_entry.poweroutlets {
+            let power_outlet_id = power_outlet.id.parse()?;
+            let power_port = match power_outlet.power_port {
+                Some(port) => power_port_idx_list.get(&port.id.parse()?).copied(),
+                None => None,
+            };
+            power_outlet_idx_list.push((
+                power_outlet_id,
+                device_builder.append_power_outlet(power_outlet_id, power_outlet.name, power_port),
+            ));
+        }
+
         let dev_idx = topo_builder.devices().len();
+        for reason in monitoring_errors {
+            topo_builder.append_issue(TopologyIssue::InvalidMonitoringSetting {
+                device: dev_idx,
+                reason,
+            });
+        }
         if let Some(id) = device_entry
             .location
             .and_then(|location_of_device| location_of_device.id.parse::<u32>().ok())
@@ -144,6 +330,66 @@ async fn do_fetch_topology() -> Result<Arc<Topology>, BackendError> {
         if let Ok(site_id) = device_entry.site.id.parse::<u32>() {
             device_builder.set_site(site_id);
         }
+        if let Some(rack) = device_entry.rack {
+            let position = device_entry
+                .position
+                .and_then(|p| RackPosition::from_units(*p));
+            device_builder.set_rack(rack.id.parse()?, position);
+        }
+        if let Some(fields) = device_entry.tenant {
+            device_builder.set_tenant(tenant(&mut topo_builder, fields)?);
+        }
+        if let Some(chassis) = device_entry.virtual_chassis {
+            let chassis_id = chassis.id.parse()?;
+            device_builder.set_virtual_chassis(
+                chassis_id,
+                device_entry.vc_position.and_then(|p| p.try_into().ok()),
+                device_entry.vc_priority.and_then(|p| p.try_into().ok()),
+            );
+            if virtual_chassis_ids.insert(chassis_id) {
+                let mut chassis_builder =
+                    VirtualChassis::builder(chassis_id, chassis.name, chassis.domain);
+                if let Some(master) = chassis.master {
+                    chassis_builder.set_master(master.id.parse()?);
+                }
+                topo_builder.append_virtual_chassis(chassis_builder);
+            }
+        }
+        for module in device_entry.modules {
+            let module_id = module.id.parse()?;
+            let status = match module.status {
+                DcimModuleStatusChoices::ACTIVE => ModuleStatus::Active,
+                DcimModuleStatusChoices::DECOMMISSIONING => ModuleStatus::Decommissioning,
+                DcimModuleStatusChoices::FAILED => ModuleStatus::Failed,
+                DcimModuleStatusChoices::OFFLINE => ModuleStatus::Offline,
+                DcimModuleStatusChoices::PLANNED => ModuleStatus::Planned,
+                DcimModuleStatusChoices::STAGED => ModuleStatus::Staged,
+                DcimModuleStatusChoices::Other(status) => {
+                    topo_builder.append_issue(TopologyIssue::InvalidModule {
+                        device: dev_idx,
+                        module_id,
+                        reason: format!("unknown status {status}"),
+                    });
+                    ModuleStatus::Unknown
+                }
+            };
+            device_builder.append_module(Module::new(
+                module_id,
+                ModuleBay::new(
+                    module.module_bay.id.parse()?,
+                    module.module_bay.name,
+                    module.module_bay.position,
+                ),
+                ModuleType::new(
+                    module.module_type.model,
+                    module.module_type.manufacturer.name,
+                    module.module_type.part_number,
+                ),
+                module.serial,
+                module.asset_tag.filter(|tag| !tag.is_empty()),
+                status,
+            ));
+        }
         topo_builder.append_device(device_builder);
         for (port_id, port_idx) in if_idx {
             device_interface_map.insert(port_id, (dev_idx, port_idx));
@@ -154,8 +400,327 @@ async fn do_fetch_topology() -> Result<Arc<Topology>, BackendError> {
         for (port_id, port_idx) in front_idx_list {
             device_front_map.insert(port_id, (dev_idx, port_idx));
         }
+        for (port_id, port_idx) in power_port_idx_list {
+            device_power_port_map.insert(port_id, (dev_idx, port_idx));
+        }
+        for (outlet_id, outlet_idx) in power_outlet_idx_list {
+            device_power_outlet_map.insert(outlet_id, (dev_idx, outlet_idx));
+        }
         device_id_map.insert(device_entry.id.clone(), dev_idx);
     }
+    let mut power_feed_map = HashMap::new();
+    for power_feed in netbox_topology.power_feed_list.into_iter().flatten() {
+        let power_feed_id = power_feed.id.parse()?;
+        let status = match power_feed.status {
+            DcimPowerFeedStatusChoices::ACTIVE => PowerFeedStatus::Active,
+            DcimPowerFeedStatusChoices::FAILED => PowerFeedStatus::Failed,
+            DcimPowerFeedStatusChoices::OFFLINE => PowerFeedStatus::Offline,
+            DcimPowerFeedStatusChoices::PLANNED => PowerFeedStatus::Planned,
+            DcimPowerFeedStatusChoices::Other(status) => {
+                topo_builder.append_issue(TopologyIssue::InvalidPowerFeed {
+                    power_feed_id,
+                    reason: format!("unknown status {status}"),
+                });
+                PowerFeedStatus::Unknown
+            }
+        };
+        let mut feed_builder = PowerFeed::builder(
+            power_feed_id,
+            power_feed.name,
+            power_feed.power_panel.name,
+            status,
+        );
+        feed_builder.set_site(power_feed.power_panel.site.id.parse()?);
+        if let Some(rack) = power_feed.rack {
+            feed_builder.set_rack(rack.id.parse()?);
+        }
+        power_feed_map.insert(power_feed_id, topo_builder.append_power_feed(feed_builder));
+    }
+    let mut circuit_termination_map = HashMap::new();
+    for circuit in netbox_topology.circuit_list.into_iter().flatten() {
+        let circuit_id = circuit.id.parse()?;
+        let status = match circuit.status {
+            CircuitsCircuitStatusChoices::ACTIVE => CircuitStatus::Active,
+            CircuitsCircuitStatusChoices::DECOMMISSIONED => CircuitStatus::Decommissioned,
+            CircuitsCircuitStatusChoices::DEPROVISIONING => CircuitStatus::Deprovisioning,
+            CircuitsCircuitStatusChoices::OFFLINE => CircuitStatus::Offline,
+            CircuitsCircuitStatusChoices::PLANNED => CircuitStatus::Planned,
+            CircuitsCircuitStatusChoices::PROVISIONING => CircuitStatus::Provisioning,
+            CircuitsCircuitStatusChoices::Other(status) => {
+                topo_builder.append_issue(TopologyIssue::InvalidCircuit {
+                    circuit_id,
+                    reason: format!("unknown status {status}"),
+                });
+                CircuitStatus::Unknown
+            }
+        };
+        let provider = Provider::new(
+            circuit.provider.id.parse()?,
+            circuit.provider.name,
+            circuit.provider.account,
+        );
+        let mut circuit_builder = Circuit::builder(
+            circuit_id,
+            circuit.cid,
+            provider,
+            circuit.type_.name,
+            status,
+        );
+        if let Some(commit_rate) = circuit.commit_rate.and_then(|r| r.try_into().ok()) {
+            circuit_builder.set_commit_rate(commit_rate);
+        }
+        circuit_builder.set_description(circuit.description);
+        let mut termination_idx_list = Vec::new();
+        for termination in circuit.terminations {
+            let side = match termination.term_side {
+                CircuitsCircuitTerminationTermSideChoices::A => TerminationSide::A,
+                CircuitsCircuitTerminationTermSideChoices::Z => TerminationSide::Z,
+                CircuitsCircuitTerminationTermSideChoices::Other(side) => {
+                    warn!("Circuit {circuit_id}: unknown termination side {side}");
+                    continue;
+                }
+            };
+            let site_id = match termination.site {
+                Some(site) => Some(site.id.parse()?),
+                None => None,
+            };
+            let termination_id = termination.id.parse::<u32>()?;
+            termination_idx_list.push((
+                termination_id,
+                circuit_builder.append_termination(
+                    termination_id,
+                    side,
+                    site_id,
+                    termination.port_speed.and_then(|s| s.try_into().ok()),
+                    termination.upstream_speed.and_then(|s| s.try_into().ok()),
+                    termination.xconnect_id,
+                ),
+            ));
+        }
+        let circuit_idx = topo_builder.append_circuit(circuit_builder);
+        for (termination_id, termination_idx) in termination_idx_list {
+            circuit_termination_map.insert(termination_id, (circuit_idx, termination_idx));
+        }
+    }
+    for cable in netbox_topology.cable_list.into_iter().flatten() {
+        let cable_id = cable.id.parse()?;
+        let mut a_ports = Vec::new();
+        let mut b_ports = Vec::new();
+        let mut a_power = Vec::new();
+        let mut b_power = Vec::new();
+        let mut a_circuits = Vec::new();
+        let mut b_circuits = Vec::new();
+        for termination in cable.terminations {
+            let port_ref = match termination.termination.as_ref() {
+                Some(FetchTopologyCableListTerminationsTermination::InterfaceType(port)) => {
+                    Some((PortType::Interface, port.id.parse::<u32>()?))
+                }
+                Some(FetchTopologyCableListTerminationsTermination::FrontPortType(port)) => {
+                    Some((PortType::Front, port.id.parse()?))
+                }
+                Some(FetchTopologyCableListTerminationsTermination::RearPortType(port)) => {
+                    Some((PortType::Rear, port.id.parse()?))
+                }
+                Some(FetchTopologyCableListTerminationsTermination::PowerPortType(port)) => {
+                    Some((PortType::PowerPort, port.id.parse()?))
+                }
+                Some(FetchTopologyCableListTerminationsTermination::PowerOutletType(outlet)) => {
+                    Some((PortType::PowerOutlet, outlet.id.parse()?))
+                }
+                Some(FetchTopologyCableListTerminationsTermination::PowerFeedType(feed)) => {
+                    Some((PortType::PowerFeed, feed.id.parse()?))
+                }
+                Some(FetchTopologyCableListTerminationsTermination::CircuitTerminationType(
+                    termination,
+                )) => Some((PortType::CircuitTermination, termination.id.parse()?)),
+                _ => None,
+            };
+            let Some((port_type, port_id)) = port_ref else {
+                debug!("Cable {cable_id}: ignore termination {}", termination.id);
+                continue;
+            };
+            let (data_ends, power_ends, circuit_ends) = match termination.cable_end {
+                DcimCableTerminationCableEndChoices::A => {
+                    (&mut a_ports, &mut a_power, &mut a_circuits)
+                }
+                DcimCableTerminationCableEndChoices::B => {
+                    (&mut b_ports, &mut b_power, &mut b_circuits)
+                }
+                DcimCableTerminationCableEndChoices::Other(end) => {
+                    topo_builder.append_issue(TopologyIssue::InvalidCable {
+                        cable_id,
+                        reason: format!("unknown cable end {end}"),
+                    });
+                    continue;
+                }
+            };
+            let port_map = match port_type {
+                PortType::Interface => &device_interface_map,
+                PortType::Front => &device_front_map,
+                PortType::Rear => &device_rear_map,
+                PortType::PowerPort => &device_power_port_map,
+                PortType::PowerOutlet => &device_power_outlet_map,
+                PortType::PowerFeed => {
+                    match power_feed_map.get(&port_id) {
+                        Some(feed_idx) => {
+                            power_ends.push(PowerEnd::Source(PowerSource::Feed(*feed_idx)))
+                        }
+                        None => topo_builder.append_issue(TopologyIssue::UnknownCableTermination {
+                            cable_id,
+                            port_id,
+                        }),
+                    }
+                    continue;
+                }
+                PortType::CircuitTermination => {
+                    match circuit_termination_map.get(&port_id) {
+                        Some(circuit_end) => circuit_ends.push(*circuit_end),
+                        None => topo_builder.append_issue(TopologyIssue::UnknownCableTermination {
+                            cable_id,
+                            port_id,
+                        }),
+                    }
+                    continue;
+                }
+            };
+            let Some((device_idx, port_idx)) = port_map.get(&port_id).copied() else {
+                topo_builder
+                    .append_issue(TopologyIssue::UnknownCableTermination { cable_id, port_id });
+                continue;
+            };
+            match port_type {
+                PortType::PowerPort => power_ends.push(PowerEnd::Port(device_idx, port_idx)),
+                PortType::PowerOutlet => power_ends.push(PowerEnd::Source(PowerSource::Outlet {
+                    device: device_idx,
+                    outlet: port_idx,
+                })),
+                _ => data_ends.push(PortIdx::new(device_idx, port_idx)),
+            }
+        }
+        let power_pairs = pair_cable_ends(&a_power, &b_power).unwrap_or_else(|| {
+            topo_builder.append_issue(TopologyIssue::InvalidCable {
+                cable_id,
+                reason: format!(
+                    "cannot pair {} with {} power terminations",
+                    a_power.len(),
+                    b_power.len()
+                ),
+            });
+            vec![]
+        });
+        for (a_end, b_end) in power_pairs {
+            match (a_end, b_end) {
+                (PowerEnd::Port(device, port), PowerEnd::Source(source))
+                | (PowerEnd::Source(source), PowerEnd::Port(device, port)) => {
+                    topo_builder.append_power_connection(device, port, source)
+                }
+                _ => topo_builder.append_issue(TopologyIssue::InvalidCable {
+                    cable_id,
+                    reason: "power cable has to connect a power port to an outlet or feed"
+                        .to_string(),
+                }),
+            }
+        }
+        let circuit_pairs = match (a_circuits.is_empty(), b_circuits.is_empty()) {
+            (true, true) => Some(vec![]),
+            (false, true) => pair_cable_ends(&a_circuits, &std::mem::take(&mut b_ports)),
+            (true, false) => pair_cable_ends(&b_circuits, &std::mem::take(&mut a_ports)),
+            (false, false) => None,
+        };
+        let Some(circuit_pairs) = circuit_pairs else {
+            topo_builder.append_issue(TopologyIssue::InvalidCable {
+                cable_id,
+                reason: "cannot pair circuit terminations with device ports".to_string(),
+            });
+            continue;
+        };
+        for ((circuit_idx, termination_idx), port) in circuit_pairs {
+            topo_builder.set_circuit_termination_port(circuit_idx, termination_idx, port);
+        }
+        let Some(pairs) = pair_cable_ends(&a_ports, &b_ports) else {
+            topo_builder.append_issue(TopologyIssue::InvalidCable {
+                cable_id,
+                reason: format!(
+                    "cannot pair {} with {} terminations",
+                    a_ports.len(),
+                    b_ports.len()
+                ),
+            });
+            continue;
+        };
+        for (a_port, b_port) in pairs {
+            let mut link_builder = LinkBuilder::new();
+            link_builder.set_cable_id(cable_id);
+            match link_builder.append_segment(
+                topo_builder.devices(),
+                a_port.device_idx(),
+                a_port.port_idx(),
+                b_port.device_idx(),
+                b_port.port_idx(),
+            ) {
+                Ok(_) => {
+                    topo_builder.append_link(link_builder.build());
+                }
+                Err(error) => topo_builder.append_issue(TopologyIssue::InvalidCable {
+                    cable_id,
+                    reason: error.to_string(),
+                }),
+            }
+        }
+    }
+    for wireless_link in netbox_topology.wireless_link_list.into_iter().flatten() {
+        let wireless_link_id = wireless_link.id.parse()?;
+        let mut ports = Vec::with_capacity(2);
+        for interface_id in [wireless_link.interface_a.id, wireless_link.interface_b.id] {
+            let port_id = interface_id.parse()?;
+            match device_interface_map.get(&port_id) {
+                Some((device_idx, port_idx)) => ports.push(PortIdx::new(*device_idx, *port_idx)),
+                None => topo_builder.append_issue(TopologyIssue::InvalidWirelessLink {
+                    wireless_link_id,
+                    reason: format!("interface {port_id} not found"),
+                }),
+            }
+        }
+        let [a_port, b_port] = ports[..] else {
+            continue;
+        };
+        let status = match wireless_link.status {
+            WirelessWirelessLinkStatusChoices::CONNECTED => WirelessLinkStatus::Connected,
+            WirelessWirelessLinkStatusChoices::PLANNED => WirelessLinkStatus::Planned,
+            WirelessWirelessLinkStatusChoices::DECOMMISSIONING => {
+                WirelessLinkStatus::Decommissioning
+            }
+            WirelessWirelessLinkStatusChoices::Other(status) => {
+                topo_builder.append_issue(TopologyIssue::InvalidWirelessLink {
+                    wireless_link_id,
+                    reason: format!("unknown status {status}"),
+                });
+                WirelessLinkStatus::Unknown
+            }
+        };
+        let mut link_builder = LinkBuilder::new();
+        link_builder.set_wireless(WirelessLink::new(
+            wireless_link_id,
+            wireless_link.ssid,
+            status,
+            wireless_link.description,
+        ));
+        match link_builder.append_segment(
+            topo_builder.devices(),
+            a_port.device_idx(),
+            a_port.port_idx(),
+            b_port.device_idx(),
+            b_port.port_idx(),
+        ) {
+            Ok(_) => {
+                topo_builder.append_link(link_builder.build());
+            }
+            Err(error) => topo_builder.append_issue(TopologyIssue::InvalidWirelessLink {
+                wireless_link_id,
+                reason: error.to_string(),
+            }),
+        }
+    }
     for site in netbox_topology.site_list.into_iter().flatten() {
         let id = site.id.parse()?;
         let name = site.name;
@@ -168,7 +733,103 @@ async fn do_fetch_topology() -> Result<Arc<Topology>, BackendError> {
             devices.shrink_to_fit();
             let location_idx = topo_builder.append_location(id, name, devices);
             topo_builder.set_site_of_location(location_idx, site_idx);
+            if let Some(parent) = location.parent {
+                topo_builder.set_parent_of_location(location_idx, parent.id.parse()?);
+            }
+            if let Some(fields) = location.tenant {
+                let tenant_id = tenant(&mut topo_builder, fields)?;
+                topo_builder.set_tenant_of_location(location_idx, tenant_id);
+            }
+        }
+        if let Some(region) = site.region {
+            topo_builder.set_region_of_site(site_idx, region.id.parse()?);
+        }
+        if let Some(group) = site.group {
+            topo_builder.set_group_of_site(site_idx, group.id.parse()?);
+        }
+        if let Some(fields) = site.tenant {
+            let tenant_id = tenant(&mut topo_builder, fields)?;
+            topo_builder.set_tenant_of_site(site_idx, tenant_id);
+        }
+        for vlan_group in site.vlan_groups.into_iter().flatten().flatten() {
+            topo_builder.append_vlan_group(
+                site_idx,
+                vlan_group.id.parse()?,
+                vlan_group.name,
+                vlan_group.slug,
+            );
+        }
+    }
+
+    for vlan in netbox_topology.vlan_list.into_iter().flatten() {
+        let mut vlan_builder = Vlan::builder(
+            vlan.id.parse()?,
+            vlan.vid.try_into().unwrap_or_default(),
+            vlan.name,
+        );
+        if let Some(group) = vlan.group {
+            vlan_builder.set_group(group.id.parse()?);
+        }
+        if let Some(site) = vlan.site {
+            vlan_builder.set_site(site.id.parse()?);
+        }
+        topo_builder.append_vlan(vlan_builder);
+    }
+
+    for region in netbox_topology.region_list.into_iter().flatten() {
+        let mut region_builder = Region::builder(region.id.parse()?, region.name, region.slug);
+        if let Some(parent) = region.parent {
+            region_builder.set_parent(parent.id.parse()?);
+        }
+        topo_builder.append_region(region_builder);
+    }
+    for site_group in netbox_topology.site_group_list.into_iter().flatten() {
+        let mut group_builder =
+            Region::builder(site_group.id.parse()?, site_group.name, site_group.slug);
+        if let Some(parent) = site_group.parent {
+            group_builder.set_parent(parent.id.parse()?);
+        }
+        topo_builder.append_site_group(group_builder);
+    }
+
+    for rack in netbox_topology.rack_list.into_iter().flatten() {
+        let mut rack_builder = Rack::builder(
+            rack.id.parse()?,
+            rack.name,
+            rack.u_height.try_into().unwrap_or_default(),
+        );
+        if let Some(location) = rack.location {
+            rack_builder.set_location(location.id.parse()?);
+        }
+        rack_builder.set_site(rack.site.id.parse()?);
+        topo_builder.append_rack(rack_builder);
+    }
+
+    for prefix in netbox_topology.prefix_list.into_iter().flatten() {
+        let net = match prefix.prefix.parse::<IpNet>() {
+            Ok(net) => net,
+            Err(error) => {
+                warn!(
+                    "Prefix {}: invalid prefix {}: {error}",
+                    prefix.id, prefix.prefix
+                );
+                continue;
+            }
+        };
+        let mut prefix_builder = Prefix::builder(prefix.id.parse()?, net);
+        if let Some(site) = prefix.site {
+            prefix_builder.set_site(site.id.parse()?);
+        }
+        if let Some(vrf) = prefix.vrf {
+            prefix_builder.set_vrf(vrf.name);
+        }
+        if let Some(vlan) = prefix.vlan {
+            prefix_builder.set_vlan(vlan.id.parse()?);
         }
+        if let Some(fields) = prefix.tenant {
+            prefix_builder.set_tenant(tenant(&mut topo_builder, fields)?);
+        }
+        topo_builder.append_prefix(prefix_builder);
     }
 
     topo_builder.build()
@@ -185,10 +846,16 @@ where
     debug!("Graphql Request {name}: {request_body:?}");
     let client = reqwest::Client::new();
     let config = config();
+    let endpoint = config
+        .netbox_endpoint()
+        .ok_or(BackendError::MissingConfig("NETBOX_ENDPOINT"))?;
+    let token = config
+        .netbox_token()
+        .ok_or(BackendError::MissingConfig("NETBOX_TOKEN"))?;
     let response: Response<Q::ResponseData> = client
-        .post(config.netbox_endpoint())
+        .post(endpoint)
         .json(&request_body)
-        .header(AUTHORIZATION, format!("Token {}", config.netbox_token()))
+        .header(AUTHORIZATION, format!("Token {token}"))
         .send()
         .await?
         .json()
@@ -205,3 +872,252 @@ where
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::{json, Value};
+
+    use crate::topology::graphql_operations::fetch_topology;
+    use crate::topology::model::circuit::CircuitStatus;
+    use crate::topology::model::device::DeviceCategory;
+    use crate::topology::model::link::WirelessLinkStatus;
+    use crate::topology::model::module::ModuleStatus;
+    use crate::topology::model::monitoring::{
+        Criticality, FIELD_CHECK_INTERVAL, FIELD_CRITICALITY, FIELD_ENABLED, FIELD_PING_TARGET,
+        TAG_DISABLED,
+    };
+    use crate::topology::model::validation::TopologyIssue;
+    use crate::topology::model::{PortIdx, Topology};
+    use crate::topology::query::{build_topology, monitoring_settings};
+
+    #[test]
+    fn test_read_monitoring_settings() {
+        let fields = json!({
+            FIELD_ENABLED: null,
+            FIELD_PING_TARGET: "2001:db8::1/64",
+            FIELD_CRITICALITY: "low",
+            FIELD_CHECK_INTERVAL: 0,
+        });
+        let (settings, errors) = monitoring_settings(Some(&fields), &[TAG_DISABLED]);
+        assert_eq!(Some(false), settings.enabled());
+        assert_eq!(Some("2001:db8::1".parse().unwrap()), settings.ping_target());
+        assert_eq!(Criticality::Low, settings.criticality());
+        assert_eq!(None, settings.check_interval());
+        assert_eq!(
+            vec![format!("{FIELD_CHECK_INTERVAL}: invalid interval 0")],
+            errors
+        );
+
+        // the custom field takes precedence over the tags
+        let fields = serde_json::json!({FIELD_ENABLED: true, FIELD_PING_TARGET: "ap01"});
+        let (settings, errors) = monitoring_settings(Some(&fields), &[TAG_DISABLED]);
+        assert_eq!(Some(true), settings.enabled());
+        assert_eq!(None, settings.ping_target());
+        assert_eq!(1, errors.len());
+
+        let (settings, errors) = monitoring_settings(None, &[]);
+        assert_eq!(None, settings.enabled());
+        assert_eq!(Criticality::default(), settings.criticality());
+        assert!(errors.is_empty());
+    }
+
+    /// netbox response with one device type and site, the given lists replace the empty defaults
+    fn response(lists: Value) -> fetch_topology::ResponseData {
+        let mut data = json!({
+            "device_type_list": [{"id": "1", "model": "generic", "tags": []}],
+            "device_list": [],
+            "site_list": [{"id": "2", "name": "Site", "physical_address": "", "region": null,
+                           "group": null, "locations": [], "vlan_groups": []}],
+            "vlan_list": [], "region_list": [], "site_group_list": [], "rack_list": [],
+            "power_feed_list": [], "circuit_list": [], "prefix_list": [], "cable_list": [],
+            "wireless_link_list": []
+        });
+        merge(&mut data, lists);
+        serde_json::from_value(data).unwrap()
+    }
+
+    /// router on the site of `response`, the given fields replace the defaults
+    fn device(fields: Value) -> Value {
+        let mut device = json!({
+            "tags": [], "custom_fields": {}, "device_type": {"id": "1"},
+            "device_role": {"name": "Router", "slug": "router"},
+            "interfaces": [], "frontports": [], "powerports": [], "poweroutlets": [],
+            "rack": null, "position": null, "location": null, "site": {"id": "2"},
+            "virtual_chassis": null, "vc_position": null, "vc_priority": null, "modules": []
+        });
+        merge(&mut device, fields);
+        device
+    }
+
+    /// interface without addresses and vlans
+    fn interface(id: &str, name: &str) -> Value {
+        json!({"id": id, "name": name, "ip_addresses": [], "mode": null,
+               "untagged_vlan": null, "tagged_vlans": []})
+    }
+
+    fn merge(target: &mut Value, fields: Value) {
+        if let (Some(target), Value::Object(fields)) = (target.as_object_mut(), fields) {
+            target.extend(fields);
+        }
+    }
+
+    fn build(data: fetch_topology::ResponseData) -> Arc<Topology> {
+        build_topology(data, &|role| role.parse::<DeviceCategory>().ok()).unwrap()
+    }
+
+    #[test]
+    fn test_pair_circuit_terminations_with_ports() {
+        let termination = |id: &str, side: &str| {
+            json!({"id": id, "term_side": side, "port_speed": null, "upstream_speed": null,
+                   "xconnect_id": "", "site": {"id": "2"}})
+        };
+        let cable = |id: &str, a: (&str, &str), b: (&str, &str)| {
+            json!({"id": id, "length": null, "length_unit": null, "terminations": [
+                {"id": format!("{id}1"), "cable_end": "A",
+                 "termination": {"__typename": a.0, "id": a.1}},
+                {"id": format!("{id}2"), "cable_end": "B",
+                 "termination": {"__typename": b.0, "id": b.1}}
+            ]})
+        };
+        let circuit_end = |id| ("CircuitTerminationType", id);
+        let interface_end = |id| ("InterfaceType", id);
+        let topology = build(response(json!({
+            "device_list": [device(json!({
+                "id": "10", "name": "rt01",
+                "interfaces": [interface("100", "sfp1"), interface("101", "sfp2")]
+            }))],
+            "circuit_list": [{
+                "id": "80", "cid": "FTTH-4711", "status": "EXPIRED", "commit_rate": null,
+                "description": "", "provider": {"id": "9", "name": "Init7", "account": ""},
+                "type": {"name": "Internet"},
+                "terminations": [termination("81", "A"), termination("82", "Z")]
+            }],
+            "cable_list": [
+                cable("20", circuit_end("81"), interface_end("100")),
+                cable("21", interface_end("101"), circuit_end("82")),
+                cable("22", circuit_end("81"), circuit_end("82"))
+            ]
+        })));
+
+        let rt01_idx = topology.get_device_idx(10).unwrap();
+        let circuit = topology.get_circuit(0).unwrap();
+        assert_eq!(CircuitStatus::Unknown, circuit.status());
+        assert!(topology.issues().contains(&TopologyIssue::InvalidCircuit {
+            circuit_id: 80,
+            reason: "unknown status EXPIRED".to_string()
+        }));
+        for (termination_idx, port_idx) in [(0, 0), (1, 1)] {
+            let port = PortIdx::new(rt01_idx, port_idx);
+            assert_eq!(Some(port), circuit.terminations()[termination_idx].port());
+            assert_eq!(vec![0], topology.circuits_of_port(port));
+            // the device port is taken by the circuit and does not become a link
+            assert!(topology.get_links_of_port(&port).is_empty());
+        }
+        assert_eq!(
+            vec![&TopologyIssue::InvalidCable {
+                cable_id: 22,
+                reason: "cannot pair circuit terminations with device ports".to_string()
+            }],
+            topology
+                .issues()
+                .iter()
+                .filter(|i| i.cable_id().is_some())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_keep_wireless_link_with_unknown_status() {
+        let topology = build(response(json!({
+            "device_list": [
+                device(json!({"id": "10", "name": "ap01",
+                              "interfaces": [interface("100", "wlan60-1")]})),
+                device(json!({"id": "11", "name": "sta01",
+                              "interfaces": [interface("110", "wlan60-1")]}))
+            ],
+            "wireless_link_list": [{
+                "id": "50", "ssid": "bridge-01", "status": "MAINTENANCE", "description": "",
+                "interface_a": {"id": "100"}, "interface_b": {"id": "110"}
+            }, {
+                "id": "51", "ssid": "bridge-02", "status": "CONNECTED", "description": "",
+                "interface_a": {"id": "100"}, "interface_b": {"id": "999"}
+            }]
+        })));
+
+        let ap01 = PortIdx::new(topology.get_device_idx(10).unwrap(), 0);
+        let sta01 = PortIdx::new(topology.get_device_idx(11).unwrap(), 0);
+        let link = topology.get_links_of_port(&ap01).remove(0);
+        assert_eq!(Some(sta01), link.peer_of(&ap01));
+        let wireless = link.wireless().unwrap();
+        assert_eq!(50, wireless.id());
+        assert_eq!(WirelessLinkStatus::Unknown, wireless.status());
+        assert!(topology
+            .issues()
+            .contains(&TopologyIssue::InvalidWirelessLink {
+                wireless_link_id: 50,
+                reason: "unknown status MAINTENANCE".to_string()
+            }));
+        // a link with a missing end is dropped
+        assert!(topology
+            .issues()
+            .contains(&TopologyIssue::InvalidWirelessLink {
+                wireless_link_id: 51,
+                reason: "interface 999 not found".to_string()
+            }));
+    }
+
+    #[test]
+    fn test_read_virtual_chassis_and_modules() {
+        let chassis =
+            json!({"id": "5", "name": "stack01", "domain": "core", "master": {"id": "11"}});
+        let module = |id: &str, asset_tag: &str, status: &str| {
+            json!({"id": id, "serial": "", "asset_tag": asset_tag, "status": status,
+                   "module_bay": {"id": format!("{id}0"), "name": "sfp1", "position": "1"},
+                   "module_type": {"model": "S+RJ10", "part_number": "",
+                                   "manufacturer": {"name": "MikroTik"}}})
+        };
+        let topology = build(response(json!({
+            "device_list": [
+                device(json!({"id": "10", "name": "sw01", "virtual_chassis": chassis,
+                              "vc_position": 2, "vc_priority": 100,
+                              "modules": [module("70", "", "ACTIVE")]})),
+                device(json!({"id": "11", "name": "sw02", "virtual_chassis": chassis,
+                              "vc_position": 1, "vc_priority": 200,
+                              "modules": [module("71", "inv-1", "RETIRED")]}))
+            ]
+        })));
+
+        let sw01 = topology.get_device_idx(10).unwrap();
+        let sw02 = topology.get_device_idx(11).unwrap();
+        let chassis = topology.list_virtual_chassis_map(|c| Some(c.clone()));
+        assert_eq!(1, chassis.len());
+        let chassis = &chassis[0];
+        assert_eq!(5, chassis.id());
+        assert_eq!(Some(sw02), chassis.master());
+        // members are ordered by their position
+        assert_eq!(&vec![sw02, sw01], chassis.members());
+        let membership = topology
+            .get_device(sw01)
+            .unwrap()
+            .virtual_chassis()
+            .unwrap();
+        assert_eq!(Some(2), membership.position());
+        assert_eq!(Some(100), membership.priority());
+
+        let sw01_device = topology.get_device(sw01).unwrap();
+        let active = &sw01_device.modules()[0];
+        assert_eq!(ModuleStatus::Active, active.status());
+        assert_eq!(None, active.asset_tag());
+        let sw02_device = topology.get_device(sw02).unwrap();
+        let retired = &sw02_device.modules()[0];
+        assert_eq!(ModuleStatus::Unknown, retired.status());
+        assert_eq!(Some("inv-1"), retired.asset_tag());
+        assert!(topology.issues().contains(&TopologyIssue::InvalidModule {
+            device: sw02,
+            module_id: 71,
+            reason: "unknown status RETIRED".to_string()
+        }));
+    }
+}
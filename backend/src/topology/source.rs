@@ -0,0 +1,487 @@
+use std::path::{Path, PathBuf};
+
+use async_graphql::futures_util::stream::{self, StreamExt, TryStreamExt};
+use clap::ValueEnum;
+use log::{debug, info};
+
+use crate::config::config;
+use crate::error::BackendError;
+use crate::topology::graphql_operations::{
+    fetch_object_ids, fetch_topology, FetchObjectIds, FetchTopology,
+};
+use crate::topology::query::query_netbox;
+
+/// Kind of source the topology is read from
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum TopologySourceType {
+    /// query a live netbox server
+    Netbox,
+    /// read a snapshot file written by `dump-topology`
+    File,
+}
+
+/// Origin of the raw inventory data the topology is built from
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum TopologySource {
+    Netbox,
+    File(PathBuf),
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum SnapshotFormat {
+    Json,
+    Yaml,
+}
+
+impl SnapshotFormat {
+    fn from_path(path: &Path) -> Result<Self, BackendError> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Ok(SnapshotFormat::Json),
+            Some("yaml") | Some("yml") => Ok(SnapshotFormat::Yaml),
+            _ => Err(BackendError::UnsupportedSnapshotFormat(
+                path.display().to_string(),
+            )),
+        }
+    }
+}
+
+impl TopologySource {
+    pub fn from_config() -> Result<Self, BackendError> {
+        let config = config();
+        match config.topology_source() {
+            TopologySourceType::Netbox => Ok(TopologySource::Netbox),
+            TopologySourceType::File => config
+                .topology_file()
+                .map(|file| TopologySource::File(file.to_path_buf()))
+                .ok_or(BackendError::MissingConfig("TOPOLOGY_FILE")),
+        }
+    }
+
+    pub async fn fetch(&self) -> Result<fetch_topology::ResponseData, BackendError> {
+        match self {
+            TopologySource::Netbox => {
+                let config = config();
+                fetch_netbox(config.netbox_page_size(), config.netbox_concurrency()).await
+            }
+            TopologySource::File(path) => read_snapshot(path).await,
+        }
+    }
+}
+
+/// list the ids of all objects, then fetch the objects in pages of at most `page_size` ids
+///
+/// netbox offers no pagination of the lists, so the ids are at least listed in one request per object type
+async fn fetch_netbox(
+    page_size: usize,
+    concurrency: usize,
+) -> Result<fetch_topology::ResponseData, BackendError> {
+    let id_lists: Vec<_> = stream::iter(id_requests())
+        .map(query_netbox::<FetchObjectIds>)
+        .buffered(concurrency)
+        .try_collect()
+        .await?;
+    let requests: Vec<_> = id_lists
+        .into_iter()
+        .flat_map(|ids| page_requests(ids, page_size))
+        .collect();
+    debug!("Fetch topology in {} requests", requests.len());
+    // keep the pages in order, so the topology does not depend on the response timing
+    let pages: Vec<_> = stream::iter(requests)
+        .map(query_netbox::<FetchTopology>)
+        .buffered(concurrency)
+        .try_collect()
+        .await?;
+    let mut data: fetch_topology::ResponseData = serde_json::from_str("{}")?;
+    for page in pages {
+        merge_page(&mut data, page);
+    }
+    Ok(data)
+}
+
+/// one id request per object type
+fn id_requests() -> Vec<fetch_object_ids::Variables> {
+    macro_rules! per_type {
+        ($($flag:ident),*) => {
+            vec![$(fetch_object_ids::Variables {
+                $flag: true,
+                ..Default::default()
+            }),*]
+        };
+    }
+    per_type!(
+        device_types,
+        devices,
+        sites,
+        vlans,
+        regions,
+        site_groups,
+        racks,
+        power_feeds,
+        circuits,
+        wireless_links,
+        prefixes,
+        cables
+    )
+}
+
+/// one request per page and object type, ids of other types are not requested
+fn page_requests(
+    ids: fetch_object_ids::ResponseData,
+    page_size: usize,
+) -> Vec<fetch_topology::Variables> {
+    let mut requests = Vec::new();
+    macro_rules! paginate {
+        ($($list:ident => $flag:ident, $ids:ident;)*) => {$(
+            let list_ids: Vec<_> = ids.$list.into_iter().flatten().map(|o| Some(o.id)).collect();
+            for page in list_ids.chunks(page_size) {
+                requests.push(fetch_topology::Variables {
+                    $flag: true,
+                    $ids: Some(page.to_vec()),
+                    ..Default::default()
+                });
+            }
+        )*};
+    }
+    paginate! {
+        device_type_list => device_types, device_type_ids;
+        device_list => devices, device_ids;
+        site_list => sites, site_ids;
+        vlan_list => vlans, vlan_ids;
+        region_list => regions, region_ids;
+        site_group_list => site_groups, site_group_ids;
+        rack_list => racks, rack_ids;
+        power_feed_list => power_feeds, power_feed_ids;
+        circuit_list => circuits, circuit_ids;
+        wireless_link_list => wireless_links, wireless_link_ids;
+        prefix_list => prefixes, prefix_ids;
+        cable_list => cables, cable_ids;
+    }
+    requests
+}
+
+fn merge_page(data: &mut fetch_topology::ResponseData, page: fetch_topology::ResponseData) {
+    macro_rules! extend {
+        ($($list:ident),*) => {$(
+            if let Some(items) = page.$list {
+                data.$list.get_or_insert_with(Vec::new).extend(items);
+            }
+        )*};
+    }
+    extend!(
+        device_type_list,
+        device_list,
+        site_list,
+        vlan_list,
+        region_list,
+        site_group_list,
+        rack_list,
+        power_feed_list,
+        circuit_list,
+        wireless_link_list,
+        prefix_list,
+        cable_list
+    );
+}
+
+async fn read_snapshot(path: &Path) -> Result<fetch_topology::ResponseData, BackendError> {
+    let format = SnapshotFormat::from_path(path)?;
+    let content = tokio::fs::read(path).await?;
+    Ok(match format {
+        SnapshotFormat::Json => serde_json::from_slice(&content)?,
+        SnapshotFormat::Yaml => serde_yaml::from_slice(&content)?,
+    })
+}
+
+async fn write_snapshot(
+    path: &Path,
+    data: &fetch_topology::ResponseData,
+) -> Result<(), BackendError> {
+    let format = SnapshotFormat::from_path(path)?;
+    let content = match format {
+        SnapshotFormat::Json => serde_json::to_vec_pretty(data)?,
+        SnapshotFormat::Yaml => serde_yaml::to_string(data)?.into_bytes(),
+    };
+    tokio::fs::write(path, content).await?;
+    Ok(())
+}
+
+/// fetch the current inventory from netbox and store it as snapshot file
+pub async fn dump_topology(path: &Path) -> Result<(), BackendError> {
+    let data = TopologySource::Netbox.fetch().await?;
+    write_snapshot(path, &data).await?;
+    info!("Topology written to {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::topology::graphql_operations::{fetch_object_ids, fetch_topology};
+    use crate::topology::model::device::DeviceCategory;
+    use crate::topology::model::module::ModuleStatus;
+    use crate::topology::model::monitoring::Criticality;
+    use crate::topology::model::power::PowerSource;
+    use crate::topology::model::validation::TopologyIssue;
+    use crate::topology::model::PortIdx;
+    use crate::topology::query::build_topology;
+    use crate::topology::source::{id_requests, merge_page, page_requests};
+
+    const SNAPSHOT: &str = r#"{
+        "device_type_list": [{"id": "1", "model": "CRS326", "tags": [{"slug": "routeros"}]}],
+        "device_list": [{
+            "id": "10", "name": "sw01", "tags": [],
+            "tenant": {"id": "90", "name": "Coop Nord", "slug": "coop-nord", "group": {"name": "Cooperatives"}},
+            "custom_fields": {"monitoring_ping_target": "192.0.2.1/32", "monitoring_criticality": "critical",
+                              "monitoring_interval": 30},
+            "device_type": {"id": "1"},
+            "device_role": {"name": "Switch", "slug": "switch"},
+            "interfaces": [
+                {"id": "100", "name": "loopback", "ip_addresses": [{"address": "172.16.0.1/32", "role": "LOOPBACK"}],
+                 "mode": null, "untagged_vlan": null, "tagged_vlans": []},
+                {"id": "101", "name": "ether1", "ip_addresses": [],
+                 "mode": "TAGGED", "untagged_vlan": null, "tagged_vlans": [{"id": "30"}]},
+                {"id": "102", "name": "wlan1", "ip_addresses": [],
+                 "mode": null, "untagged_vlan": null, "tagged_vlans": []},
+                {"id": "103", "name": "ether24", "ip_addresses": [],
+                 "mode": null, "untagged_vlan": null, "tagged_vlans": []}
+            ],
+            "frontports": [],
+            "powerports": [{"id": "60", "name": "PSU1"}], "poweroutlets": [],
+            "rack": {"id": "5"}, "position": "12.0",
+            "location": {"id": "3"}, "site": {"id": "2"},
+            "virtual_chassis": {"id": "40", "name": "stack01", "domain": "", "master": {"id": "10"}},
+            "vc_position": 1, "vc_priority": 255,
+            "modules": [{"id": "50", "serial": "S123", "asset_tag": null, "status": "ACTIVE",
+                         "module_bay": {"id": "51", "name": "SFP+ 1", "position": "1"},
+                         "module_type": {"model": "S+85DLC03D", "part_number": "", "manufacturer": {"name": "MikroTik"}}}]
+        }, {
+            "id": "11", "name": "sw02", "tags": [{"slug": "no-monitoring"}],
+            "custom_fields": {"monitoring": null, "monitoring_criticality": "urgent"},
+            "device_type": {"id": "1"},
+            "device_role": {"name": "Switch", "slug": "switch"},
+            "interfaces": [{"id": "110", "name": "ether1", "ip_addresses": [],
+                            "mode": "ACCESS", "untagged_vlan": {"id": "30"}, "tagged_vlans": []},
+                           {"id": "111", "name": "wlan1", "ip_addresses": [],
+                            "mode": null, "untagged_vlan": null, "tagged_vlans": []}],
+            "frontports": [],
+            "powerports": [{"id": "61", "name": "PSU1"}],
+            "poweroutlets": [{"id": "62", "name": "Outlet 1", "power_port": {"id": "61"}}],
+            "rack": null, "position": null,
+            "location": {"id": "3"}, "site": {"id": "2"},
+            "virtual_chassis": {"id": "40", "name": "stack01", "domain": "", "master": {"id": "10"}},
+            "vc_position": 2, "vc_priority": null,
+            "modules": []
+        }],
+        "site_list": [{"id": "2", "name": "Site", "physical_address": "", "region": {"id": "7"}, "group": null,
+                       "locations": [{"id": "3", "name": "Cellar", "parent": null},
+                                     {"id": "8", "name": "Room 1", "parent": {"id": "3"},
+                                      "tenant": {"id": "90", "name": "Coop Nord", "slug": "coop-nord", "group": null}}],
+                       "vlan_groups": [{"id": "4", "name": "Office", "slug": "office"}]}],
+        "vlan_list": [{"id": "30", "vid": 100, "name": "Users", "group": {"id": "4"}, "site": {"id": "2"}}],
+        "region_list": [{"id": "6", "name": "Switzerland", "slug": "ch", "parent": null},
+                        {"id": "7", "name": "Zurich", "slug": "zh", "parent": {"id": "6"}}],
+        "site_group_list": [],
+        "rack_list": [{"id": "5", "name": "R1", "u_height": 42, "location": {"id": "3"}, "site": {"id": "2"}}],
+        "power_feed_list": [{
+            "id": "70", "name": "Feed A", "status": "ACTIVE",
+            "power_panel": {"name": "Panel 1", "site": {"id": "2"}}, "rack": {"id": "5"}
+        }],
+        "circuit_list": [{
+            "id": "80", "cid": "FTTH-4711", "status": "ACTIVE", "commit_rate": 1000000, "description": "",
+            "provider": {"id": "9", "name": "Init7", "account": "C-123"}, "type": {"name": "Internet"},
+            "terminations": [{"id": "81", "term_side": "A", "port_speed": null, "upstream_speed": null,
+                              "xconnect_id": "", "site": {"id": "2"}}]
+        }],
+        "wireless_link_list": [{
+            "id": "50", "ssid": "bridge-01", "status": "CONNECTED", "description": "",
+            "interface_a": {"id": "102"}, "interface_b": {"id": "111"}
+        }],
+        "prefix_list": [
+            {"id": "40", "prefix": "172.16.0.0/16", "site": null, "vrf": null, "vlan": null},
+            {"id": "41", "prefix": "172.16.0.0/24", "site": {"id": "2"}, "vrf": null, "vlan": {"id": "30"}}
+        ],
+        "cable_list": [{
+            "id": "20", "length": null, "length_unit": null,
+            "terminations": [
+                {"id": "1", "cable_end": "A", "termination": {"__typename": "InterfaceType", "id": "101"}},
+                {"id": "2", "cable_end": "B", "termination": {"__typename": "InterfaceType", "id": "110"}}
+            ]
+        }, {
+            "id": "21", "length": null, "length_unit": null,
+            "terminations": [
+                {"id": "3", "cable_end": "A", "termination": {"__typename": "PowerPortType", "id": "60"}},
+                {"id": "4", "cable_end": "B", "termination": {"__typename": "PowerOutletType", "id": "62"}}
+            ]
+        }, {
+            "id": "22", "length": null, "length_unit": null,
+            "terminations": [
+                {"id": "5", "cable_end": "A", "termination": {"__typename": "PowerFeedType", "id": "70"}},
+                {"id": "6", "cable_end": "B", "termination": {"__typename": "PowerPortType", "id": "61"}}
+            ]
+        }, {
+            "id": "23", "length": null, "length_unit": null,
+            "terminations": [
+                {"id": "7", "cable_end": "A", "termination": {"__typename": "CircuitTerminationType", "id": "81"}},
+                {"id": "8", "cable_end": "B", "termination": {"__typename": "InterfaceType", "id": "103"}}
+            ]
+        }]
+    }"#;
+
+    #[test]
+    fn test_build_from_snapshot() {
+        let data: fetch_topology::ResponseData = serde_json::from_str(SNAPSHOT).unwrap();
+        // the snapshot has to survive a round trip through the writer
+        let data: fetch_topology::ResponseData =
+            serde_yaml::from_str(&serde_yaml::to_string(&data).unwrap()).unwrap();
+        let topology = build_topology(data, &|role| role.parse::<DeviceCategory>().ok()).unwrap();
+
+        let sw01 = topology.get_device_by_id(10).unwrap();
+        assert_eq!(DeviceCategory::Switch, sw01.category());
+        assert_eq!(
+            Some("172.16.0.1".parse().unwrap()),
+            sw01.get_loopback_address()
+        );
+        let rack = topology.get_rack_by_id(5).unwrap();
+        assert_eq!(Some(12.0), rack.devices()[0].position().map(|p| p.units()));
+
+        let sw01_idx = topology.get_device_idx(10).unwrap();
+        let sw02_idx = topology.get_device_idx(11).unwrap();
+        let link = topology
+            .get_links_of_port(&PortIdx::new(sw01_idx, 1))
+            .remove(0);
+        assert_eq!(Some(20), link.cable_id());
+        assert_eq!(
+            Some(PortIdx::new(sw02_idx, 0)),
+            link.peer_of(&PortIdx::new(sw01_idx, 1))
+        );
+
+        assert_eq!(
+            vec![
+                PowerSource::Outlet {
+                    device: sw02_idx,
+                    outlet: 0
+                },
+                PowerSource::Feed(0)
+            ],
+            topology.powered_by(sw01_idx)
+        );
+        assert_eq!(vec![sw01_idx], topology.powers(sw02_idx));
+        assert!(topology.issues().iter().all(|i| i.cable_id().is_none()));
+
+        assert!(sw01.can_ping());
+        assert_eq!(Some("192.0.2.1".parse().unwrap()), sw01.ping_target());
+        assert_eq!(Criticality::Critical, sw01.monitoring().criticality());
+        assert_eq!(
+            Some(Duration::from_secs(30)),
+            sw01.monitoring().check_interval()
+        );
+        let sw02 = topology.get_device(sw02_idx).unwrap();
+        assert!(!sw02.can_ping());
+        let chassis = topology
+            .get_virtual_chassis(sw01.virtual_chassis().unwrap().chassis())
+            .unwrap();
+        assert_eq!(Some(sw01_idx), chassis.master());
+        assert_eq!(&vec![sw01_idx, sw02_idx], chassis.members());
+        assert_eq!(Some(2), sw02.virtual_chassis().and_then(|m| m.position()));
+        assert_eq!("SFP+ 1", sw01.modules()[0].bay().name());
+        assert_eq!(ModuleStatus::Active, sw01.modules()[0].status());
+        let tenant_of = |device| topology.tenant_of_device(device);
+        assert!(topology.tenant_matches(tenant_of(&sw01), Some("coop-nord")));
+        assert!(!topology.tenant_matches(tenant_of(&sw02), Some("coop-nord")));
+        assert!(topology.tenant_matches(tenant_of(&sw02), None));
+        let tenants = topology.list_tenants();
+        assert_eq!(1, tenants.len());
+        assert_eq!(Some("Cooperatives"), tenants[0].group());
+        assert!(topology
+            .issues()
+            .contains(&TopologyIssue::InvalidMonitoringSetting {
+                device: sw02_idx,
+                reason: "monitoring_criticality: Unknown criticality: urgent".to_string()
+            }));
+
+        let radio = topology
+            .get_links_of_port(&PortIdx::new(sw01_idx, 2))
+            .remove(0);
+        assert_eq!(None, radio.cable_id());
+        assert_eq!(Some("bridge-01"), radio.wireless().map(|w| w.ssid()));
+        assert_eq!(
+            Some(PortIdx::new(sw02_idx, 1)),
+            topology.trace_paths(PortIdx::new(sw01_idx, 2))[0].far_end()
+        );
+
+        let vlan = topology.get_vlan_by_id(30).unwrap();
+        assert_eq!(100, vlan.vid());
+        let group = topology.get_vlan_group(vlan.group().unwrap()).unwrap();
+        assert_eq!("office", group.slug());
+        let vlan_idx = topology.get_vlan_idx(30).unwrap();
+        assert_eq!(
+            &[PortIdx::new(sw01_idx, 1), PortIdx::new(sw02_idx, 0)],
+            topology.ports_of_vlan(vlan_idx)
+        );
+
+        let cellar = topology.get_location_by_id(3).unwrap();
+        let room = topology.get_location(cellar.children()[0]).unwrap();
+        assert_eq!(8, room.id());
+        assert_eq!(topology.get_location_idx(3), room.parent());
+        let site = topology.get_site_by_id(2).unwrap();
+        let region = topology.get_region(site.region().unwrap()).unwrap();
+        assert_eq!("zh", region.slug());
+        let parent = topology.get_region(region.parent().unwrap()).unwrap();
+        assert_eq!(&vec![site.region().unwrap()], parent.children());
+
+        let lookup = topology.lookup_address("172.16.0.1".parse().unwrap(), None);
+        let prefix = topology.get_prefix(lookup.prefix().unwrap()).unwrap();
+        assert_eq!(41, prefix.id());
+        assert_eq!(&vec![PortIdx::new(sw01_idx, 0)], lookup.ports());
+        assert_eq!(
+            Some(2),
+            lookup
+                .site()
+                .and_then(|s| topology.get_site(s))
+                .map(|s| s.id())
+        );
+        let lookup = topology.lookup_address("172.16.1.1".parse().unwrap(), None);
+        assert_eq!(
+            Some(40),
+            lookup
+                .prefix()
+                .and_then(|p| topology.get_prefix(p))
+                .map(|p| p.id())
+        );
+        assert!(lookup.ports().is_empty());
+        assert_eq!(None, lookup.site());
+
+        let site_idx = topology.get_site_idx(2).unwrap();
+        let uplink = PortIdx::new(sw01_idx, 3);
+        assert_eq!(vec![0], topology.circuits_of_site(site_idx));
+        assert_eq!(vec![0], topology.circuits_of_port(uplink));
+        let circuit = topology.get_circuit(0).unwrap();
+        assert_eq!("Init7", circuit.provider().name());
+        assert_eq!(Some(1000000), circuit.commit_rate());
+    }
+
+    #[test]
+    fn test_fetch_in_pages() {
+        let id_requests = id_requests();
+        assert_eq!(12, id_requests.len());
+        assert!(id_requests[1].devices && !id_requests[1].device_types);
+
+        let ids: fetch_object_ids::ResponseData = serde_json::from_str(
+            r#"{"device_list": [{"id": "10"}, {"id": "11"}, {"id": "12"}], "site_list": [{"id": "2"}]}"#,
+        )
+        .unwrap();
+        let requests = page_requests(ids, 2);
+        assert_eq!(3, requests.len());
+        assert!(requests[0].devices && !requests[0].sites);
+        assert_eq!(Some(vec![Some("12".to_string())]), requests[1].device_ids);
+        assert!(requests[2].sites && requests[2].device_ids.is_none());
+
+        let mut data: fetch_topology::ResponseData = serde_json::from_str("{}").unwrap();
+        for _ in 0..2 {
+            merge_page(&mut data, serde_json::from_str(SNAPSHOT).unwrap());
+        }
+        assert_eq!(4, data.device_list.map(|d| d.len()).unwrap_or_default());
+        assert!(data.region_list.is_some());
+    }
+}
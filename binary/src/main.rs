@@ -14,19 +14,26 @@ use async_graphql::futures_util::future::join_all;
 use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse};
 use biscuit::ValidationOptions;
 use env_logger::Env;
-use log::error;
+use log::{error, info};
 use prometheus::{histogram_opts, HistogramVec};
 use static_files::Resource;
 
 use backend::{
     api::{create_schema, GraphqlSchema},
-    config::config,
+    config::{config, Command},
     context::UserInfo,
+    routeros::traffic::spawn_traffic_poller,
+    topology::cache::{request_topology_refresh, spawn_topology_refresher},
+    topology::source::dump_topology,
 };
 
 use crate::error::{BinaryError, Result};
+use crate::metrics::{TopologyCollector, TrafficCollector};
+use crate::webhook::{netbox_webhook, NetboxWebhook};
 
 mod error;
+mod metrics;
+mod webhook;
 
 include!(concat!(env!("OUT_DIR"), "/generated.rs"));
 
@@ -71,12 +78,21 @@ struct ApplicationContext {
 #[actix_web::main]
 async fn main() -> ExitCode {
     env_logger::init_from_env(Env::default().filter_or("LOG_LEVEL", "info"));
-    if let Err(error) = run_server().await {
-        error!("Error running server: {error}");
-        ExitCode::FAILURE
-    } else {
-        ExitCode::SUCCESS
+    match config().command() {
+        Some(Command::DumpTopology { file }) => {
+            if let Err(error) = dump_topology(file).await {
+                error!("Error dumping topology: {error}");
+                return ExitCode::FAILURE;
+            }
+        }
+        None => {
+            if let Err(error) = run_server().await {
+                error!("Error running server: {error}");
+                return ExitCode::FAILURE;
+            }
+        }
     }
+    ExitCode::SUCCESS
 }
 
 async fn run_server() -> Result<()> {
@@ -100,12 +116,17 @@ async fn run_server() -> Result<()> {
 
     let registry = prometheus.registry.clone();
     registry.register(Box::new(graphql_request_histogram.clone()))?;
+    registry.register(Box::new(TopologyCollector::new()?))?;
+    registry.register(Box::new(TrafficCollector::new()?))?;
+
+    spawn_topology_refresher();
+    spawn_traffic_poller();
 
     let schema = create_schema();
 
     let validation_options = ValidationOptions::default();
 
-    let issuer = config.auth_issuer();
+    let issuer = config.auth_issuer()?;
     let created_validator = OIDCValidator::new_from_issuer(issuer.to_owned(), validation_options)
         .await
         .map_err(|e| BinaryError::oidc_validation_error(issuer.to_string(), e))?;
@@ -119,6 +140,16 @@ async fn run_server() -> Result<()> {
         graphql_request_histogram,
         schema,
     });
+    // the endpoint is reachable on the public port, so unsigned webhooks are never accepted
+    let webhook = config.netbox_webhook_secret().map(|secret| {
+        Data::new(NetboxWebhook::new(
+            secret.to_string(),
+            request_topology_refresh,
+        ))
+    });
+    if webhook.is_none() {
+        info!("NETBOX_WEBHOOK_SECRET not set, netbox webhooks are disabled");
+    }
     let main_server = HttpServer::new(move || {
         let resources: HashMap<&str, Resource> = generate();
 
@@ -130,6 +161,12 @@ async fn run_server() -> Result<()> {
             .service(resource("/graphql").guard(Post()).to(graphql))
             // workaround for proxy troubles
             .service(resource("/graphql/").guard(Post()).to(graphql))
+            .configure(|cfg| {
+                if let Some(webhook) = &webhook {
+                    cfg.app_data(webhook.clone())
+                        .service(resource("/webhook/netbox").guard(Post()).to(netbox_webhook));
+                }
+            })
             .service(ResourceFiles::new("/", resources).resolve_not_found_to_root())
     })
     .bind((bind_addr, api_port))?
@@ -0,0 +1,164 @@
+use prometheus::core::{Collector, Desc};
+use prometheus::proto::MetricFamily;
+use prometheus::{Gauge, IntCounter, IntCounterVec, IntGauge, Opts};
+
+use backend::routeros::traffic::list_interface_traffic;
+use backend::topology::cache::topology_cache_status;
+
+/// exports the state of the topology cache at scrape time
+pub struct TopologyCollector {
+    age: Gauge,
+    failing: IntGauge,
+    failed_fetches: IntCounter,
+}
+
+impl TopologyCollector {
+    pub fn new() -> prometheus::Result<Self> {
+        Ok(TopologyCollector {
+            age: Gauge::with_opts(Opts::new(
+                "topology_age_seconds",
+                "Seconds since the served topology was fetched",
+            ))?,
+            failing: IntGauge::with_opts(Opts::new(
+                "topology_fetch_failing",
+                "1 if the last topology fetch failed",
+            ))?,
+            failed_fetches: IntCounter::with_opts(Opts::new(
+                "topology_fetch_errors_total",
+                "Count of failed topology fetches",
+            ))?,
+        })
+    }
+}
+
+impl Collector for TopologyCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        self.age
+            .desc()
+            .into_iter()
+            .chain(self.failing.desc())
+            .chain(self.failed_fetches.desc())
+            .collect()
+    }
+
+    fn collect(&self) -> Vec<MetricFamily> {
+        let status = topology_cache_status();
+        self.age
+            .set(status.age().map(|a| a.as_secs_f64()).unwrap_or(f64::NAN));
+        self.failing
+            .set(if status.last_error().is_some() { 1 } else { 0 });
+        self.failed_fetches.inc_by(
+            status
+                .failed_fetches()
+                .saturating_sub(self.failed_fetches.get()),
+        );
+        self.age
+            .collect()
+            .into_iter()
+            .chain(self.failing.collect())
+            .chain(self.failed_fetches.collect())
+            .collect()
+    }
+}
+
+/// exports the last polled interface counters of the RouterOS devices
+pub struct TrafficCollector {
+    bytes: IntCounterVec,
+    packets: IntCounterVec,
+    errors: IntCounterVec,
+    drops: IntCounterVec,
+}
+
+impl TrafficCollector {
+    pub fn new() -> prometheus::Result<Self> {
+        let labels = &["device_id", "device", "interface", "direction"];
+        Ok(TrafficCollector {
+            bytes: IntCounterVec::new(
+                Opts::new(
+                    "routeros_interface_bytes_total",
+                    "Bytes received (rx) and sent (tx) by the interface",
+                ),
+                labels,
+            )?,
+            packets: IntCounterVec::new(
+                Opts::new(
+                    "routeros_interface_packets_total",
+                    "Packets received (rx) and sent (tx) by the interface",
+                ),
+                labels,
+            )?,
+            errors: IntCounterVec::new(
+                Opts::new(
+                    "routeros_interface_errors_total",
+                    "Receive (rx) and transmit (tx) errors of the interface",
+                ),
+                labels,
+            )?,
+            drops: IntCounterVec::new(
+                Opts::new(
+                    "routeros_interface_drops_total",
+                    "Packets dropped on receive (rx) and transmit (tx) by the interface",
+                ),
+                labels,
+            )?,
+        })
+    }
+}
+
+impl Collector for TrafficCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        self.bytes
+            .desc()
+            .into_iter()
+            .chain(self.packets.desc())
+            .chain(self.errors.desc())
+            .chain(self.drops.desc())
+            .collect()
+    }
+
+    fn collect(&self) -> Vec<MetricFamily> {
+        // rebuilt on every scrape, the device counters restart on reboots
+        self.bytes.reset();
+        self.packets.reset();
+        self.errors.reset();
+        self.drops.reset();
+        for (device_id, device, interface, traffic) in list_interface_traffic() {
+            let device_id = device_id.to_string();
+            let counters = traffic.counters();
+            for (direction, bytes, packets, errors, drops) in [
+                (
+                    "rx",
+                    counters.rx_bytes,
+                    counters.rx_packets,
+                    counters.rx_errors,
+                    counters.rx_drops,
+                ),
+                (
+                    "tx",
+                    counters.tx_bytes,
+                    counters.tx_packets,
+                    counters.tx_errors,
+                    counters.tx_drops,
+                ),
+            ] {
+                let labels = [
+                    device_id.as_str(),
+                    device.as_str(),
+                    interface.as_str(),
+                    direction,
+                ];
+                self.bytes.with_label_values(&labels).inc_by(bytes);
+                self.packets.with_label_values(&labels).inc_by(packets);
+                self.errors.with_label_values(&labels).inc_by(errors);
+                self.drops.with_label_values(&labels).inc_by(drops);
+            }
+        }
+        self.bytes
+            .collect()
+            .into_iter()
+            .chain(self.packets.collect())
+            .chain(self.errors.collect())
+            .chain(self.drops.collect())
+            .collect()
+    }
+}
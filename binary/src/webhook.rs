@@ -0,0 +1,213 @@
+use std::sync::Arc;
+
+use actix_web::http::header::HeaderMap;
+use actix_web::web::{Bytes, Data};
+use actix_web::{HttpRequest, HttpResponse};
+use hmac::{Hmac, Mac};
+use log::{debug, info, warn};
+use serde::Deserialize;
+use sha2::Sha512;
+
+/// header netbox puts the hex encoded HMAC-SHA512 of the body into
+const SIGNATURE_HEADER: &str = "X-Hook-Signature";
+
+/// netbox models the topology is built from, events of other models are ignored
+const TOPOLOGY_MODELS: &[&str] = &[
+    "region",
+    "sitegroup",
+    "site",
+    "location",
+    "rack",
+    "manufacturer",
+    "devicetype",
+    "devicerole",
+    "device",
+    "interface",
+    "frontport",
+    "rearport",
+    "powerport",
+    "poweroutlet",
+    "powerpanel",
+    "powerfeed",
+    "cable",
+    "wirelesslink",
+    "ipaddress",
+    "prefix",
+    "vrf",
+    "vlan",
+    "provider",
+    "circuit",
+    "circuittermination",
+    "tenant",
+    "tenantgroup",
+    "virtualchassis",
+    "module",
+    "modulebay",
+    "moduletype",
+];
+
+/// receives netbox webhooks and triggers a topology refresh, only signed webhooks are accepted
+#[derive(Clone)]
+pub struct NetboxWebhook {
+    secret: String,
+    refresh: Arc<dyn Fn() + Send + Sync>,
+}
+
+#[derive(Deserialize, Debug)]
+struct WebhookEvent {
+    event: String,
+    model: String,
+    username: Option<String>,
+    #[serde(default)]
+    data: WebhookData,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct WebhookData {
+    id: Option<u32>,
+}
+
+impl NetboxWebhook {
+    pub fn new<F: Fn() + Send + Sync + 'static>(secret: String, refresh: F) -> Self {
+        Self {
+            secret,
+            refresh: Arc::new(refresh),
+        }
+    }
+
+    fn signature_valid(&self, headers: &HeaderMap, body: &[u8]) -> bool {
+        let Some(signature) = headers
+            .get(SIGNATURE_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| hex::decode(value.trim()).ok())
+        else {
+            return false;
+        };
+        let Ok(mut mac) = Hmac::<Sha512>::new_from_slice(self.secret.as_bytes()) else {
+            return false;
+        };
+        mac.update(body);
+        mac.verify_slice(&signature).is_ok()
+    }
+}
+
+/// trigger a full topology refresh for events of topology models
+///
+/// The changed object is only logged, the topology is always fetched completely. Events arriving
+/// during a running refresh are merged into a single further refresh.
+pub async fn netbox_webhook(
+    webhook: Data<NetboxWebhook>,
+    request: HttpRequest,
+    body: Bytes,
+) -> HttpResponse {
+    if !webhook.signature_valid(request.headers(), &body) {
+        warn!("Rejected netbox webhook with invalid signature");
+        return HttpResponse::Unauthorized().finish();
+    }
+    let event: WebhookEvent = match serde_json::from_slice(&body) {
+        Ok(event) => event,
+        Err(error) => {
+            warn!("Cannot parse netbox webhook: {error}");
+            return HttpResponse::BadRequest().body(error.to_string());
+        }
+    };
+    if !TOPOLOGY_MODELS.contains(&event.model.as_str()) {
+        debug!("Ignored netbox webhook for {}", event.model);
+        return HttpResponse::NoContent().finish();
+    }
+    info!(
+        "Netbox {} {} {} by {}, refreshing topology",
+        event.model,
+        event.data.id.map(|id| id.to_string()).unwrap_or_default(),
+        event.event,
+        event.username.as_deref().unwrap_or("unknown"),
+    );
+    (webhook.refresh)();
+    HttpResponse::Accepted().finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use actix_web::http::StatusCode;
+    use actix_web::web::{resource, Data};
+    use actix_web::{test, App};
+    use hmac::{Hmac, Mac};
+    use sha2::Sha512;
+
+    use crate::webhook::{netbox_webhook, NetboxWebhook};
+
+    const INTERFACE_UPDATED: &str = r#"{
+        "event": "updated",
+        "timestamp": "2023-01-12 09:31:07.423421+00:00",
+        "model": "interface",
+        "username": "admin",
+        "request_id": "a0ff2b3e-0a1a-4d3b-a4a3-2f5a06a0c0a7",
+        "data": {"id": 42, "name": "ether1", "device": {"id": 10, "name": "sw01"}},
+        "snapshots": {"prechange": {"name": "ether2"}, "postchange": {"name": "ether1"}}
+    }"#;
+    const TAG_CREATED: &str = r#"{
+        "event": "created",
+        "timestamp": "2023-01-12 09:32:11.102934+00:00",
+        "model": "tag",
+        "username": "admin",
+        "request_id": "4c8e5e58-3b07-4bd5-9d0a-0d3e1b6f3a41",
+        "data": {"id": 3, "name": "uplink"},
+        "snapshots": {"prechange": null, "postchange": {"name": "uplink"}}
+    }"#;
+
+    fn sign(secret: &str, body: &str) -> String {
+        let mut mac = Hmac::<Sha512>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    #[actix_web::test]
+    async fn test_post_recorded_webhooks() {
+        let refreshes = Arc::new(AtomicUsize::new(0));
+        let counter = refreshes.clone();
+        let webhook = NetboxWebhook::new("secret".to_string(), move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(webhook))
+                .service(resource("/webhook/netbox").to(netbox_webhook)),
+        )
+        .await;
+
+        let cases = [
+            (
+                INTERFACE_UPDATED,
+                Some(sign("secret", INTERFACE_UPDATED)),
+                StatusCode::ACCEPTED,
+            ),
+            (
+                TAG_CREATED,
+                Some(sign("secret", TAG_CREATED)),
+                StatusCode::NO_CONTENT,
+            ),
+            (
+                INTERFACE_UPDATED,
+                Some(sign("other", INTERFACE_UPDATED)),
+                StatusCode::UNAUTHORIZED,
+            ),
+            (INTERFACE_UPDATED, None, StatusCode::UNAUTHORIZED),
+            ("{}", Some(sign("secret", "{}")), StatusCode::BAD_REQUEST),
+        ];
+        for (body, signature, expected) in cases {
+            let mut request = test::TestRequest::post()
+                .uri("/webhook/netbox")
+                .insert_header(("Content-Type", "application/json"))
+                .set_payload(body);
+            if let Some(signature) = signature {
+                request = request.insert_header(("X-Hook-Signature", signature));
+            }
+            let response = test::call_service(&app, request.to_request()).await;
+            assert_eq!(expected, response.status());
+        }
+        assert_eq!(1, refreshes.load(Ordering::SeqCst));
+    }
+}
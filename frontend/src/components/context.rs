@@ -31,7 +31,7 @@ use crate::{
 pub struct DeviceDetails {
     name: String,
     model_name: Option<String>,
-    has_routeros: bool,
+    can_ping: bool,
 }
 
 impl DeviceDetails {
@@ -39,8 +39,8 @@ impl DeviceDetails {
         &self.name
     }
 
-    pub fn has_routeros(&self) -> bool {
-        self.has_routeros
+    pub fn can_ping(&self) -> bool {
+        self.can_ping
     }
     pub fn model_name(&self) -> Option<&str> {
         self.model_name.as_ref().map(String::as_str)
@@ -251,18 +251,20 @@ impl ApiContext {
                     |GetDeviceDetailsDevice {
                          id: _,
                          name,
+                         can_ping,
                          location: _,
                          device_type,
                      }| {
-                        let (has_routeros, model_name) = device_type
-                            .map(|GetDeviceDetailsDeviceDeviceType { has_routeros, name }| {
-                                (has_routeros, Some(name))
-                            })
-                            .unwrap_or((false, None));
+                        let model_name = device_type.map(
+                            |GetDeviceDetailsDeviceDeviceType {
+                                 has_routeros: _,
+                                 name,
+                             }| name,
+                        );
                         Ok::<DeviceDetails, FrontendError>(DeviceDetails {
                             name,
                             model_name,
-                            has_routeros,
+                            can_ping,
                         })
                     },
                 )
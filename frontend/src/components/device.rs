@@ -129,9 +129,9 @@ impl Component for DeviceComponent {
                       scope.send_message(DeviceUpdateMessage::QueryError);
                         return;
                     };
-                    let has_routeros = device.has_routeros();
+                    let can_ping = device.can_ping();
                     scope.send_message(DeviceUpdateMessage::QueryResult(device));
-                    if !has_routeros {
+                    if !can_ping {
                         return;
                     }
                     match query_with_scope::<PingDevice, _>(